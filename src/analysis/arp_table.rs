@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use crate::packet::PacketInfo;
+
+/// An IP → MAC binding learned from observed ARP traffic.
+#[derive(Debug, Clone)]
+pub struct ArpEntry {
+    pub ip: Ipv4Addr,
+    pub mac: [u8; 6],
+    pub first_seen: Duration,
+    pub last_seen: Duration,
+    /// Number of ARP messages that announced this binding
+    pub packets: usize,
+    /// Number of gratuitous announcements among them
+    pub gratuitous: usize,
+    /// MAC addresses previously bound to this IP. Non-empty means a
+    /// duplicate address, a failover, or possibly ARP spoofing.
+    pub previous_macs: Vec<[u8; 6]>,
+}
+
+impl ArpEntry {
+    pub fn has_conflict(&self) -> bool {
+        !self.previous_macs.is_empty()
+    }
+}
+
+/// Live IP → MAC table built from the sender fields of ARP requests and replies.
+#[derive(Debug, Default)]
+pub struct ArpTable {
+    entries: HashMap<Ipv4Addr, ArpEntry>,
+}
+
+impl ArpTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Learns the sender binding from an ARP packet; other packets are ignored.
+    /// Probes carry no sender IP and therefore teach us nothing.
+    pub fn observe(&mut self, info: &PacketInfo) {
+        let Some(arp) = &info.arp else {
+            return;
+        };
+
+        if arp.is_probe() || arp.sender_ip.is_unspecified() {
+            return;
+        }
+
+        let entry = self.entries.entry(arp.sender_ip).or_insert_with(|| ArpEntry {
+            ip: arp.sender_ip,
+            mac: arp.sender_mac,
+            first_seen: info.timestamp,
+            last_seen: info.timestamp,
+            packets: 0,
+            gratuitous: 0,
+            previous_macs: Vec::new(),
+        });
+
+        if entry.mac != arp.sender_mac {
+            if !entry.previous_macs.contains(&entry.mac) {
+                entry.previous_macs.push(entry.mac);
+            }
+            entry.mac = arp.sender_mac;
+        }
+
+        entry.last_seen = info.timestamp;
+        entry.packets += 1;
+        if arp.is_gratuitous() {
+            entry.gratuitous += 1;
+        }
+    }
+
+    /// Returns all entries ordered by IP address.
+    pub fn entries(&self) -> Vec<&ArpEntry> {
+        let mut entries: Vec<_> = self.entries.values().collect();
+        entries.sort_by_key(|entry| entry.ip);
+        entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
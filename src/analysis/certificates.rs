@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::packet::{Certificate, PacketInfo, TlsInfo};
+
+/// Certificates expiring within this many seconds are flagged
+pub const EXPIRY_WARNING_SECS: i64 = 30 * 86_400;
+
+/// A certificate seen in a server's chain.
+#[derive(Debug, Clone)]
+pub struct ObservedCertificate {
+    /// Endpoint that sent the chain, as `ip:port`
+    pub server: String,
+    pub certificate: Certificate,
+    /// Position in the chain; 0 is the leaf
+    pub chain_position: usize,
+    pub first_seen: Duration,
+    pub last_seen: Duration,
+    pub times_seen: usize,
+}
+
+impl ObservedCertificate {
+    /// Problems worth attention at `now` (seconds since the Unix epoch).
+    pub fn warnings(&self, now: i64) -> Vec<&'static str> {
+        let cert = &self.certificate;
+        let mut warnings = Vec::new();
+        if cert.not_after < now {
+            warnings.push("expired");
+        } else if cert.not_after - now < EXPIRY_WARNING_SECS {
+            warnings.push("expires soon");
+        }
+        if cert.not_before > now {
+            warnings.push("not yet valid");
+        }
+        if cert.self_signed {
+            warnings.push("self-signed");
+        }
+        warnings
+    }
+
+    /// File name for the DER export, e.g. `10.0.0.1_443_0_ab12cd34ef56.der`.
+    pub fn der_file_name(&self) -> String {
+        let server = self.server.replace([':', '[', ']'], "_");
+        format!("{}_{}_{}.der", server, self.chain_position, &self.certificate.fingerprint[..12])
+    }
+}
+
+/// Every certificate observed per server endpoint, built from TLS Certificate messages.
+#[derive(Debug, Default)]
+pub struct CertificateInventory {
+    /// Keyed by server endpoint and certificate fingerprint
+    certificates: HashMap<(String, String), ObservedCertificate>,
+}
+
+impl CertificateInventory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the certificate chains carried by a packet; other packets are ignored.
+    pub fn observe(&mut self, info: &PacketInfo) {
+        for message in &info.tls {
+            let TlsInfo::Certificate(chain) = message else {
+                continue;
+            };
+            let (Some(ip), Some(port)) = (&info.src_ip, info.src_port) else {
+                continue;
+            };
+            let server = if ip.contains(':') { format!("[{}]:{}", ip, port) } else { format!("{}:{}", ip, port) };
+
+            for (position, certificate) in chain.iter().enumerate() {
+                let key = (server.clone(), certificate.fingerprint.clone());
+                let observed = self.certificates.entry(key).or_insert_with(|| ObservedCertificate {
+                    server: server.clone(),
+                    certificate: certificate.clone(),
+                    chain_position: position,
+                    first_seen: info.timestamp,
+                    last_seen: info.timestamp,
+                    times_seen: 0,
+                });
+                observed.last_seen = info.timestamp;
+                observed.times_seen += 1;
+            }
+        }
+    }
+
+    /// Certificates sorted by server, then chain position.
+    pub fn certificates(&self) -> Vec<&ObservedCertificate> {
+        let mut certificates: Vec<_> = self.certificates.values().collect();
+        certificates.sort_by(|a, b| {
+            a.server
+                .cmp(&b.server)
+                .then(a.chain_position.cmp(&b.chain_position))
+                .then(a.certificate.fingerprint.cmp(&b.certificate.fingerprint))
+        });
+        certificates
+    }
+
+    /// Writes every certificate's DER encoding into `dir`, returning how many were written.
+    pub fn export_der(&self, dir: &Path) -> io::Result<usize> {
+        std::fs::create_dir_all(dir)?;
+        for observed in self.certificates.values() {
+            std::fs::write(dir.join(observed.der_file_name()), &observed.certificate.der)?;
+        }
+        Ok(self.certificates.len())
+    }
+
+    pub fn len(&self) -> usize {
+        self.certificates.len()
+    }
+}
+
+/// Current time in seconds since the Unix epoch, for expiry checks.
+pub fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_secs() as i64)
+        .unwrap_or_default()
+}
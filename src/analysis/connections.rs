@@ -0,0 +1,227 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::time::Duration;
+
+use crate::packet::PacketInfo;
+
+/// Upper bound on connections that are still open
+const MAX_ACTIVE: usize = 10_000;
+
+/// Upper bound on finished connections kept for display
+const MAX_FINISHED: usize = 10_000;
+
+/// An (address, port) pair.
+pub type Endpoint = (String, u16);
+
+/// (IP protocol, lower endpoint, upper endpoint)
+type ConnectionKey = (u8, Endpoint, Endpoint);
+
+/// TCP connection state as seen from the wire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TcpState {
+    /// SYN seen, waiting for SYN-ACK
+    SynSent,
+    /// SYN-ACK seen, waiting for the final handshake ACK
+    SynReceived,
+    Established,
+    /// One side has sent FIN
+    Closing,
+    /// Both sides have sent FIN
+    Closed,
+    Reset,
+}
+
+impl fmt::Display for TcpState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TcpState::SynSent => "SYN_SENT",
+            TcpState::SynReceived => "SYN_RECEIVED",
+            TcpState::Established => "ESTABLISHED",
+            TcpState::Closing => "CLOSING",
+            TcpState::Closed => "CLOSED",
+            TcpState::Reset => "RESET",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Packet and byte counters for one direction of a connection.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DirectionStats {
+    pub packets: usize,
+    /// Frame bytes on the wire
+    pub bytes: u64,
+}
+
+/// A TCP connection or UDP conversation between two endpoints.
+#[derive(Debug, Clone)]
+pub struct Connection {
+    pub protocol: u8,
+    /// The side that sent the SYN, or the first packet seen
+    pub client: Endpoint,
+    pub server: Endpoint,
+    pub start: Duration,
+    pub end: Duration,
+    pub client_to_server: DirectionStats,
+    pub server_to_client: DirectionStats,
+    /// `None` for UDP
+    pub state: Option<TcpState>,
+    client_fin: bool,
+    server_fin: bool,
+}
+
+impl Connection {
+    pub fn protocol_name(&self) -> &'static str {
+        match self.protocol {
+            6 => "TCP",
+            _ => "UDP",
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.end.saturating_sub(self.start)
+    }
+
+    fn is_finished(&self) -> bool {
+        matches!(self.state, Some(TcpState::Closed | TcpState::Reset))
+    }
+}
+
+/// Tracks TCP connections and UDP conversations keyed by 5-tuple.
+#[derive(Debug, Default)]
+pub struct ConnectionTracker {
+    active: HashMap<ConnectionKey, Connection>,
+    /// Connections replaced by a new one on the same 5-tuple or evicted, oldest first
+    finished: VecDeque<Connection>,
+}
+
+impl ConnectionTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accounts a TCP or UDP packet to its connection; other packets are ignored.
+    pub fn observe(&mut self, info: &PacketInfo) {
+        let protocol = match (&info.tcp, info.src_port) {
+            (Some(_), _) => 6,
+            (None, Some(_)) => 17,
+            (None, None) => return,
+        };
+        let (Some(src_ip), Some(src_port), Some(dst_ip), Some(dst_port)) =
+            (info.src_ip.clone(), info.src_port, info.dst_ip.clone(), info.dst_port)
+        else {
+            return;
+        };
+        let src = (src_ip, src_port);
+        let dst = (dst_ip, dst_port);
+        let key = if src <= dst {
+            (protocol, src.clone(), dst.clone())
+        } else {
+            (protocol, dst.clone(), src.clone())
+        };
+        let flags = info.tcp.as_ref().map(|tcp| tcp.flags);
+
+        // A fresh SYN on a finished connection starts a new one on the same 5-tuple
+        let is_syn = flags.is_some_and(|f| f.syn && !f.ack);
+        if is_syn && self.active.get(&key).is_some_and(Connection::is_finished) {
+            self.retire(&key);
+        }
+
+        if !self.active.contains_key(&key) {
+            if self.active.len() >= MAX_ACTIVE {
+                self.evict_oldest();
+            }
+            // The SYN-ACK comes from the server; otherwise assume the sender is the client
+            let (client, server) = if flags.is_some_and(|f| f.syn && f.ack) {
+                (dst.clone(), src.clone())
+            } else {
+                (src.clone(), dst.clone())
+            };
+            self.active.insert(
+                key.clone(),
+                Connection {
+                    protocol,
+                    client,
+                    server,
+                    start: info.timestamp,
+                    end: info.timestamp,
+                    client_to_server: DirectionStats::default(),
+                    server_to_client: DirectionStats::default(),
+                    state: None,
+                    client_fin: false,
+                    server_fin: false,
+                },
+            );
+        }
+
+        let Some(connection) = self.active.get_mut(&key) else {
+            return;
+        };
+        let from_client = src == connection.client;
+
+        connection.end = info.timestamp;
+        let stats = if from_client {
+            &mut connection.client_to_server
+        } else {
+            &mut connection.server_to_client
+        };
+        stats.packets += 1;
+        stats.bytes += info.orig_len as u64;
+
+        let Some(flags) = flags else {
+            return;
+        };
+
+        if flags.fin {
+            if from_client {
+                connection.client_fin = true;
+            } else {
+                connection.server_fin = true;
+            }
+        }
+
+        connection.state = Some(match connection.state {
+            _ if flags.rst => TcpState::Reset,
+            Some(TcpState::Reset) => TcpState::Reset,
+            _ if connection.client_fin && connection.server_fin => TcpState::Closed,
+            _ if flags.fin => TcpState::Closing,
+            Some(TcpState::Closing) => TcpState::Closing,
+            // Retransmitted handshake packets do not move an open connection backwards
+            Some(TcpState::Established) => TcpState::Established,
+            _ if flags.syn && !flags.ack => TcpState::SynSent,
+            _ if flags.syn => TcpState::SynReceived,
+            Some(TcpState::SynSent) if !flags.ack => TcpState::SynSent,
+            // Either the handshake ACK or traffic on a connection that predates the capture
+            _ => TcpState::Established,
+        });
+    }
+
+    /// All connections, finished ones first.
+    pub fn connections(&self) -> Vec<&Connection> {
+        self.finished.iter().chain(self.active.values()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.finished.len() + self.active.len()
+    }
+
+    fn retire(&mut self, key: &ConnectionKey) {
+        if let Some(connection) = self.active.remove(key) {
+            if self.finished.len() >= MAX_FINISHED {
+                self.finished.pop_front();
+            }
+            self.finished.push_back(connection);
+        }
+    }
+
+    fn evict_oldest(&mut self) {
+        let oldest = self
+            .active
+            .iter()
+            .min_by_key(|(_, connection)| connection.end)
+            .map(|(key, _)| key.clone());
+        if let Some(key) = oldest {
+            self.retire(&key);
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use crate::packet::{DhcpMessageType, PacketInfo};
+
+/// Upper bound on each of the lease, server and client detail tables,
+/// so a starvation attack with random MACs cannot grow them without limit
+const MAX_ENTRIES: usize = 4096;
+
+/// An address assignment confirmed by a DHCP ACK.
+#[derive(Debug, Clone)]
+pub struct DhcpLease {
+    pub mac: [u8; 6],
+    pub ip: Ipv4Addr,
+    /// Hostname the client announced in its Discover or Request
+    pub hostname: Option<String>,
+    pub vendor_class: Option<String>,
+    pub server: Option<Ipv4Addr>,
+    /// Lease time in seconds
+    pub lease_time: Option<u32>,
+    pub first_seen: Duration,
+    /// Time of the most recent ACK
+    pub last_ack: Duration,
+    pub released: bool,
+}
+
+/// A server that answered DHCP clients.
+#[derive(Debug, Clone)]
+pub struct DhcpServer {
+    pub server_id: Ipv4Addr,
+    pub mac: Option<[u8; 6]>,
+    pub offers: usize,
+    pub acks: usize,
+    pub naks: usize,
+    pub first_seen: Duration,
+    pub last_seen: Duration,
+}
+
+impl DhcpServer {
+    pub fn responses(&self) -> usize {
+        self.offers + self.acks + self.naks
+    }
+}
+
+/// MAC → IP → hostname table built from DORA exchanges, plus the set of
+/// servers seen answering clients.
+#[derive(Debug, Default)]
+pub struct DhcpLeaseTable {
+    leases: HashMap<[u8; 6], DhcpLease>,
+    servers: HashMap<Ipv4Addr, DhcpServer>,
+    /// Hostname and vendor class announced by each client, applied when its ACK arrives
+    client_details: HashMap<[u8; 6], (Option<String>, Option<String>)>,
+}
+
+impl DhcpLeaseTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Learns from a DHCP message; other packets are ignored.
+    pub fn observe(&mut self, info: &PacketInfo) {
+        let Some(dhcp) = &info.dhcp else {
+            return;
+        };
+        let Some(message_type) = dhcp.message_type else {
+            return;
+        };
+
+        if !dhcp.is_reply() {
+            let Some(mac) = dhcp.client_mac else {
+                return;
+            };
+            if !self.client_details.contains_key(&mac) && self.client_details.len() >= MAX_ENTRIES {
+                self.client_details.clear();
+            }
+            let details = self.client_details.entry(mac).or_default();
+            if dhcp.hostname.is_some() {
+                details.0 = dhcp.hostname.clone();
+            }
+            if dhcp.vendor_class.is_some() {
+                details.1 = dhcp.vendor_class.clone();
+            }
+            if message_type == DhcpMessageType::Release
+                && let Some(lease) = self.leases.get_mut(&mac)
+            {
+                lease.released = true;
+            }
+            return;
+        }
+
+        // Replies identify their server by option 54, falling back to the source address
+        let server_id = dhcp
+            .server_id
+            .or_else(|| info.src_ip.as_deref().and_then(|ip| ip.parse().ok()));
+        if let Some(server_id) = server_id {
+            if !self.servers.contains_key(&server_id) && self.servers.len() >= MAX_ENTRIES {
+                self.servers.clear();
+            }
+            let server = self.servers.entry(server_id).or_insert_with(|| DhcpServer {
+                server_id,
+                mac: info.src_mac,
+                offers: 0,
+                acks: 0,
+                naks: 0,
+                first_seen: info.timestamp,
+                last_seen: info.timestamp,
+            });
+            server.last_seen = info.timestamp;
+            match message_type {
+                DhcpMessageType::Offer => server.offers += 1,
+                DhcpMessageType::Ack => server.acks += 1,
+                DhcpMessageType::Nak => server.naks += 1,
+                _ => {}
+            }
+        }
+
+        // ACKs to DHCPINFORM carry no address assignment
+        let (DhcpMessageType::Ack, Some(mac)) = (message_type, dhcp.client_mac) else {
+            return;
+        };
+        if dhcp.yiaddr.is_unspecified() {
+            return;
+        }
+
+        let (hostname, vendor_class) = self.client_details.get(&mac).cloned().unwrap_or_default();
+        if !self.leases.contains_key(&mac) && self.leases.len() >= MAX_ENTRIES {
+            self.leases.clear();
+        }
+        let lease = self.leases.entry(mac).or_insert_with(|| DhcpLease {
+            mac,
+            ip: dhcp.yiaddr,
+            hostname: None,
+            vendor_class: None,
+            server: None,
+            lease_time: None,
+            first_seen: info.timestamp,
+            last_ack: info.timestamp,
+            released: false,
+        });
+        lease.ip = dhcp.yiaddr;
+        lease.hostname = hostname.or_else(|| dhcp.hostname.clone()).or(lease.hostname.take());
+        lease.vendor_class = vendor_class.or(lease.vendor_class.take());
+        lease.server = server_id;
+        lease.lease_time = dhcp.lease_time;
+        lease.last_ack = info.timestamp;
+        lease.released = false;
+    }
+
+    /// Leases sorted by IP address.
+    pub fn leases(&self) -> Vec<&DhcpLease> {
+        let mut leases: Vec<_> = self.leases.values().collect();
+        leases.sort_by_key(|lease| lease.ip);
+        leases
+    }
+
+    /// Servers, busiest first.
+    pub fn servers(&self) -> Vec<&DhcpServer> {
+        let mut servers: Vec<_> = self.servers.values().collect();
+        servers.sort_by(|a, b| b.responses().cmp(&a.responses()).then(a.server_id.cmp(&b.server_id)));
+        servers
+    }
+
+    /// On a segment with a single legitimate server, any other server answering
+    /// clients is suspicious. The busiest server is assumed to be the legitimate one.
+    pub fn is_suspected_rogue(&self, server_id: Ipv4Addr) -> bool {
+        self.servers().first().is_some_and(|primary| primary.server_id != server_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.leases.len()
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::packet::{Dhcpv6MessageType, IaAddress, IaPrefix, PacketInfo};
+
+/// Upper bound on tracked clients
+const MAX_ENTRIES: usize = 4096;
+
+/// Addresses and prefixes a DHCPv6 server assigned to one client DUID.
+#[derive(Debug, Clone)]
+pub struct Dhcpv6Client {
+    pub duid: Vec<u8>,
+    /// Source address of the client's own messages, usually link-local
+    pub client_ip: Option<String>,
+    pub server_duid: Option<Vec<u8>>,
+    pub addresses: Vec<IaAddress>,
+    pub prefixes: Vec<IaPrefix>,
+    pub first_seen: Duration,
+    /// Time of the most recent Reply carrying an assignment
+    pub last_reply: Option<Duration>,
+    pub released: bool,
+}
+
+/// Client DUID → assigned addresses and delegated prefixes, built from DHCPv6 Replies.
+#[derive(Debug, Default)]
+pub struct Dhcpv6LeaseTable {
+    clients: HashMap<Vec<u8>, Dhcpv6Client>,
+}
+
+impl Dhcpv6LeaseTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Learns from a DHCPv6 message; other packets are ignored.
+    pub fn observe(&mut self, info: &PacketInfo) {
+        let Some(dhcpv6) = &info.dhcpv6 else {
+            return;
+        };
+        let Some(duid) = &dhcpv6.client_duid else {
+            return;
+        };
+
+        if !self.clients.contains_key(duid) && self.clients.len() >= MAX_ENTRIES {
+            self.clients.clear();
+        }
+        let client = self.clients.entry(duid.clone()).or_insert_with(|| Dhcpv6Client {
+            duid: duid.clone(),
+            client_ip: None,
+            server_duid: None,
+            addresses: Vec::new(),
+            prefixes: Vec::new(),
+            first_seen: info.timestamp,
+            last_reply: None,
+            released: false,
+        });
+
+        match dhcpv6.message_type {
+            Dhcpv6MessageType::Reply => {
+                // Replies to Information-request or Release carry no assignment
+                if dhcpv6.addresses().next().is_none() && dhcpv6.prefixes().next().is_none() {
+                    return;
+                }
+                // A valid lifetime of zero withdraws the address or prefix, e.g. in Replies to Release or Decline
+                let addresses: Vec<_> = dhcpv6.addresses().filter(|a| a.valid_lifetime > 0).copied().collect();
+                let prefixes: Vec<_> = dhcpv6.prefixes().filter(|p| p.valid_lifetime > 0).copied().collect();
+                if addresses.is_empty() && prefixes.is_empty() {
+                    client.released = true;
+                    return;
+                }
+                client.addresses = addresses;
+                client.prefixes = prefixes;
+                client.server_duid = dhcpv6.server_duid.clone();
+                client.last_reply = Some(info.timestamp);
+                client.released = false;
+            }
+            Dhcpv6MessageType::Release => client.released = true,
+            Dhcpv6MessageType::Advertise | Dhcpv6MessageType::Reconfigure => {}
+            // Relayed client messages are sourced from the relay, not the client
+            _ if dhcpv6.relay_hops == 0 => client.client_ip = info.src_ip.clone(),
+            _ => {}
+        }
+    }
+
+    /// Clients with at least one assignment first, then by DUID.
+    pub fn clients(&self) -> Vec<&Dhcpv6Client> {
+        let mut clients: Vec<_> = self.clients.values().collect();
+        clients.sort_by(|a, b| b.last_reply.is_some().cmp(&a.last_reply.is_some()).then(a.duid.cmp(&b.duid)));
+        clients
+    }
+
+    pub fn len(&self) -> usize {
+        self.clients.len()
+    }
+}
@@ -0,0 +1,267 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use crate::packet::{DnsMessage, DnsProtocol, PacketInfo};
+
+/// Queries unanswered for this long are counted as timed out
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Upper bound on outstanding queries
+const MAX_PENDING: usize = 8192;
+
+/// Number of most recent latencies kept for percentiles
+const MAX_LATENCY_SAMPLES: usize = 10_000;
+
+/// Upper bound on each of the domain, client and server tables, so a
+/// random-subdomain flood cannot grow them without limit
+const MAX_ENTRIES: usize = 4096;
+
+/// (client IP, client port, server IP, server port, transaction ID)
+type TransactionKey = (String, u16, String, u16, u16);
+
+#[derive(Debug, Clone)]
+struct PendingQuery {
+    sent: Duration,
+}
+
+/// Per-resolver counters.
+#[derive(Debug, Clone, Default)]
+pub struct ServerStats {
+    pub address: String,
+    pub queries: usize,
+    pub responses: usize,
+    pub timeouts: usize,
+    /// SERVFAIL and REFUSED responses
+    pub failures: usize,
+    pub total_latency: Duration,
+    pub max_latency: Duration,
+}
+
+impl ServerStats {
+    pub fn mean_latency(&self) -> Option<Duration> {
+        (self.responses > 0).then(|| self.total_latency / self.responses as u32)
+    }
+}
+
+/// Latency distribution over the matched transactions.
+#[derive(Debug, Clone, Copy)]
+pub struct LatencySummary {
+    pub mean: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+    pub max: Duration,
+}
+
+/// Pairs DNS queries with their responses and aggregates resolution statistics.
+/// mDNS is left out, as its responses are multicast and not tied to a query.
+#[derive(Debug, Default)]
+pub struct DnsStats {
+    pending: HashMap<TransactionKey, PendingQuery>,
+    pub queries: usize,
+    pub responses: usize,
+    /// Responses that matched an outstanding query
+    pub matched: usize,
+    /// Responses without a matching query, e.g. the query predates the capture
+    pub unmatched_responses: usize,
+    pub timeouts: usize,
+    rcodes: HashMap<u16, usize>,
+    domains: HashMap<String, usize>,
+    clients: HashMap<String, usize>,
+    servers: HashMap<String, ServerStats>,
+    latencies: VecDeque<Duration>,
+    total_latency: Duration,
+    max_latency: Duration,
+}
+
+impl DnsStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts the DNS queries and responses a packet carries; other packets are ignored.
+    pub fn observe(&mut self, info: &PacketInfo) {
+        if info.dns.is_empty() {
+            return;
+        }
+        let (Some(src_ip), Some(src_port), Some(dst_ip), Some(dst_port)) =
+            (&info.src_ip, info.src_port, &info.dst_ip, info.dst_port)
+        else {
+            return;
+        };
+
+        self.expire(info.timestamp);
+
+        for dns in &info.dns {
+            if dns.protocol != DnsProtocol::Mdns {
+                self.observe_message(dns, (src_ip, src_port), (dst_ip, dst_port), info.timestamp);
+            }
+        }
+    }
+
+    fn observe_message(
+        &mut self,
+        dns: &DnsMessage,
+        (src_ip, src_port): (&str, u16),
+        (dst_ip, dst_port): (&str, u16),
+        timestamp: Duration,
+    ) {
+        if !dns.is_response {
+            self.queries += 1;
+            for question in &dns.questions {
+                let name = question.name.to_lowercase();
+                if !self.domains.contains_key(&name) {
+                    make_room(&mut self.domains, |&count| count);
+                }
+                *self.domains.entry(name).or_insert(0) += 1;
+            }
+            if !self.clients.contains_key(src_ip) {
+                make_room(&mut self.clients, |&count| count);
+            }
+            *self.clients.entry(src_ip.to_string()).or_insert(0) += 1;
+            self.server_mut(dst_ip).queries += 1;
+
+            if self.pending.len() < MAX_PENDING {
+                // A retransmitted query keeps the time of the first attempt
+                self.pending
+                    .entry((src_ip.to_string(), src_port, dst_ip.to_string(), dst_port, dns.id))
+                    .or_insert(PendingQuery { sent: timestamp });
+            }
+            return;
+        }
+
+        self.responses += 1;
+        *self.rcodes.entry(dns.rcode).or_insert(0) += 1;
+
+        let failed = matches!(dns.rcode, 2 | 5);
+        let key = (dst_ip.to_string(), dst_port, src_ip.to_string(), src_port, dns.id);
+        let latency = self
+            .pending
+            .remove(&key)
+            .map(|query| timestamp.saturating_sub(query.sent));
+
+        let server = self.server_mut(src_ip);
+        server.responses += 1;
+        if failed {
+            server.failures += 1;
+        }
+        if let Some(latency) = latency {
+            server.total_latency += latency;
+            server.max_latency = server.max_latency.max(latency);
+        }
+
+        let Some(latency) = latency else {
+            self.unmatched_responses += 1;
+            return;
+        };
+
+        self.matched += 1;
+        self.total_latency += latency;
+        self.max_latency = self.max_latency.max(latency);
+        if self.latencies.len() >= MAX_LATENCY_SAMPLES {
+            self.latencies.pop_front();
+        }
+        self.latencies.push_back(latency);
+    }
+
+    /// Queries still waiting for a response that have not yet timed out.
+    pub fn outstanding(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn rcode_count(&self, rcode: u16) -> usize {
+        self.rcodes.get(&rcode).copied().unwrap_or(0)
+    }
+
+    /// Share of responses carrying `rcode`, in percent.
+    pub fn rcode_rate(&self, rcode: u16) -> f64 {
+        if self.responses == 0 {
+            return 0.0;
+        }
+        self.rcode_count(rcode) as f64 * 100.0 / self.responses as f64
+    }
+
+    /// Response codes seen, most frequent first.
+    pub fn rcodes(&self) -> Vec<(u16, usize)> {
+        let mut rcodes: Vec<_> = self.rcodes.iter().map(|(&rcode, &count)| (rcode, count)).collect();
+        rcodes.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        rcodes
+    }
+
+    pub fn latency(&self) -> Option<LatencySummary> {
+        if self.latencies.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<Duration> = self.latencies.iter().copied().collect();
+        sorted.sort();
+        let percentile = |p: usize| sorted[(sorted.len() - 1) * p / 100];
+
+        Some(LatencySummary {
+            mean: self.total_latency / self.matched as u32,
+            median: percentile(50),
+            p95: percentile(95),
+            max: self.max_latency,
+        })
+    }
+
+    pub fn top_domains(&self, limit: usize) -> Vec<(&str, usize)> {
+        top(&self.domains, limit)
+    }
+
+    pub fn top_clients(&self, limit: usize) -> Vec<(&str, usize)> {
+        top(&self.clients, limit)
+    }
+
+    /// Resolvers, busiest first.
+    pub fn servers(&self) -> Vec<&ServerStats> {
+        let mut servers: Vec<_> = self.servers.values().collect();
+        servers.sort_by(|a, b| b.queries.cmp(&a.queries).then(a.address.cmp(&b.address)));
+        servers
+    }
+
+    fn server_mut(&mut self, address: &str) -> &mut ServerStats {
+        if !self.servers.contains_key(address) {
+            make_room(&mut self.servers, |server| server.queries + server.responses);
+        }
+        self.servers
+            .entry(address.to_string())
+            .or_insert_with(|| ServerStats {
+                address: address.to_string(),
+                ..Default::default()
+            })
+    }
+
+    /// Counts and forgets queries that have gone unanswered past the timeout.
+    fn expire(&mut self, now: Duration) {
+        let mut timed_out = Vec::new();
+        self.pending.retain(|(_, _, server, _, _), query| {
+            let keep = now.saturating_sub(query.sent) <= QUERY_TIMEOUT;
+            if !keep {
+                timed_out.push(server.clone());
+            }
+            keep
+        });
+
+        self.timeouts += timed_out.len();
+        for server in timed_out {
+            self.server_mut(&server).timeouts += 1;
+        }
+    }
+}
+
+/// Drops the less active half of a full table. One-off names from a flood go
+/// first, so the busiest entries and their counts survive.
+fn make_room<V>(table: &mut HashMap<String, V>, activity: impl Fn(&V) -> usize) {
+    if table.len() < MAX_ENTRIES {
+        return;
+    }
+    let mut counts: Vec<usize> = table.values().map(&activity).collect();
+    let (_, &mut median, _) = counts.select_nth_unstable(table.len() / 2);
+    table.retain(|_, value| activity(value) > median);
+}
+
+fn top(counts: &HashMap<String, usize>, limit: usize) -> Vec<(&str, usize)> {
+    let mut entries: Vec<_> = counts.iter().map(|(key, &count)| (key.as_str(), count)).collect();
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    entries.truncate(limit);
+    entries
+}
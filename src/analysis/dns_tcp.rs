@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+use crate::analysis::tcp_reassembly::{Direction, StreamData};
+use crate::packet::{dns_protocol_for_ports, parse_dns_message, PacketInfo, PacketType};
+
+/// Upper bound on streams holding a partial message
+const MAX_PENDING_STREAMS: usize = 1024;
+
+/// Splits reassembled TCP streams on DNS ports into length-prefixed messages
+/// (RFC 1035 section 4.2.2) and decodes them.
+#[derive(Debug, Default)]
+pub struct DnsTcpDecoder {
+    /// Bytes of an incomplete message per stream direction
+    pending: HashMap<(u64, Direction), Vec<u8>>,
+}
+
+impl DnsTcpDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds stream bytes delivered by `info`. When they complete messages, the
+    /// packet is marked as DNS and carries every message completed.
+    pub fn observe(&mut self, info: &mut PacketInfo, data: &StreamData) {
+        let (Some(src_port), Some(dst_port)) = (info.src_port, info.dst_port) else {
+            return;
+        };
+        let Some(protocol) = dns_protocol_for_ports(src_port, dst_port) else {
+            return;
+        };
+
+        let key = (data.stream_id, data.direction);
+        if !self.pending.contains_key(&key) && self.pending.len() >= MAX_PENDING_STREAMS {
+            self.pending.clear();
+        }
+        let buffer = self.pending.entry(key).or_default();
+        buffer.extend_from_slice(&data.data);
+
+        let mut messages = Vec::new();
+        while buffer.len() >= 2 {
+            let len = u16::from_be_bytes([buffer[0], buffer[1]]) as usize;
+            if buffer.len() < 2 + len {
+                break;
+            }
+            if let Ok(message) = parse_dns_message(&buffer[2..2 + len]) {
+                messages.push(message);
+            }
+            buffer.drain(..2 + len);
+        }
+
+        if buffer.is_empty() {
+            self.pending.remove(&key);
+        }
+
+        if !messages.is_empty() {
+            for message in &mut messages {
+                message.protocol = protocol;
+            }
+            info.packet_type = PacketType::DNS;
+            info.dns_queries = Some(messages.iter().flat_map(|message| message.query_names()).collect());
+            info.dns = messages;
+        }
+    }
+}
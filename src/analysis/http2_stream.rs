@@ -0,0 +1,418 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+
+use crate::analysis::tcp_reassembly::{Direction, StreamData};
+use crate::packet::{
+    looks_like_http, parse_control_frame, parse_http_head, strip_padding, HpackDecoder, Http2Frame, Http2FrameKind, PacketInfo,
+    PacketType, FLAG_END_HEADERS, FLAG_PADDED, FLAG_PRIORITY, HTTP2_PREFACE,
+};
+
+/// Upper bound on connections with HTTP/2 state
+const MAX_CONNECTIONS: usize = 4096;
+/// Frames other than DATA larger than this are skipped instead of buffered
+const MAX_FRAME_BYTES: usize = 256 * 1024;
+/// Upper bound on a header block spread across CONTINUATION frames
+const MAX_HEADER_BLOCK_BYTES: usize = 1024 * 1024;
+/// An HTTP/1.1 head before an h2c upgrade that grows past this is not one
+const MAX_UPGRADE_HEAD_BYTES: usize = 64 * 1024;
+
+const DATA: u8 = 0x0;
+const HEADERS: u8 = 0x1;
+const PUSH_PROMISE: u8 = 0x5;
+const CONTINUATION: u8 = 0x9;
+
+lazy_static! {
+    static ref H2C_PORTS: RwLock<Vec<u16>> = RwLock::new(Vec::new());
+}
+
+/// Registers ports whose TCP traffic should be decoded as cleartext HTTP/2
+/// even without a connection preface or upgrade in the capture.
+pub fn set_h2c_ports(ports: &[u16]) {
+    if let Ok(mut h2c) = H2C_PORTS.write() {
+        *h2c = ports.to_vec();
+    }
+}
+
+fn is_h2c_port(port: Option<u16>) -> bool {
+    port.is_some_and(|port| H2C_PORTS.read().is_ok_and(|h2c| h2c.contains(&port)))
+}
+
+/// What a stream direction expects next.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Sync {
+    /// HTTP/1.1 upgrade request; frames start after the connection preface
+    AwaitPreface,
+    /// HTTP/1.1 response to an upgrade request; frames start after a 101 head
+    UpgradeResponse,
+    /// Frame boundaries are known
+    #[default]
+    Frames,
+}
+
+/// A header block waiting for CONTINUATION frames.
+#[derive(Debug)]
+struct PendingBlock {
+    stream_id: u32,
+    flags: u8,
+    length: usize,
+    promised_stream_id: Option<u32>,
+    block: Vec<u8>,
+}
+
+#[derive(Debug, Default)]
+struct HalfState {
+    sync: Sync,
+    buffer: Vec<u8>,
+    /// Bytes of the current frame still to be skipped
+    skip: usize,
+    /// No frame seen yet in this direction
+    first_frame: bool,
+    /// The HPACK dynamic table is complete, i.e. this direction was seen from its start
+    hpack_synced: bool,
+    hpack: HpackDecoder,
+    pending: Option<PendingBlock>,
+}
+
+impl HalfState {
+    fn new(sync: Sync) -> Self {
+        HalfState {
+            sync,
+            first_frame: true,
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Connection {
+    halves: HashMap<Direction, HalfState>,
+    /// Set by an `Upgrade: h2c` request; both directions start in HTTP/1.1
+    upgrade: bool,
+    /// Lost frame sync, or the upgrade was refused
+    done: bool,
+    last_seen: Duration,
+}
+
+/// Splits reassembled TCP streams into HTTP/2 frames for cleartext HTTP/2: connections
+/// opened with the prior-knowledge preface, `Upgrade: h2c` connections and configured ports.
+/// Header blocks are decompressed with a per-direction HPACK decoder.
+#[derive(Debug, Default)]
+pub struct Http2StreamDecoder {
+    connections: HashMap<u64, Connection>,
+}
+
+impl Http2StreamDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds stream bytes delivered by `info`. When they complete frames,
+    /// the packet is marked as HTTP/2 and carries them.
+    pub fn observe(&mut self, info: &mut PacketInfo, data: &StreamData) {
+        if !self.connections.contains_key(&data.stream_id) {
+            let upgrade = is_h2c_upgrade(&data.data);
+            if !upgrade
+                && !data.data.starts_with(HTTP2_PREFACE)
+                && !is_h2c_port(info.src_port)
+                && !is_h2c_port(info.dst_port)
+            {
+                return;
+            }
+            if self.connections.len() >= MAX_CONNECTIONS {
+                self.evict_oldest();
+            }
+            self.connections.insert(
+                data.stream_id,
+                Connection {
+                    upgrade,
+                    ..Connection::default()
+                },
+            );
+        }
+        let Some(connection) = self.connections.get_mut(&data.stream_id) else {
+            return;
+        };
+        connection.last_seen = info.timestamp;
+        if connection.done {
+            return;
+        }
+
+        let frames = connection.feed(data.direction, &data.data);
+        if !frames.is_empty() {
+            info.packet_type = PacketType::HTTP2;
+            info.http2 = frames;
+        }
+    }
+
+    /// Forgets the least recently active connection to make room for a new one.
+    fn evict_oldest(&mut self) {
+        let oldest = self
+            .connections
+            .iter()
+            .min_by_key(|(_, connection)| connection.last_seen)
+            .map(|(&id, _)| id);
+        if let Some(id) = oldest {
+            self.connections.remove(&id);
+        }
+    }
+}
+
+/// An HTTP/1.1 request asking to upgrade to cleartext HTTP/2.
+fn is_h2c_upgrade(data: &[u8]) -> bool {
+    if !looks_like_http(data) {
+        return false;
+    }
+    let Ok(Some((message, _))) = parse_http_head(data) else {
+        return false;
+    };
+    message.is_request()
+        && message
+            .header("upgrade")
+            .is_some_and(|upgrade| upgrade.split(',').any(|token| token.trim().eq_ignore_ascii_case("h2c")))
+}
+
+impl Connection {
+    fn feed(&mut self, direction: Direction, data: &[u8]) -> Vec<Http2Frame> {
+        let mut half = self.halves.remove(&direction).unwrap_or_else(|| {
+            HalfState::new(match (self.upgrade, direction) {
+                (true, Direction::ClientToServer) => Sync::AwaitPreface,
+                (true, Direction::ServerToClient) => Sync::UpgradeResponse,
+                (false, _) => Sync::Frames,
+            })
+        });
+        let mut data = data;
+        let mut frames = Vec::new();
+
+        // Finish skipping a frame that started in an earlier segment
+        let skipped = half.skip.min(data.len());
+        half.skip -= skipped;
+        data = &data[skipped..];
+        half.buffer.extend_from_slice(data);
+
+        match half.sync {
+            Sync::AwaitPreface => match find(&half.buffer, HTTP2_PREFACE) {
+                Some(at) => {
+                    half.buffer.drain(..at);
+                    half.sync = Sync::Frames;
+                }
+                None => {
+                    // Keep just enough to match a preface split across segments
+                    let keep = half.buffer.len().saturating_sub(HTTP2_PREFACE.len() - 1);
+                    half.buffer.drain(..keep);
+                }
+            },
+            Sync::UpgradeResponse => match parse_http_head(&half.buffer) {
+                Ok(Some((message, len))) if message.status() == Some(101) => {
+                    half.buffer.drain(..len);
+                    half.sync = Sync::Frames;
+                }
+                // Upgrade refused, the connection stays HTTP/1.1
+                Ok(Some(_)) | Err(_) => self.done = true,
+                Ok(None) => self.done = half.buffer.len() > MAX_UPGRADE_HEAD_BYTES,
+            },
+            Sync::Frames => {}
+        }
+
+        if half.sync == Sync::Frames && !self.done {
+            self.read_frames(&mut half, &mut frames);
+        }
+
+        if self.done {
+            self.halves.clear();
+        } else {
+            self.halves.insert(direction, half);
+        }
+        frames
+    }
+
+    fn read_frames(&mut self, half: &mut HalfState, frames: &mut Vec<Http2Frame>) {
+        if half.first_frame {
+            if half.buffer.starts_with(HTTP2_PREFACE) {
+                half.buffer.drain(..HTTP2_PREFACE.len());
+                half.hpack_synced = true;
+            } else if HTTP2_PREFACE.starts_with(&half.buffer) {
+                // Wait for the rest of a preface split across segments
+                return;
+            }
+        }
+
+        while half.buffer.len() >= 9 {
+            let length = u32::from_be_bytes([0, half.buffer[0], half.buffer[1], half.buffer[2]]) as usize;
+            let frame_type = half.buffer[3];
+            let flags = half.buffer[4];
+            let stream_id = u32::from_be_bytes([half.buffer[5], half.buffer[6], half.buffer[7], half.buffer[8]]);
+            if !frame_header_valid(frame_type, stream_id) {
+                // Lost frame sync
+                self.done = true;
+                return;
+            }
+
+            // A server's first frame is its SETTINGS; anything else means the capture started mid-connection
+            if std::mem::take(&mut half.first_frame) && frame_type == 0x4 && flags & 0x1 == 0 {
+                half.hpack_synced = true;
+            }
+
+            if frame_type == DATA {
+                // Report DATA as soon as the padding length is known, without buffering the payload
+                let padded = flags & FLAG_PADDED != 0 && length > 0;
+                if padded && half.buffer.len() < 10 {
+                    break;
+                }
+                let len = if padded {
+                    length.saturating_sub(1 + half.buffer[9] as usize)
+                } else {
+                    length
+                };
+                let kind = Http2FrameKind::Data { len };
+                frames.push(Http2Frame {
+                    stream_id,
+                    flags,
+                    length,
+                    kind,
+                });
+                let available = half.buffer.len().min(9 + length);
+                half.buffer.drain(..available);
+                half.skip = 9 + length - available;
+                continue;
+            }
+
+            if length > MAX_FRAME_BYTES {
+                // A header block we cannot follow leaves the HPACK table unknown
+                if matches!(frame_type, HEADERS | PUSH_PROMISE | CONTINUATION) {
+                    half.hpack_synced = false;
+                    half.pending = None;
+                }
+                let available = half.buffer.len().min(9 + length);
+                half.buffer.drain(..available);
+                half.skip = 9 + length - available;
+                continue;
+            }
+
+            if half.buffer.len() < 9 + length {
+                break;
+            }
+            let payload: Vec<u8> = half.buffer.drain(..9 + length).skip(9).collect();
+            if let Some(frame) = self.read_frame(half, frame_type, flags, stream_id, length, &payload) {
+                frames.push(frame);
+            }
+            if self.done {
+                return;
+            }
+        }
+    }
+
+    fn read_frame(
+        &mut self,
+        half: &mut HalfState,
+        frame_type: u8,
+        flags: u8,
+        stream_id: u32,
+        length: usize,
+        payload: &[u8],
+    ) -> Option<Http2Frame> {
+        let pending = match frame_type {
+            HEADERS => {
+                let mut block = strip_padding(flags, payload).ok()?;
+                if flags & FLAG_PRIORITY != 0 {
+                    block = block.get(5..)?;
+                }
+                PendingBlock {
+                    stream_id,
+                    flags,
+                    length,
+                    promised_stream_id: None,
+                    block: block.to_vec(),
+                }
+            }
+            PUSH_PROMISE => {
+                let block = strip_padding(flags, payload).ok()?;
+                let promised = block.get(..4)?;
+                PendingBlock {
+                    stream_id,
+                    flags,
+                    length,
+                    promised_stream_id: Some(
+                        u32::from_be_bytes([promised[0], promised[1], promised[2], promised[3]]) & 0x7fff_ffff,
+                    ),
+                    block: block[4..].to_vec(),
+                }
+            }
+            CONTINUATION => {
+                let Some(mut pending) = half.pending.take().filter(|pending| pending.stream_id == stream_id) else {
+                    // A CONTINUATION that follows nothing means the header block was missed
+                    half.hpack_synced = false;
+                    return None;
+                };
+                pending.block.extend_from_slice(payload);
+                pending.length += length;
+                if pending.block.len() > MAX_HEADER_BLOCK_BYTES {
+                    half.hpack_synced = false;
+                    return None;
+                }
+                // END_HEADERS lives on the CONTINUATION frame; the other flags on the first
+                pending.flags |= flags & FLAG_END_HEADERS;
+                pending
+            }
+            _ => {
+                let kind = parse_control_frame(frame_type, flags, payload).ok()?;
+                return Some(Http2Frame {
+                    stream_id,
+                    flags,
+                    length,
+                    kind,
+                });
+            }
+        };
+
+        if pending.flags & FLAG_END_HEADERS == 0 {
+            half.pending = Some(pending);
+            return None;
+        }
+
+        let headers = if half.hpack_synced {
+            let decoded = half.hpack.decode(&pending.block).ok();
+            half.hpack_synced = decoded.is_some();
+            decoded
+        } else {
+            None
+        };
+        let kind = match pending.promised_stream_id {
+            Some(promised_stream_id) => Http2FrameKind::PushPromise {
+                promised_stream_id,
+                headers,
+            },
+            None => Http2FrameKind::Headers { headers },
+        };
+        Some(Http2Frame {
+            stream_id: pending.stream_id,
+            flags: pending.flags,
+            length: pending.length,
+            kind,
+        })
+    }
+}
+
+/// Rejects frame headers that cannot occur on a conforming connection, which
+/// is how a stream that is not HTTP/2 (or lost sync) is noticed.
+fn frame_header_valid(frame_type: u8, stream_id: u32) -> bool {
+    if stream_id & 0x8000_0000 != 0 {
+        return false;
+    }
+    match frame_type {
+        // DATA, HEADERS, PRIORITY, RST_STREAM, PUSH_PROMISE, CONTINUATION
+        0x0 | 0x1 | 0x2 | 0x3 | 0x5 | 0x9 => stream_id != 0,
+        // SETTINGS, PING, GOAWAY
+        0x4 | 0x6 | 0x7 => stream_id == 0,
+        // WINDOW_UPDATE applies to the connection or a stream
+        0x8 => true,
+        // Extension frame types are allowed, but nothing registered is above 0x10
+        frame_type => frame_type <= 0x10,
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+use crate::packet::{error_code_name, grpc_call, header_value, Http2Frame, Http2FrameKind, PacketInfo};
+
+/// Upper bound on tracked streams; finished ones are dropped first when it is reached
+const MAX_STREAMS: usize = 16384;
+
+/// Lifecycle of an HTTP/2 stream as seen on the wire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Http2StreamState {
+    Open,
+    /// One side has sent END_STREAM
+    HalfClosed,
+    Closed,
+    /// Ended by RST_STREAM with this error code
+    Reset(u32),
+}
+
+impl fmt::Display for Http2StreamState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Http2StreamState::Open => write!(f, "open"),
+            Http2StreamState::HalfClosed => write!(f, "half-closed"),
+            Http2StreamState::Closed => write!(f, "closed"),
+            Http2StreamState::Reset(code) => write!(f, "reset ({})", error_code_name(*code)),
+        }
+    }
+}
+
+/// One HTTP/2 request/response exchange.
+#[derive(Debug, Clone)]
+pub struct Http2Stream {
+    /// TCP stream the HTTP/2 connection runs over
+    pub connection: u64,
+    pub stream_id: u32,
+    /// `ip:port` of the client, once a request or response header block has shown it
+    pub client: Option<String>,
+    pub server: Option<String>,
+    pub method: Option<String>,
+    pub path: Option<String>,
+    pub authority: Option<String>,
+    pub status: Option<String>,
+    /// gRPC service and method, e.g. `helloworld.Greeter` and `SayHello`
+    pub grpc: Option<(String, String)>,
+    pub grpc_status: Option<String>,
+    pub grpc_message: Option<String>,
+    /// DATA payload bytes sent by each side
+    pub request_bytes: usize,
+    pub response_bytes: usize,
+    pub request_ended: bool,
+    pub response_ended: bool,
+    pub reset: Option<u32>,
+    pub first_seen: Duration,
+    pub last_seen: Duration,
+}
+
+impl Http2Stream {
+    fn new(connection: u64, stream_id: u32, timestamp: Duration) -> Self {
+        Http2Stream {
+            connection,
+            stream_id,
+            client: None,
+            server: None,
+            method: None,
+            path: None,
+            authority: None,
+            status: None,
+            grpc: None,
+            grpc_status: None,
+            grpc_message: None,
+            request_bytes: 0,
+            response_bytes: 0,
+            request_ended: false,
+            response_ended: false,
+            reset: None,
+            first_seen: timestamp,
+            last_seen: timestamp,
+        }
+    }
+
+    pub fn state(&self) -> Http2StreamState {
+        match (self.reset, self.request_ended, self.response_ended) {
+            (Some(code), _, _) => Http2StreamState::Reset(code),
+            (None, true, true) => Http2StreamState::Closed,
+            (None, false, false) => Http2StreamState::Open,
+            _ => Http2StreamState::HalfClosed,
+        }
+    }
+
+    /// `gRPC pkg.Service/Method` for gRPC calls, otherwise `METHOD path`.
+    pub fn request_label(&self) -> String {
+        if let Some((service, method)) = &self.grpc {
+            return format!("gRPC {}/{}", service, method);
+        }
+        format!(
+            "{} {}",
+            self.method.as_deref().unwrap_or("?"),
+            self.path.as_deref().unwrap_or("")
+        )
+    }
+
+    /// HTTP status, followed by the gRPC status from the trailers if any.
+    pub fn status_label(&self) -> String {
+        let mut label = self.status.clone().unwrap_or_default();
+        if let Some(grpc_status) = &self.grpc_status {
+            label += &format!(" grpc-status {}", grpc_status);
+        }
+        if let Some(message) = &self.grpc_message {
+            label += &format!(" ({})", message);
+        }
+        label.trim_start().to_string()
+    }
+
+    fn apply_headers(&mut self, headers: &[(String, String)]) {
+        let value = |name| header_value(headers, name).map(str::to_string);
+        self.method = value(":method").or(self.method.take());
+        self.path = value(":path").or(self.path.take());
+        self.authority = value(":authority").or(self.authority.take());
+        self.status = value(":status").or(self.status.take());
+        self.grpc = grpc_call(headers).or(self.grpc.take());
+        self.grpc_status = value("grpc-status").or(self.grpc_status.take());
+        self.grpc_message = value("grpc-message").or(self.grpc_message.take());
+    }
+}
+
+/// HTTP/2 streams keyed by (TCP stream, HTTP/2 stream ID), built from decoded frames.
+#[derive(Debug, Default)]
+pub struct Http2StreamTable {
+    streams: HashMap<(u64, u32), Http2Stream>,
+    /// Client endpoint of each connection, learned from request and response headers
+    clients: HashMap<u64, String>,
+}
+
+impl Http2StreamTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Learns from the HTTP/2 frames of a packet; other packets are ignored.
+    pub fn observe(&mut self, info: &PacketInfo) {
+        if info.http2.is_empty() {
+            return;
+        }
+        let Some(connection) = info.tcp.as_ref().and_then(|tcp| tcp.stream_id) else {
+            return;
+        };
+        let (Some(src_ip), Some(src_port), Some(dst_ip), Some(dst_port)) =
+            (&info.src_ip, info.src_port, &info.dst_ip, info.dst_port)
+        else {
+            return;
+        };
+        let src = format!("{}:{}", src_ip, src_port);
+        let dst = format!("{}:{}", dst_ip, dst_port);
+
+        for frame in &info.http2 {
+            self.observe_frame(connection, frame, &src, &dst, info.timestamp);
+        }
+    }
+
+    fn observe_frame(&mut self, connection: u64, frame: &Http2Frame, src: &str, dst: &str, timestamp: Duration) {
+        // Connection-level frames (SETTINGS, PING, GOAWAY, ...) belong to no stream
+        if frame.stream_id == 0 {
+            return;
+        }
+
+        if let Some(headers) = frame.headers() {
+            if header_value(headers, ":method").is_some() {
+                self.clients.insert(connection, src.to_string());
+            } else if header_value(headers, ":status").is_some() {
+                self.clients.insert(connection, dst.to_string());
+            }
+        }
+        let from_client = self.clients.get(&connection).map(|client| client == src);
+
+        if self.streams.len() >= MAX_STREAMS {
+            self.streams.retain(|_, stream| stream.state() == Http2StreamState::Open);
+            if self.streams.len() >= MAX_STREAMS {
+                self.streams.clear();
+                self.clients.clear();
+            }
+        }
+
+        let stream_id = match frame.kind {
+            // The promise carries the request of the pushed stream
+            Http2FrameKind::PushPromise { promised_stream_id, .. } => promised_stream_id,
+            Http2FrameKind::Data { .. } | Http2FrameKind::Headers { .. } | Http2FrameKind::RstStream { .. } => {
+                frame.stream_id
+            }
+            _ => {
+                if let Some(stream) = self.streams.get_mut(&(connection, frame.stream_id)) {
+                    stream.last_seen = timestamp;
+                }
+                return;
+            }
+        };
+        let stream = self
+            .streams
+            .entry((connection, stream_id))
+            .or_insert_with(|| Http2Stream::new(connection, stream_id, timestamp));
+        stream.last_seen = timestamp;
+        if let Some(from_client) = from_client {
+            let (client, server) = if from_client { (src, dst) } else { (dst, src) };
+            stream.client = Some(client.to_string());
+            stream.server = Some(server.to_string());
+        }
+
+        match &frame.kind {
+            Http2FrameKind::Headers { headers } | Http2FrameKind::PushPromise { headers, .. } => {
+                if let Some(headers) = headers {
+                    stream.apply_headers(headers);
+                }
+            }
+            Http2FrameKind::Data { len } => match from_client {
+                Some(true) => stream.request_bytes += len,
+                Some(false) => stream.response_bytes += len,
+                None => {}
+            },
+            Http2FrameKind::RstStream { error_code } => stream.reset = Some(*error_code),
+            _ => {}
+        }
+
+        if frame.end_stream() {
+            match from_client {
+                Some(true) => stream.request_ended = true,
+                Some(false) => stream.response_ended = true,
+                None => {}
+            }
+        }
+    }
+
+    /// Streams in the order they were first seen.
+    pub fn streams(&self) -> Vec<&Http2Stream> {
+        let mut streams: Vec<_> = self.streams.values().collect();
+        streams.sort_by_key(|stream| (stream.first_seen, stream.connection, stream.stream_id));
+        streams
+    }
+
+    pub fn len(&self) -> usize {
+        self.streams.len()
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use crate::analysis::tcp_reassembly::{Direction, StreamData};
+use crate::packet::{looks_like_http, parse_http_head, HttpMessage, HttpStartLine, PacketInfo, PacketType};
+
+/// Upper bound on connections with HTTP state
+const MAX_CONNECTIONS: usize = 4096;
+/// A head that grows past this without ending is not HTTP
+const MAX_HEAD_BYTES: usize = 64 * 1024;
+/// Longest chunk-size or trailer line accepted
+const MAX_LINE_BYTES: usize = 4096;
+
+/// Where a stream direction is within the current message.
+#[derive(Debug, Clone, Copy, Default)]
+enum State {
+    /// Between messages; the next bytes must look like a start line
+    #[default]
+    Idle,
+    Head,
+    /// Body of known length; bytes remaining
+    Body(u64),
+    ChunkSize,
+    /// Chunk bytes remaining, including the CRLF after the chunk
+    ChunkData(u64),
+    Trailer,
+    /// Body delimited by connection close
+    UntilClose,
+}
+
+#[derive(Debug, Default)]
+struct HalfState {
+    state: State,
+    buffer: Vec<u8>,
+}
+
+#[derive(Debug, Default)]
+struct Connection {
+    halves: HashMap<Direction, HalfState>,
+    /// Requests still waiting for a response, oldest first
+    outstanding: VecDeque<(String, String)>,
+    requests: usize,
+    responses: usize,
+    /// Switched to another protocol (101 or CONNECT tunnel); no longer HTTP/1.x
+    upgraded: bool,
+    last_seen: Duration,
+}
+
+/// Splits reassembled TCP streams into HTTP/1.x messages, following
+/// Content-Length and chunked bodies so keep-alive and pipelined messages are found.
+#[derive(Debug, Default)]
+pub struct HttpStreamDecoder {
+    connections: HashMap<u64, Connection>,
+}
+
+impl HttpStreamDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds stream bytes delivered by `info`. When they complete message heads,
+    /// the packet is marked as HTTP and carries every message completed, in stream order.
+    pub fn observe(&mut self, info: &mut PacketInfo, data: &StreamData) {
+        if !self.connections.contains_key(&data.stream_id) {
+            if !looks_like_http(&data.data) {
+                return;
+            }
+            if self.connections.len() >= MAX_CONNECTIONS {
+                self.evict_oldest();
+            }
+        }
+        let connection = self.connections.entry(data.stream_id).or_default();
+        connection.last_seen = info.timestamp;
+        if connection.upgraded {
+            return;
+        }
+
+        let messages = connection.feed(data.direction, &data.data);
+        if !messages.is_empty() {
+            info.packet_type = PacketType::HTTP;
+            info.http = messages;
+        }
+    }
+
+    /// Forgets the least recently active connection to make room for a new one.
+    fn evict_oldest(&mut self) {
+        let oldest = self
+            .connections
+            .iter()
+            .min_by_key(|(_, connection)| connection.last_seen)
+            .map(|(&id, _)| id);
+        if let Some(id) = oldest {
+            self.connections.remove(&id);
+        }
+    }
+}
+
+impl Connection {
+    fn feed(&mut self, direction: Direction, data: &[u8]) -> Vec<HttpMessage> {
+        let mut half = self.halves.remove(&direction).unwrap_or_default();
+        half.buffer.extend_from_slice(data);
+
+        let mut messages = Vec::new();
+        loop {
+            match half.state {
+                State::Idle => {
+                    if half.buffer.is_empty() {
+                        break;
+                    }
+                    // Lost sync, e.g. the capture started mid-body: wait for a segment that starts a message
+                    if !looks_like_http(&half.buffer) {
+                        half.buffer.clear();
+                        break;
+                    }
+                    half.state = State::Head;
+                }
+                State::Head => match parse_http_head(&half.buffer) {
+                    Ok(Some((message, len))) => {
+                        half.buffer.drain(..len);
+                        let message = self.complete(message);
+                        half.state = self.body_state(&message);
+                        messages.push(message);
+                        if self.upgraded {
+                            break;
+                        }
+                    }
+                    Ok(None) if half.buffer.len() <= MAX_HEAD_BYTES => break,
+                    _ => {
+                        half.buffer.clear();
+                        half.state = State::Idle;
+                        break;
+                    }
+                },
+                State::Body(remaining) | State::ChunkData(remaining) => {
+                    let take = remaining.min(half.buffer.len() as u64);
+                    half.buffer.drain(..take as usize);
+                    let remaining = remaining - take;
+                    half.state = match (half.state, remaining) {
+                        (State::Body(_), 0) => State::Idle,
+                        (State::Body(_), _) => State::Body(remaining),
+                        (_, 0) => State::ChunkSize,
+                        _ => State::ChunkData(remaining),
+                    };
+                    if remaining > 0 {
+                        break;
+                    }
+                }
+                State::ChunkSize | State::Trailer => {
+                    let Some(end) = half.buffer.iter().position(|&b| b == b'\n') else {
+                        if half.buffer.len() > MAX_LINE_BYTES {
+                            half.buffer.clear();
+                            half.state = State::Idle;
+                        }
+                        break;
+                    };
+                    let line: Vec<u8> = half.buffer.drain(..=end).collect();
+                    let line = String::from_utf8_lossy(&line);
+                    let line = line.trim();
+                    half.state = match half.state {
+                        State::Trailer if line.is_empty() => State::Idle,
+                        State::Trailer => State::Trailer,
+                        _ => {
+                            // Chunk extensions follow a semicolon
+                            let size = line.split(';').next().unwrap_or("").trim();
+                            match u64::from_str_radix(size, 16) {
+                                Ok(0) => State::Trailer,
+                                Ok(size) => State::ChunkData(size.saturating_add(2)),
+                                Err(_) => {
+                                    half.buffer.clear();
+                                    State::Idle
+                                }
+                            }
+                        }
+                    };
+                }
+                State::UntilClose => {
+                    half.buffer.clear();
+                    break;
+                }
+            }
+        }
+
+        self.halves.insert(direction, half);
+        messages
+    }
+
+    /// Fills in the connection-level fields of a newly parsed message.
+    fn complete(&mut self, mut message: HttpMessage) -> HttpMessage {
+        match message.status() {
+            None => {
+                self.requests += 1;
+                message.sequence = self.requests;
+                message.pipelined = !self.outstanding.is_empty();
+                if let HttpStartLine::Request { method, uri } = &message.start {
+                    self.outstanding.push_back((method.clone(), uri.clone()));
+                }
+            }
+            // Interim responses precede the final one to the same request
+            Some(status) if (100..200).contains(&status) && status != 101 => {
+                message.sequence = self.responses + 1;
+                message.request = self.outstanding.front().cloned();
+            }
+            Some(_) => {
+                self.responses += 1;
+                message.sequence = self.responses;
+                message.request = self.outstanding.pop_front();
+            }
+        }
+        message
+    }
+
+    /// State after a message head: which body, if any, follows it.
+    fn body_state(&mut self, message: &HttpMessage) -> State {
+        let method = message.request.as_ref().map(|(method, _)| method.as_str());
+        match message.status() {
+            Some(101) => {
+                self.upgraded = true;
+                return State::Idle;
+            }
+            Some(status) if method == Some("CONNECT") && (200..300).contains(&status) => {
+                self.upgraded = true;
+                return State::Idle;
+            }
+            Some(status) if (100..200).contains(&status) || status == 204 || status == 304 => {
+                return State::Idle;
+            }
+            Some(_) if method == Some("HEAD") => return State::Idle,
+            _ => {}
+        }
+
+        if message.chunked {
+            State::ChunkSize
+        } else if let Some(length) = message.content_length {
+            if length == 0 { State::Idle } else { State::Body(length) }
+        } else if message.is_request() {
+            State::Idle
+        } else {
+            State::UntilClose
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use crate::packet::{parse_transport, PacketInfo, Reassembly};
+
+/// Incomplete datagrams older than this are dropped (matches the Linux default)
+const FRAGMENT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Largest datagram payload we are willing to rebuild
+const MAX_DATAGRAM_LEN: usize = 65535;
+
+/// Upper bound on incomplete datagrams held at once
+const MAX_PENDING: usize = 1024;
+
+/// Upper bound on fragment data buffered across all incomplete datagrams
+const MAX_BUFFERED_BYTES: usize = 4 * 1024 * 1024;
+
+/// (source, destination, protocol, identification)
+type FragmentKey = (String, String, u8, u32);
+
+#[derive(Debug)]
+struct PendingDatagram {
+    first_seen: Duration,
+    /// Fragment data keyed by byte offset; a repeated fragment replaces the earlier copy
+    fragments: BTreeMap<usize, Vec<u8>>,
+    /// Datagram length, known once the fragment without MF arrives
+    total_len: Option<usize>,
+    buffered: usize,
+}
+
+impl PendingDatagram {
+    /// Returns the datagram payload once every byte up to `total_len` is covered.
+    /// Where fragments overlap, the one with the lower offset wins.
+    fn assemble(&self) -> Option<Vec<u8>> {
+        let total_len = self.total_len?;
+        let mut datagram = vec![0u8; total_len];
+        let mut covered = 0;
+
+        for (&offset, data) in &self.fragments {
+            if offset > covered {
+                return None; // Hole
+            }
+            let end = (offset + data.len()).min(total_len);
+            if end > covered {
+                datagram[covered..end].copy_from_slice(&data[covered - offset..end - offset]);
+                covered = end;
+            }
+        }
+
+        (covered == total_len).then_some(datagram)
+    }
+}
+
+/// Collects IPv4 and IPv6 fragments and decodes the transport layer of each
+/// datagram on the fragment that completes it.
+#[derive(Debug, Default)]
+pub struct FragmentReassembler {
+    pending: HashMap<FragmentKey, PendingDatagram>,
+    buffered: usize,
+}
+
+impl FragmentReassembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Buffers the fragment carried by `info`, if any. When it completes a datagram,
+    /// the transport fields of `info` are filled in from the reassembled payload.
+    pub fn observe(&mut self, info: &mut PacketInfo) {
+        let timestamp = info.timestamp;
+        let (Some(src), Some(dst)) = (info.src_ip.clone(), info.dst_ip.clone()) else {
+            return;
+        };
+        let Some(fragment) = info.fragment.as_mut() else {
+            return;
+        };
+
+        self.expire(timestamp);
+
+        let data = std::mem::take(&mut fragment.payload);
+        let end = fragment.offset + data.len();
+        if end > MAX_DATAGRAM_LEN || data.len() > MAX_BUFFERED_BYTES {
+            return;
+        }
+
+        let protocol = fragment.protocol;
+        let key = (src, dst, protocol, fragment.identification);
+        self.make_room(&key, data.len());
+
+        let pending = self.pending.entry(key.clone()).or_insert_with(|| PendingDatagram {
+            first_seen: timestamp,
+            fragments: BTreeMap::new(),
+            total_len: None,
+            buffered: 0,
+        });
+
+        if !fragment.more_fragments {
+            pending.total_len = Some(end);
+        }
+
+        pending.buffered += data.len();
+        self.buffered += data.len();
+        if let Some(previous) = pending.fragments.insert(fragment.offset, data) {
+            pending.buffered -= previous.len();
+            self.buffered -= previous.len();
+        }
+
+        let Some(datagram) = pending.assemble() else {
+            return;
+        };
+
+        fragment.reassembled = Some(Reassembly {
+            fragments: pending.fragments.len(),
+            length: datagram.len(),
+        });
+        if let Some(done) = self.pending.remove(&key) {
+            self.buffered -= done.buffered;
+        }
+
+        // A malformed transport header leaves the packet marked as a bare fragment
+        let _ = parse_transport(protocol, &datagram, info);
+    }
+
+    /// Evicts the oldest incomplete datagrams until `incoming` more bytes fit.
+    fn make_room(&mut self, key: &FragmentKey, incoming: usize) {
+        while !self.pending.is_empty()
+            && (self.buffered + incoming > MAX_BUFFERED_BYTES
+                || (self.pending.len() >= MAX_PENDING && !self.pending.contains_key(key)))
+        {
+            let Some(oldest) = self
+                .pending
+                .iter()
+                .min_by_key(|(_, pending)| pending.first_seen)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            if let Some(evicted) = self.pending.remove(&oldest) {
+                self.buffered -= evicted.buffered;
+            }
+        }
+    }
+
+    /// Drops incomplete datagrams whose first fragment is older than the timeout.
+    fn expire(&mut self, now: Duration) {
+        let mut freed = 0;
+        self.pending.retain(|_, pending| {
+            let keep = now.saturating_sub(pending.first_seen) <= FRAGMENT_TIMEOUT;
+            if !keep {
+                freed += pending.buffered;
+            }
+            keep
+        });
+        self.buffered -= freed;
+    }
+}
@@ -0,0 +1,15 @@
+pub mod arp_table;
+pub mod certificates;
+pub mod connections;
+pub mod dhcp_leases;
+pub mod dhcpv6_leases;
+pub mod dns_stats;
+pub mod dns_tcp;
+pub mod http2_stream;
+pub mod http2_streams;
+pub mod http_stream;
+pub mod ip_reassembly;
+pub mod ping;
+pub mod quic;
+pub mod tcp_reassembly;
+pub mod tls_stream;
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::packet::PacketInfo;
+
+/// Requests older than this are assumed lost and forgotten
+const PENDING_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Upper bound on outstanding requests, so a ping flood cannot grow the table forever
+const MAX_PENDING: usize = 4096;
+
+/// (requester, responder, identifier, sequence)
+type PingKey = (String, String, u16, u16);
+
+/// Matches echo replies to their requests and records the round-trip time on the reply.
+#[derive(Debug, Default)]
+pub struct PingTracker {
+    pending: HashMap<PingKey, Duration>,
+}
+
+impl PingTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remembers echo requests and sets `rtt` on echo replies that match one.
+    pub fn observe(&mut self, info: &mut PacketInfo) {
+        let timestamp = info.timestamp;
+        let (Some(src), Some(dst)) = (info.src_ip.clone(), info.dst_ip.clone()) else {
+            return;
+        };
+
+        // ICMP and ICMPv6 share the echo layout; only the type numbers differ
+        let (echo, is_request, is_reply, rtt) = if let Some(icmp) = info.icmp.as_mut() {
+            (icmp.echo, icmp.is_echo_request(), icmp.is_echo_reply(), &mut icmp.rtt)
+        } else if let Some(icmpv6) = info.icmpv6.as_mut() {
+            (icmpv6.echo, icmpv6.is_echo_request(), icmpv6.is_echo_reply(), &mut icmpv6.rtt)
+        } else {
+            return;
+        };
+        let Some(echo) = echo else {
+            return;
+        };
+
+        if is_request {
+            if self.pending.len() >= MAX_PENDING {
+                self.expire(timestamp);
+            }
+            if self.pending.len() < MAX_PENDING {
+                self.pending.insert((src, dst, echo.identifier, echo.sequence), timestamp);
+            }
+        } else if is_reply {
+            // The reply travels in the opposite direction of the request
+            if let Some(sent) = self.pending.remove(&(dst, src, echo.identifier, echo.sequence)) {
+                *rtt = Some(timestamp.saturating_sub(sent));
+            }
+        }
+    }
+
+    fn expire(&mut self, now: Duration) {
+        self.pending
+            .retain(|_, sent| now.saturating_sub(*sent) < PENDING_TIMEOUT);
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::analysis::tcp_reassembly::Endpoint;
+use crate::packet::{parse_client_hello, PacketInfo, QuicPacketType, TlsInfo, TlsTransport};
+
+/// Upper bound on tracked endpoints and Initial CRYPTO streams
+const MAX_ENTRIES: usize = 4096;
+/// A ClientHello larger than this is not reassembled
+const MAX_CRYPTO_BYTES: usize = 64 * 1024;
+
+/// CRYPTO frame data of a client's Initial packets.
+#[derive(Debug, Default)]
+struct CryptoStream {
+    /// Frame data by offset
+    frames: BTreeMap<u64, Vec<u8>>,
+    buffered: usize,
+    /// The ClientHello was decoded or the stream was abandoned
+    done: bool,
+}
+
+impl CryptoStream {
+    /// The bytes contiguous from offset 0.
+    fn contiguous(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        for (&offset, frame) in &self.frames {
+            let offset = offset as usize;
+            if offset > data.len() {
+                break;
+            }
+            if offset + frame.len() > data.len() {
+                data.extend_from_slice(&frame[data.len() - offset..]);
+            }
+        }
+        data
+    }
+}
+
+/// Follows QUIC connections across datagrams: learns each endpoint's connection ID
+/// length so short header DCIDs can be split off, and reassembles the CRYPTO frames
+/// of client Initials, which browsers spread over several packets, into the ClientHello.
+#[derive(Debug, Default)]
+pub struct QuicTracker {
+    /// Connection ID length chosen by each endpoint, from the SCID of its long header packets
+    cid_lengths: HashMap<Endpoint, usize>,
+    /// Keyed by client endpoint and the DCID of its Initial packets
+    crypto: HashMap<(Endpoint, Vec<u8>), CryptoStream>,
+}
+
+impl QuicTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Annotates a QUIC packet; when it completes a ClientHello, the hello is added to `info.tls`.
+    pub fn observe(&mut self, info: &mut PacketInfo) {
+        let (Some(src_ip), Some(src_port), Some(dst_ip), Some(dst_port)) =
+            (&info.src_ip, info.src_port, &info.dst_ip, info.dst_port)
+        else {
+            return;
+        };
+        let src = (src_ip.clone(), src_port);
+        let dst = (dst_ip.clone(), dst_port);
+        let Some(quic) = info.quic.as_mut() else {
+            return;
+        };
+
+        let mut hellos = Vec::new();
+        for packet in &mut quic.packets {
+            match packet.packet_type {
+                QuicPacketType::OneRtt => {
+                    if let Some(&len) = self.cid_lengths.get(&dst)
+                        && packet.short_header_bytes.len() >= len
+                    {
+                        packet.dcid = Some(packet.short_header_bytes[..len].to_vec());
+                    }
+                }
+                QuicPacketType::VersionNegotiation | QuicPacketType::Retry => {}
+                _ => {
+                    if let Some(scid) = &packet.scid {
+                        if self.cid_lengths.len() >= MAX_ENTRIES {
+                            self.cid_lengths.clear();
+                        }
+                        self.cid_lengths.insert(src.clone(), scid.len());
+                    }
+                }
+            }
+
+            if packet.crypto.is_empty() {
+                continue;
+            }
+            let Some(dcid) = &packet.dcid else {
+                continue;
+            };
+            if self.crypto.len() >= MAX_ENTRIES {
+                self.crypto.clear();
+            }
+            let stream = self.crypto.entry((src.clone(), dcid.clone())).or_default();
+            if stream.done {
+                continue;
+            }
+            for (offset, data) in &packet.crypto {
+                stream.buffered += data.len();
+                stream.frames.insert(*offset, data.clone());
+            }
+            if stream.buffered > MAX_CRYPTO_BYTES {
+                stream.done = true;
+                stream.frames.clear();
+                continue;
+            }
+
+            let data = stream.contiguous();
+            let [msg_type, a, b, c, ..] = data[..] else {
+                continue;
+            };
+            let len = u32::from_be_bytes([0, a, b, c]) as usize;
+            if data.len() < 4 + len {
+                continue;
+            }
+            stream.done = true;
+            stream.frames.clear();
+            if msg_type == 1
+                && let Ok(hello) = parse_client_hello(&data[4..4 + len], TlsTransport::Quic)
+            {
+                hellos.push(TlsInfo::ClientHello(hello));
+            }
+        }
+
+        info.tls.extend(hellos);
+    }
+}
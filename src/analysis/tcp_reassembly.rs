@@ -0,0 +1,663 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::time::Duration;
+
+use crate::packet::{PacketInfo, SegmentKind, TcpFlags};
+
+/// Streams idle for longer than this are forgotten
+const IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How often idle streams are swept
+const EXPIRE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Upper bound on tracked connections
+const MAX_STREAMS: usize = 4096;
+
+/// In-order bytes retained per direction for follow-stream views and file extraction,
+/// when retention is enabled. Bytes past the cap are still handed to dissectors, just not kept.
+const MAX_STREAM_BYTES: usize = 1024 * 1024;
+
+/// Out-of-order bytes buffered per direction while waiting for a gap to fill.
+/// Once exceeded, the gap is given up on and skipped.
+const MAX_OUT_OF_ORDER_BYTES: usize = 256 * 1024;
+
+/// Segments starting further than this past the next expected byte are not buffered
+const MAX_REORDER_WINDOW: u64 = 1024 * 1024;
+
+/// Upper bound on bytes held across all streams, retained and out-of-order alike
+const MAX_TOTAL_BYTES: usize = 64 * 1024 * 1024;
+
+/// An (address, port) pair.
+pub type Endpoint = (String, u16);
+
+/// Which side of the connection sent a chunk of data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    ClientToServer,
+    ServerToClient,
+}
+
+/// Direction-independent identity of a TCP connection.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StreamKey {
+    lower: Endpoint,
+    upper: Endpoint,
+}
+
+impl StreamKey {
+    /// Builds the key for a TCP packet; `None` for anything else.
+    pub fn from_packet(info: &PacketInfo) -> Option<Self> {
+        let segment = Segment::from_packet(info)?;
+        Some(Self::new(segment.src, segment.dst))
+    }
+
+    fn new(src: Endpoint, dst: Endpoint) -> Self {
+        if src <= dst {
+            StreamKey { lower: src, upper: dst }
+        } else {
+            StreamKey { lower: dst, upper: src }
+        }
+    }
+}
+
+/// The fields of a TCP packet the reassembler works from.
+struct Segment<'a> {
+    src: Endpoint,
+    dst: Endpoint,
+    timestamp: Duration,
+    seq: u32,
+    flags: TcpFlags,
+    payload: &'a [u8],
+}
+
+impl<'a> Segment<'a> {
+    fn from_packet(info: &'a PacketInfo) -> Option<Self> {
+        let tcp = info.tcp.as_ref()?;
+        Some(Segment {
+            src: (info.src_ip.clone()?, info.src_port?),
+            dst: (info.dst_ip.clone()?, info.dst_port?),
+            timestamp: info.timestamp,
+            seq: tcp.seq,
+            flags: tcp.flags,
+            payload: &info.payload,
+        })
+    }
+}
+
+impl fmt::Display for StreamKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{} <-> {}:{}", self.lower.0, self.lower.1, self.upper.0, self.upper.1)
+    }
+}
+
+/// One direction of a connection, reassembled into an ordered byte stream.
+#[derive(Debug, Default)]
+pub struct HalfStream {
+    /// Sequence number of stream offset 0 (ISN + 1 when the SYN was seen)
+    base: Option<u32>,
+    /// Stream offset of the next expected byte
+    next: u64,
+    /// Out-of-order data keyed by stream offset
+    pending: BTreeMap<u64, Vec<u8>>,
+    pending_bytes: usize,
+    /// In-order payload, up to `MAX_STREAM_BYTES`; empty unless the reassembler retains data
+    pub data: Vec<u8>,
+    /// True once in-order payload was dropped because of the retention cap
+    pub truncated: bool,
+    /// Bytes that never showed up and were skipped over
+    pub missing_bytes: u64,
+    /// Payload bytes seen again after they had already been delivered
+    pub retransmitted_bytes: u64,
+    pub fin: bool,
+}
+
+impl HalfStream {
+    /// Total stream bytes accounted for, delivered or skipped.
+    pub fn len(&self) -> u64 {
+        self.next
+    }
+
+    /// Adds a segment and returns how it relates to the stream together with any
+    /// bytes that became contiguous as a result.
+    fn push(&mut self, seq: u32, syn: bool, payload: &[u8]) -> (Option<SegmentKind>, Vec<u8>) {
+        // The SYN occupies one sequence number ahead of the first data byte
+        let seq = if syn { seq.wrapping_add(1) } else { seq };
+        if syn {
+            self.base.get_or_insert(seq);
+        }
+        if payload.is_empty() {
+            return (None, Vec::new());
+        }
+
+        // Without the handshake the first data segment defines offset 0
+        let base = *self.base.get_or_insert(seq);
+
+        // Offset relative to the next expected byte, tolerant of sequence wraparound
+        let expected = base.wrapping_add(self.next as u32);
+        let start = self.next as i64 + seq.wrapping_sub(expected) as i32 as i64;
+        let end = start + payload.len() as i64;
+        let next = self.next as i64;
+
+        if end <= next {
+            self.retransmitted_bytes += payload.len() as u64;
+            return (Some(SegmentKind::Retransmission), Vec::new());
+        }
+
+        let (kind, start, payload) = if start < next {
+            let overlap = (next - start) as usize;
+            self.retransmitted_bytes += overlap as u64;
+            (SegmentKind::Retransmission, next as u64, &payload[overlap..])
+        } else if start > next {
+            (SegmentKind::OutOfOrder, start as u64, payload)
+        } else {
+            (SegmentKind::InOrder, start as u64, payload)
+        };
+
+        let mut delivered = Vec::new();
+        if start == self.next {
+            delivered.extend_from_slice(payload);
+            self.next += payload.len() as u64;
+        } else if start - self.next <= MAX_REORDER_WINDOW {
+            self.buffer(start, payload);
+        }
+
+        if self.pending_bytes > MAX_OUT_OF_ORDER_BYTES {
+            self.skip_gap();
+        }
+        delivered.extend(self.drain());
+
+        (Some(kind), delivered)
+    }
+
+    /// Buffers out-of-order data, keeping the longer copy if the offset was seen before.
+    fn buffer(&mut self, start: u64, payload: &[u8]) {
+        if self.pending.get(&start).is_some_and(|existing| existing.len() >= payload.len()) {
+            return;
+        }
+        self.pending_bytes += payload.len();
+        if let Some(previous) = self.pending.insert(start, payload.to_vec()) {
+            self.pending_bytes -= previous.len();
+        }
+    }
+
+    /// Moves buffered data that is now contiguous into the stream.
+    fn drain(&mut self) -> Vec<u8> {
+        let mut delivered = Vec::new();
+
+        while let Some(entry) = self.pending.first_entry() {
+            let start = *entry.key();
+            if start > self.next {
+                break;
+            }
+            let data = entry.remove();
+            self.pending_bytes -= data.len();
+
+            let end = start + data.len() as u64;
+            if end > self.next {
+                delivered.extend_from_slice(&data[(self.next - start) as usize..]);
+                self.next = end;
+            }
+        }
+
+        delivered
+    }
+
+    /// Gives up on the bytes in front of the first buffered segment.
+    fn skip_gap(&mut self) {
+        if let Some(&start) = self.pending.keys().next()
+            && start > self.next
+        {
+            self.missing_bytes += start - self.next;
+            self.next = start;
+        }
+    }
+
+    /// Keeps delivered bytes up to the retention cap; returns how many were kept.
+    fn retain(&mut self, delivered: &[u8]) -> usize {
+        let room = MAX_STREAM_BYTES.saturating_sub(self.data.len());
+        let kept = delivered.len().min(room);
+        if kept < delivered.len() {
+            self.truncated = true;
+        }
+        self.data.extend_from_slice(&delivered[..kept]);
+        kept
+    }
+
+    fn memory(&self) -> usize {
+        self.data.len() + self.pending_bytes
+    }
+}
+
+/// A TCP connection with both directions reassembled.
+#[derive(Debug)]
+pub struct TcpStream {
+    pub id: u64,
+    /// The side that sent the initial SYN, or the first packet seen mid-connection
+    pub client: Endpoint,
+    pub server: Endpoint,
+    pub client_to_server: HalfStream,
+    pub server_to_client: HalfStream,
+    pub first_seen: Duration,
+    pub last_seen: Duration,
+    pub reset: bool,
+    /// Retained bytes per conversational turn, in arrival order; empty unless the
+    /// reassembler retains data
+    turns: Vec<(Direction, usize)>,
+}
+
+impl TcpStream {
+    /// The retained data of both directions interleaved in the order it was delivered.
+    pub fn transcript(&self) -> Vec<(Direction, &[u8])> {
+        let mut offsets = (0, 0);
+        self.turns
+            .iter()
+            .map(|&(direction, len)| {
+                let (data, offset) = match direction {
+                    Direction::ClientToServer => (&self.client_to_server.data, &mut offsets.0),
+                    Direction::ServerToClient => (&self.server_to_client.data, &mut offsets.1),
+                };
+                let chunk = &data[*offset..*offset + len];
+                *offset += len;
+                (direction, chunk)
+            })
+            .collect()
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.reset || (self.client_to_server.fin && self.server_to_client.fin)
+    }
+
+    fn half_mut(&mut self, direction: Direction) -> &mut HalfStream {
+        match direction {
+            Direction::ClientToServer => &mut self.client_to_server,
+            Direction::ServerToClient => &mut self.server_to_client,
+        }
+    }
+
+    fn record_turn(&mut self, direction: Direction, len: usize) {
+        if len == 0 {
+            return;
+        }
+        match self.turns.last_mut() {
+            Some((last, total)) if *last == direction => *total += len,
+            _ => self.turns.push((direction, len)),
+        }
+    }
+
+    fn memory(&self) -> usize {
+        self.client_to_server.memory() + self.server_to_client.memory()
+    }
+}
+
+/// Bytes that became contiguous on a stream, for application-layer dissectors.
+#[derive(Debug, Clone)]
+pub struct StreamData {
+    pub stream_id: u64,
+    pub direction: Direction,
+    pub data: Vec<u8>,
+}
+
+/// Reassembles TCP payloads into ordered, deduplicated byte streams per connection
+/// and direction, and labels each segment with its stream and ordering.
+#[derive(Debug, Default)]
+pub struct TcpReassembler {
+    streams: HashMap<StreamKey, TcpStream>,
+    next_id: u64,
+    buffered: usize,
+    last_expire: Duration,
+    /// Whether delivered bytes are kept on the stream for `transcript()`
+    retain: bool,
+}
+
+impl TcpReassembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether in-order bytes are kept on each stream after being delivered.
+    /// The live pipeline only needs the delivered chunks, so it leaves this off and
+    /// the memory budget covers out-of-order buffers alone.
+    pub fn with_retention(mut self, retain: bool) -> Self {
+        self.retain = retain;
+        self
+    }
+
+    /// Adds a TCP segment, setting `stream_id` and `segment` on its header.
+    /// Returns the bytes the segment made contiguous, if any.
+    pub fn observe(&mut self, info: &mut PacketInfo) -> Option<StreamData> {
+        let (stream_id, direction, kind, delivered) = self.push(Segment::from_packet(info)?);
+        let tcp = info.tcp.as_mut()?;
+        tcp.stream_id = Some(stream_id);
+        tcp.segment = kind;
+
+        (!delivered.is_empty()).then_some(StreamData {
+            stream_id,
+            direction,
+            data: delivered,
+        })
+    }
+
+    /// Adds a segment to its stream, returning the stream id, the direction the
+    /// segment travelled in, how it relates to the stream and the bytes it made contiguous.
+    fn push(&mut self, segment: Segment) -> (u64, Direction, Option<SegmentKind>, Vec<u8>) {
+        let Segment { src, dst, timestamp, seq, flags, payload } = segment;
+        let key = StreamKey::new(src.clone(), dst.clone());
+
+        self.expire(timestamp);
+
+        // A fresh SYN on a finished connection means the port pair was reused
+        if flags.syn
+            && !flags.ack
+            && self.streams.get(&key).is_some_and(|stream| stream.is_closed())
+        {
+            self.remove(&key);
+        }
+
+        if !self.streams.contains_key(&key) {
+            self.make_room();
+        }
+        let next_id = &mut self.next_id;
+        let stream = self.streams.entry(key).or_insert_with(|| {
+            // Whoever sends the SYN is the client; the SYN-ACK comes from the server
+            let (client, server) = if flags.syn && flags.ack {
+                (dst, src.clone())
+            } else {
+                (src.clone(), dst)
+            };
+            *next_id += 1;
+            TcpStream {
+                id: *next_id - 1,
+                client,
+                server,
+                client_to_server: HalfStream::default(),
+                server_to_client: HalfStream::default(),
+                first_seen: timestamp,
+                last_seen: timestamp,
+                reset: false,
+                turns: Vec::new(),
+            }
+        });
+        let before = stream.memory();
+        let direction = if src == stream.client {
+            Direction::ClientToServer
+        } else {
+            Direction::ServerToClient
+        };
+
+        stream.last_seen = timestamp;
+        stream.reset |= flags.rst;
+
+        let half = stream.half_mut(direction);
+        let (kind, delivered) = half.push(seq, flags.syn, payload);
+        half.fin |= flags.fin;
+        if self.retain {
+            let kept = half.retain(&delivered);
+            stream.record_turn(direction, kept);
+        }
+
+        let stream_id = stream.id;
+        self.buffered = self.buffered - before + stream.memory();
+        self.enforce_memory_limit();
+
+        (stream_id, direction, kind, delivered)
+    }
+
+    fn remove(&mut self, key: &StreamKey) -> Option<TcpStream> {
+        let stream = self.streams.remove(key)?;
+        self.buffered -= stream.memory();
+        Some(stream)
+    }
+
+    /// Evicts the least recently active stream when the table is full.
+    fn make_room(&mut self) {
+        if self.streams.len() >= MAX_STREAMS {
+            self.evict_oldest();
+        }
+    }
+
+    fn enforce_memory_limit(&mut self) {
+        while self.buffered > MAX_TOTAL_BYTES && !self.streams.is_empty() {
+            self.evict_oldest();
+        }
+    }
+
+    fn evict_oldest(&mut self) {
+        let oldest = self
+            .streams
+            .iter()
+            .min_by_key(|(_, stream)| stream.last_seen)
+            .map(|(key, _)| key.clone());
+        if let Some(key) = oldest {
+            self.remove(&key);
+        }
+    }
+
+    /// Drops streams that have been idle longer than the timeout.
+    fn expire(&mut self, now: Duration) {
+        if now.saturating_sub(self.last_expire) < EXPIRE_INTERVAL {
+            return;
+        }
+        self.last_expire = now;
+
+        let mut freed = 0;
+        self.streams.retain(|_, stream| {
+            let keep = now.saturating_sub(stream.last_seen) <= IDLE_TIMEOUT;
+            if !keep {
+                freed += stream.memory();
+            }
+            keep
+        });
+        self.buffered -= freed;
+    }
+}
+
+/// Reassembles a single connection from already decoded packets, such as the
+/// GUI packet log, for follow-stream views and file extraction.
+pub fn follow_stream<'a>(
+    packets: impl IntoIterator<Item = &'a PacketInfo>,
+    key: &StreamKey,
+) -> Option<TcpStream> {
+    let mut reassembler = TcpReassembler::new().with_retention(true);
+    for segment in packets.into_iter().filter_map(Segment::from_packet) {
+        if StreamKey::new(segment.src.clone(), segment.dst.clone()) == *key {
+            reassembler.push(segment);
+        }
+    }
+    reassembler.remove(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SYN: u16 = 0x002;
+    const SYN_ACK: u16 = 0x012;
+    const ACK: u16 = 0x010;
+
+    const CLIENT_ISN: u32 = 1000;
+    const SERVER_ISN: u32 = 5000;
+
+    fn segment(client_port: u16, to_server: bool, seq: u32, flags: u16, payload: &[u8]) -> Segment<'_> {
+        let client = ("10.0.0.1".to_string(), client_port);
+        let server = ("10.0.0.2".to_string(), 80);
+        let (src, dst) = if to_server { (client, server) } else { (server, client) };
+        Segment {
+            src,
+            dst,
+            timestamp: Duration::from_secs(1),
+            seq,
+            flags: TcpFlags::from_bits(flags),
+            payload,
+        }
+    }
+
+    /// Sends client data at `offset` bytes into the client's stream.
+    fn client_data(reassembler: &mut TcpReassembler, offset: u32, payload: &[u8]) -> (Option<SegmentKind>, Vec<u8>) {
+        let (_, direction, kind, delivered) =
+            reassembler.push(segment(40000, true, CLIENT_ISN + 1 + offset, ACK, payload));
+        assert_eq!(direction, Direction::ClientToServer);
+        (kind, delivered)
+    }
+
+    fn handshake(reassembler: &mut TcpReassembler, client_port: u16) {
+        reassembler.push(segment(client_port, true, CLIENT_ISN, SYN, b""));
+        reassembler.push(segment(client_port, false, SERVER_ISN, SYN_ACK, b""));
+    }
+
+    fn key(client_port: u16) -> StreamKey {
+        StreamKey::new(("10.0.0.1".to_string(), client_port), ("10.0.0.2".to_string(), 80))
+    }
+
+    #[test]
+    fn retransmissions_and_overlaps_are_delivered_once() {
+        let mut reassembler = TcpReassembler::new();
+        handshake(&mut reassembler, 40000);
+
+        assert_eq!(client_data(&mut reassembler, 0, b"hello"), (Some(SegmentKind::InOrder), b"hello".to_vec()));
+        assert_eq!(client_data(&mut reassembler, 0, b"hello"), (Some(SegmentKind::Retransmission), Vec::new()));
+        assert_eq!(
+            client_data(&mut reassembler, 3, b"lo world"),
+            (Some(SegmentKind::Retransmission), b" world".to_vec())
+        );
+
+        let half = &reassembler.streams[&key(40000)].client_to_server;
+        assert_eq!(half.len(), 11);
+        assert_eq!(half.retransmitted_bytes, 7);
+        assert_eq!(half.missing_bytes, 0);
+    }
+
+    #[test]
+    fn out_of_order_segments_wait_for_the_gap() {
+        let mut reassembler = TcpReassembler::new();
+        handshake(&mut reassembler, 40000);
+
+        assert_eq!(client_data(&mut reassembler, 10, b"third"), (Some(SegmentKind::OutOfOrder), Vec::new()));
+        assert_eq!(client_data(&mut reassembler, 5, b"secnd"), (Some(SegmentKind::OutOfOrder), Vec::new()));
+        assert_eq!(reassembler.buffered, 10);
+        assert_eq!(
+            client_data(&mut reassembler, 0, b"first"),
+            (Some(SegmentKind::InOrder), b"firstsecndthird".to_vec())
+        );
+        assert_eq!(reassembler.buffered, 0);
+    }
+
+    #[test]
+    fn sequence_numbers_wrap_around() {
+        let mut reassembler = TcpReassembler::new();
+        let isn = u32::MAX - 2;
+        reassembler.push(segment(40000, true, isn, SYN, b""));
+
+        let (_, _, _, first) = reassembler.push(segment(40000, true, isn.wrapping_add(1), ACK, b"abcd"));
+        let (_, _, kind, second) = reassembler.push(segment(40000, true, isn.wrapping_add(5), ACK, b"efgh"));
+        assert_eq!(first, b"abcd");
+        assert_eq!((kind, second), (Some(SegmentKind::InOrder), b"efgh".to_vec()));
+    }
+
+    #[test]
+    fn segments_past_the_reorder_window_are_not_buffered() {
+        let mut reassembler = TcpReassembler::new();
+        handshake(&mut reassembler, 40000);
+
+        let far = MAX_REORDER_WINDOW as u32 + 1;
+        assert_eq!(client_data(&mut reassembler, far, b"late"), (Some(SegmentKind::OutOfOrder), Vec::new()));
+        assert_eq!(reassembler.buffered, 0);
+        assert!(reassembler.streams[&key(40000)].client_to_server.pending.is_empty());
+    }
+
+    #[test]
+    fn gap_is_skipped_once_the_out_of_order_buffer_is_full() {
+        let mut reassembler = TcpReassembler::new();
+        handshake(&mut reassembler, 40000);
+
+        let chunk = vec![0x55; 64 * 1024];
+        let mut delivered = Vec::new();
+        for index in 0..=(MAX_OUT_OF_ORDER_BYTES / chunk.len()) as u32 {
+            let (_, data) = client_data(&mut reassembler, 100 + index * chunk.len() as u32, &chunk);
+            delivered.extend(data);
+        }
+
+        let half = &reassembler.streams[&key(40000)].client_to_server;
+        assert_eq!(half.missing_bytes, 100);
+        assert_eq!(delivered.len(), MAX_OUT_OF_ORDER_BYTES + chunk.len());
+        assert_eq!(reassembler.buffered, 0);
+    }
+
+    #[test]
+    fn delivered_bytes_are_only_kept_with_retention() {
+        let mut live = TcpReassembler::new();
+        let mut follow = TcpReassembler::new().with_retention(true);
+        for reassembler in [&mut live, &mut follow] {
+            handshake(reassembler, 40000);
+            client_data(reassembler, 0, b"GET / HTTP/1.1\r\n\r\n");
+            reassembler.push(segment(40000, false, SERVER_ISN + 1, ACK, b"HTTP/1.1 200 OK\r\n\r\n"));
+        }
+
+        let stream = live.remove(&key(40000)).unwrap();
+        assert!(stream.client_to_server.data.is_empty());
+        assert!(stream.transcript().is_empty());
+        assert_eq!(live.buffered, 0);
+
+        let stream = follow.remove(&key(40000)).unwrap();
+        assert_eq!(
+            stream.transcript(),
+            vec![
+                (Direction::ClientToServer, &b"GET / HTTP/1.1\r\n\r\n"[..]),
+                (Direction::ServerToClient, &b"HTTP/1.1 200 OK\r\n\r\n"[..]),
+            ]
+        );
+    }
+
+    #[test]
+    fn retention_stops_at_the_per_direction_cap() {
+        let mut reassembler = TcpReassembler::new().with_retention(true);
+        handshake(&mut reassembler, 40000);
+
+        let chunk = vec![0xaa; 256 * 1024];
+        let mut offset = 0;
+        while offset as usize <= MAX_STREAM_BYTES {
+            let (_, delivered) = client_data(&mut reassembler, offset, &chunk);
+            assert_eq!(delivered.len(), chunk.len());
+            offset += chunk.len() as u32;
+        }
+
+        let half = &reassembler.streams[&key(40000)].client_to_server;
+        assert_eq!(half.data.len(), MAX_STREAM_BYTES);
+        assert!(half.truncated);
+    }
+
+    #[test]
+    fn in_order_traffic_does_not_count_against_the_memory_budget() {
+        let mut reassembler = TcpReassembler::new();
+        let chunk = vec![0; 1024 * 1024];
+        let streams = (MAX_TOTAL_BYTES / MAX_STREAM_BYTES) as u16 + 8;
+        for port in 0..streams {
+            handshake(&mut reassembler, 40000 + port);
+            for index in 0..2 {
+                let seq = CLIENT_ISN + 1 + index * chunk.len() as u32;
+                reassembler.push(segment(40000 + port, true, seq, ACK, &chunk));
+            }
+        }
+
+        assert_eq!(reassembler.streams.len(), streams as usize);
+        assert_eq!(reassembler.buffered, 0);
+    }
+
+    #[test]
+    fn out_of_order_buffers_are_bounded_across_streams() {
+        let mut reassembler = TcpReassembler::new();
+        let chunk = vec![0; MAX_OUT_OF_ORDER_BYTES];
+        let streams = (MAX_TOTAL_BYTES / MAX_OUT_OF_ORDER_BYTES) as u16 + 8;
+        for port in 0..streams {
+            handshake(&mut reassembler, 40000 + port);
+            let mut data = segment(40000 + port, true, CLIENT_ISN + 2, ACK, &chunk);
+            // Later streams are more recently active, so the earliest ones are evicted first
+            data.timestamp += Duration::from_millis(port as u64);
+            reassembler.push(data);
+            assert!(reassembler.buffered <= MAX_TOTAL_BYTES);
+        }
+
+        assert!(reassembler.streams.len() < streams as usize);
+        assert!(!reassembler.streams.contains_key(&key(40000)));
+        assert!(reassembler.streams.contains_key(&key(40000 + streams - 1)));
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::analysis::tcp_reassembly::{Direction, StreamData};
+use crate::packet::{
+    parse_certificate_message, parse_client_hello, parse_server_hello, PacketInfo, PacketType, TlsInfo, TlsTransport,
+};
+
+/// Upper bound on connections with TLS state
+const MAX_CONNECTIONS: usize = 4096;
+/// Largest record allowed by RFC 8446 section 5.2 (2^14 plus expansion)
+const MAX_RECORD_LEN: usize = 16384 + 2048;
+/// Upper bound on a handshake message spread across records
+const MAX_HANDSHAKE_BYTES: usize = 256 * 1024;
+
+const CHANGE_CIPHER_SPEC: u8 = 20;
+const HANDSHAKE: u8 = 22;
+
+#[derive(Debug, Default)]
+struct HalfState {
+    /// Bytes of an incomplete record
+    buffer: Vec<u8>,
+    /// Bytes of the current record still to be skipped
+    skip: usize,
+    /// Handshake message bytes collected from consecutive records
+    handshake: Vec<u8>,
+    /// Handshake records after ChangeCipherSpec are encrypted
+    encrypted: bool,
+}
+
+#[derive(Debug, Default)]
+struct Connection {
+    halves: HashMap<Direction, HalfState>,
+    /// A HelloRetryRequest was seen. TLS 1.3 middlebox compatibility mode sends
+    /// ChangeCipherSpec around it, so CCS no longer means the direction is encrypted.
+    hello_retry: bool,
+    /// Nothing more to learn, or the stream turned out not to be TLS
+    done: bool,
+    last_seen: Duration,
+}
+
+/// Splits reassembled TCP streams into TLS records and decodes the cleartext
+/// handshake messages: ClientHello, ServerHello and, before TLS 1.3, Certificate.
+#[derive(Debug, Default)]
+pub struct TlsStreamDecoder {
+    connections: HashMap<u64, Connection>,
+}
+
+impl TlsStreamDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds stream bytes delivered by `info`. When they complete handshake
+    /// messages, the packet is marked as TLS and carries them.
+    pub fn observe(&mut self, info: &mut PacketInfo, data: &StreamData) {
+        if !self.connections.contains_key(&data.stream_id) {
+            if !looks_like_handshake(&data.data) {
+                return;
+            }
+            if self.connections.len() >= MAX_CONNECTIONS {
+                self.evict_oldest();
+            }
+        }
+        let connection = self.connections.entry(data.stream_id).or_default();
+        connection.last_seen = info.timestamp;
+
+        let messages = connection.feed(data.direction, &data.data);
+        if connection.done {
+            self.connections.remove(&data.stream_id);
+        }
+        if !messages.is_empty() {
+            info.packet_type = PacketType::TLS;
+            info.tls = messages;
+        }
+    }
+
+    /// Forgets the least recently active connection to make room for a new one.
+    fn evict_oldest(&mut self) {
+        let oldest = self
+            .connections
+            .iter()
+            .min_by_key(|(_, connection)| connection.last_seen)
+            .map(|(&id, _)| id);
+        if let Some(id) = oldest {
+            self.connections.remove(&id);
+        }
+    }
+}
+
+/// A handshake record header: content type 22 and a 3.x record version.
+fn looks_like_handshake(data: &[u8]) -> bool {
+    matches!(data, [HANDSHAKE, 3, 0..=4, ..])
+}
+
+impl Connection {
+    fn feed(&mut self, direction: Direction, data: &[u8]) -> Vec<TlsInfo> {
+        let mut half = self.halves.remove(&direction).unwrap_or_default();
+        let mut data = data;
+        let mut messages = Vec::new();
+
+        // Finish skipping a record that started in an earlier segment
+        let skipped = half.skip.min(data.len());
+        half.skip -= skipped;
+        data = &data[skipped..];
+        half.buffer.extend_from_slice(data);
+
+        while half.buffer.len() >= 5 {
+            let content_type = half.buffer[0];
+            let len = u16::from_be_bytes([half.buffer[3], half.buffer[4]]) as usize;
+            if !(20..=24).contains(&content_type) || half.buffer[1] != 3 || len > MAX_RECORD_LEN {
+                // Lost record sync
+                self.done = true;
+                break;
+            }
+
+            if content_type != HANDSHAKE || half.encrypted {
+                if content_type == CHANGE_CIPHER_SPEC && !self.hello_retry {
+                    half.encrypted = true;
+                }
+                let available = half.buffer.len().min(5 + len);
+                half.buffer.drain(..available);
+                half.skip = 5 + len - available;
+                continue;
+            }
+
+            if half.buffer.len() < 5 + len {
+                break;
+            }
+            half.handshake.extend_from_slice(&half.buffer[5..5 + len]);
+            half.buffer.drain(..5 + len);
+
+            while half.handshake.len() >= 4 {
+                let msg_type = half.handshake[0];
+                let msg_len = u32::from_be_bytes([0, half.handshake[1], half.handshake[2], half.handshake[3]]) as usize;
+                if half.handshake.len() < 4 + msg_len {
+                    break;
+                }
+                let body = &half.handshake[4..4 + msg_len];
+                match msg_type {
+                    1 => messages.extend(parse_client_hello(body, TlsTransport::Tcp).ok().map(TlsInfo::ClientHello)),
+                    2 => {
+                        if let Ok(hello) = parse_server_hello(body) {
+                            // TLS 1.3 encrypts everything after the ServerHello, but a
+                            // HelloRetryRequest is followed by another cleartext ClientHello
+                            if hello.hello_retry_request {
+                                self.hello_retry = true;
+                            } else if hello.version() == 0x0304 {
+                                self.done = true;
+                            }
+                            messages.push(TlsInfo::ServerHello(hello));
+                        }
+                    }
+                    11 => messages.extend(parse_certificate_message(body).ok().map(TlsInfo::Certificate)),
+                    _ => {}
+                }
+                half.handshake.drain(..4 + msg_len);
+            }
+            if half.handshake.len() > MAX_HANDSHAKE_BYTES {
+                self.done = true;
+            }
+            if self.done {
+                break;
+            }
+        }
+
+        if self.done {
+            self.halves.clear();
+        } else {
+            self.halves.insert(direction, half);
+            self.done = self.halves.len() == 2 && self.halves.values().all(|half| half.encrypted);
+        }
+        messages
+    }
+}
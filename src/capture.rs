@@ -1,4 +1,12 @@
-use pcap::{Capture, Device};
+use pcap::{Capture, Device, Precision};
+
+/// Timestamp precision of live captures. Microseconds is the libpcap default and
+/// the only resolution every platform supports.
+pub const DEVICE_PRECISION: Precision = Precision::Micro;
+
+/// Timestamp precision requested when reading capture files. libpcap scales
+/// microsecond files up, so nanosecond files keep their full resolution.
+pub const FILE_PRECISION: Precision = Precision::Nano;
 
 /// Opens a packet capture session on the given device with non-blocking mode enabled.
 ///
@@ -12,3 +20,15 @@ pub fn open_device_capture(device: &Device) -> Result<Capture<pcap::Active>, pca
     let cap = cap.setnonblock()?;  // Enable non-blocking mode by taking ownership properly
     Ok(cap)
 }
+
+/// Opens a saved capture file (pcap or pcapng) for offline analysis.
+/// Timestamps are delivered with `FILE_PRECISION`.
+///
+/// # Arguments
+/// * `path` - Path to the capture file on disk
+///
+/// # Returns
+/// * A `Capture<pcap::Offline>` object on success, or a `pcap::Error` on failure
+pub fn open_file_capture(path: &str) -> Result<Capture<pcap::Offline>, pcap::Error> {
+    Capture::from_file_with_precision(path, FILE_PRECISION)
+}
@@ -0,0 +1,79 @@
+use clap::{Parser, ValueEnum};
+
+/// Sniffy - Rust packet sniffer
+#[derive(Parser, Debug)]
+#[command(name = "sniffy", about = "Rust packet sniffer with optional PCAP export")]
+pub struct CliArgs {
+    /// Enable PCAP export and specify output file (optional).
+    /// If no filename is provided, defaults to 'capture.pcap'.
+    #[arg(long, value_name = "FILE", num_args = 0..=1, default_missing_value = "capture.pcap")]
+    pub export: Option<String>,
+
+    /// Run the full-screen terminal UI instead of the GUI.
+    #[arg(long, conflicts_with = "read")]
+    pub tui: bool,
+
+    /// Capture on this interface (headless, or preselected in the TUI).
+    #[arg(short, long, value_name = "NAME", conflicts_with = "read")]
+    pub interface: Option<String>,
+
+    /// Analyze an existing capture file instead of opening the GUI.
+    #[arg(short, long, value_name = "FILE")]
+    pub read: Option<String>,
+
+    /// BPF filter applied to the capture.
+    #[arg(short, long, value_name = "EXPR")]
+    pub filter: Option<String>,
+
+    /// Stop after this many packets have been processed.
+    #[arg(short, long, value_name = "N")]
+    pub count: Option<usize>,
+
+    /// Stop after capturing for this many seconds.
+    #[arg(short, long, value_name = "SECS")]
+    pub duration: Option<u64>,
+
+    /// Output format for per-packet lines written to stdout.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    /// Suppress per-packet output and only print the summary.
+    #[arg(short, long)]
+    pub quiet: bool,
+
+    /// Also decode traffic on this port as DNS (may be repeated).
+    /// Ports 53, 5353 (mDNS) and 5355 (LLMNR) are always decoded.
+    #[arg(long = "dns-port", value_name = "PORT")]
+    pub dns_ports: Vec<u16>,
+
+    /// Decode TCP traffic on this port as cleartext HTTP/2 (h2c), even when the
+    /// connection preface was not captured (may be repeated).
+    #[arg(long = "h2c-port", value_name = "PORT")]
+    pub h2c_ports: Vec<u16>,
+
+    /// Write the DER encoding of every TLS certificate observed into this directory.
+    #[arg(long, value_name = "DIR")]
+    pub cert_dir: Option<String>,
+
+    /// List available capture interfaces and exit.
+    #[arg(long)]
+    pub list_interfaces: bool,
+}
+
+impl CliArgs {
+    /// Headless mode runs without a display: either a live interface or a capture file was given.
+    pub fn is_headless(&self) -> bool {
+        !self.tui && (self.interface.is_some() || self.read.is_some())
+    }
+}
+
+/// Per-packet output format for headless mode.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human readable, one line per packet
+    Text,
+    /// JSON Lines, one object per packet
+    Json,
+    /// Comma separated values with a header row
+    Csv,
+}
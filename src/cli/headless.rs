@@ -0,0 +1,225 @@
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use pcap::{Capture, Precision};
+
+use crate::analysis::certificates::{unix_now, CertificateInventory};
+use crate::analysis::dns_stats::DnsStats;
+use crate::capture::{DEVICE_PRECISION, FILE_PRECISION};
+use crate::cli::args::CliArgs;
+use crate::cli::output::{format_packet, header};
+use crate::core::capture_loop::{
+    apply_bpf_filter, get_available_devices, open_offline_capture, reinitialize_capture,
+};
+use crate::core::runner::{run_packet_loop, setup_savefile};
+use crate::core::signal::setup_ctrlc_handler;
+use crate::packet::{format_unix_time, rcode_name, PacketInfo, PacketType};
+
+/// Prints the available capture interfaces, one per line.
+pub fn list_interfaces() -> Result<(), String> {
+    for device in get_available_devices()? {
+        match &device.desc {
+            Some(desc) => println!("{}\t{}", device.name, desc),
+            None => println!("{}", device.name),
+        }
+    }
+    Ok(())
+}
+
+/// Runs a capture without a display, either live on `--interface` or from a `--read` file.
+/// Packets are written to stdout in the requested format and a summary to stderr.
+pub fn run_headless(args: &CliArgs) -> Result<(), String> {
+    if let Some(path) = &args.read {
+        run_capture(open_offline_capture(path)?, FILE_PRECISION, args)
+    } else if let Some(device_name) = &args.interface {
+        run_capture(reinitialize_capture(device_name)?, DEVICE_PRECISION, args)
+    } else {
+        Err("Headless mode needs --interface or --read".into())
+    }
+} // run_headless
+
+fn run_capture<T: pcap::Activated + ?Sized>(
+    mut cap: Capture<T>,
+    precision: Precision,
+    args: &CliArgs,
+) -> Result<(), String> {
+    let running = Arc::new(AtomicBool::new(true));
+    let packet_counts = Arc::new(Mutex::new(HashMap::new()));
+    let (tx, rx) = mpsc::channel::<PacketInfo>();
+
+    setup_ctrlc_handler(running.clone());
+
+    apply_bpf_filter(&mut cap, args.filter.as_deref().unwrap_or_default())?;
+    let cap = Arc::new(Mutex::new(cap));
+
+    let savefile = args.export.as_deref().and_then(|filename| {
+        setup_savefile(&cap, filename)
+    });
+
+    if let Some(secs) = args.duration {
+        let running = running.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_secs(secs));
+            running.store(false, Ordering::SeqCst);
+        });
+    }
+
+    let format = args.format;
+    let quiet = args.quiet;
+    let printer = thread::spawn(move || {
+        let mut out = io::stdout().lock();
+        if !quiet && let Some(line) = header(format) {
+            let _ = writeln!(out, "{}", line);
+        }
+
+        let mut dns_stats = DnsStats::new();
+        let mut certificates = CertificateInventory::new();
+        let mut printing = !quiet;
+        for packet in rx {
+            dns_stats.observe(&packet);
+            certificates.observe(&packet);
+
+            // Stop printing (but keep draining) once stdout is gone, e.g. piped into `head`
+            if printing && writeln!(out, "{}", format_packet(&packet, format)).is_err() {
+                printing = false;
+            }
+        }
+        (dns_stats, certificates)
+    });
+
+    run_packet_loop(
+        running,
+        cap,
+        precision,
+        savefile,
+        packet_counts.clone(),
+        false,
+        args.count,
+        Some(tx),
+    )
+    .map_err(|e| format!("Packet loop error: {}", e))?;
+
+    // The sender was moved into the loop and dropped on return, so the printer drains and exits
+    let (dns_stats, certificates) = printer.join().unwrap_or_default();
+
+    print_summary(&packet_counts);
+    print_dns_summary(&dns_stats);
+    print_certificate_summary(&certificates);
+
+    if let Some(dir) = &args.cert_dir {
+        let written = certificates
+            .export_der(dir.as_ref())
+            .map_err(|e| format!("Failed to export certificates to {}: {}", dir, e))?;
+        eprintln!("Wrote {} certificates to {}", written, dir);
+    }
+    Ok(())
+} // run_capture
+
+fn print_summary(packet_counts: &Arc<Mutex<HashMap<PacketType, usize>>>) {
+    let Ok(counts) = packet_counts.lock() else {
+        return;
+    };
+
+    let mut entries: Vec<_> = counts.iter().collect();
+    entries.sort_by(|a, b| b.1.cmp(a.1));
+
+    let total: usize = counts.values().sum();
+    eprintln!();
+    eprintln!("Captured {} packets", total);
+    for (ptype, count) in entries {
+        eprintln!("  {:<24} {}", ptype.to_string(), count);
+    }
+}
+
+fn print_dns_summary(stats: &DnsStats) {
+    if stats.queries == 0 && stats.responses == 0 {
+        return;
+    }
+
+    eprintln!();
+    eprintln!(
+        "DNS: {} queries, {} responses ({} matched, {} unmatched), {} timed out, {} outstanding",
+        stats.queries,
+        stats.responses,
+        stats.matched,
+        stats.unmatched_responses,
+        stats.timeouts,
+        stats.outstanding()
+    );
+
+    if let Some(latency) = stats.latency() {
+        eprintln!(
+            "  Latency: mean {:.1} ms, median {:.1} ms, p95 {:.1} ms, max {:.1} ms",
+            millis(latency.mean),
+            millis(latency.median),
+            millis(latency.p95),
+            millis(latency.max)
+        );
+    }
+
+    eprintln!(
+        "  NXDOMAIN {:.1}%, SERVFAIL {:.1}%",
+        stats.rcode_rate(3),
+        stats.rcode_rate(2)
+    );
+    for (rcode, count) in stats.rcodes() {
+        eprintln!("    {:<22} {}", rcode_name(rcode), count);
+    }
+
+    eprintln!("  Top domains:");
+    for (domain, count) in stats.top_domains(10) {
+        eprintln!("    {:<40} {}", domain, count);
+    }
+
+    eprintln!("  Top clients:");
+    for (client, count) in stats.top_clients(10) {
+        eprintln!("    {:<40} {}", client, count);
+    }
+
+    eprintln!("  Resolvers:");
+    for server in stats.servers() {
+        let mean = server
+            .mean_latency()
+            .map(|mean| format!("{:.1} ms", millis(mean)))
+            .unwrap_or_else(|| "-".into());
+        eprintln!(
+            "    {:<40} {} queries, {} responses, {} timeouts, {} failures, mean {}",
+            server.address, server.queries, server.responses, server.timeouts, server.failures, mean
+        );
+    }
+}
+
+fn print_certificate_summary(inventory: &CertificateInventory) {
+    if inventory.len() == 0 {
+        return;
+    }
+
+    let now = unix_now();
+    eprintln!();
+    eprintln!("TLS certificates: {}", inventory.len());
+    for observed in inventory.certificates() {
+        let cert = &observed.certificate;
+        let warnings = observed.warnings(now);
+        eprintln!(
+            "  {:<24} [{}] {} (expires {}, {}){}",
+            observed.server,
+            observed.chain_position,
+            cert.common_name(),
+            format_unix_time(cert.not_after),
+            cert.key_type,
+            if warnings.is_empty() { String::new() } else { format!(" {}", warnings.join(", ")) }
+        );
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
@@ -0,0 +1,3 @@
+pub mod args;
+pub mod headless;
+pub mod output;
\ No newline at end of file
@@ -0,0 +1,494 @@
+use crate::cli::args::OutputFormat;
+use crate::packet::{
+    format_duid, format_mac, format_timestamp, rcode_name, type_name, DnsRecord, HttpStartLine, PacketInfo,
+    format_unix_time, grpc_call, Http2FrameKind, TlsInfo,
+};
+
+/// Returns the header line for formats that have one.
+pub fn header(format: OutputFormat) -> Option<String> {
+    match format {
+        OutputFormat::Csv => Some(
+            "timestamp,caplen,len,type,src_mac,dst_mac,vlan,src_ip,src_port,dst_ip,dst_port,tcp_flags,dns_queries,info"
+                .into(),
+        ),
+        OutputFormat::Text | OutputFormat::Json => None,
+    }
+}
+
+/// Formats a single packet in the requested output format.
+pub fn format_packet(packet: &PacketInfo, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Text => format_text(packet),
+        OutputFormat::Json => format_json(packet),
+        OutputFormat::Csv => format_csv(packet),
+    }
+}
+
+fn format_text(packet: &PacketInfo) -> String {
+    let src = packet.src_ip.as_deref().unwrap_or("-");
+    let dst = packet.dst_ip.as_deref().unwrap_or("-");
+    let sport = packet.src_port.map(|p| p.to_string()).unwrap_or_else(|| "-".into());
+    let dport = packet.dst_port.map(|p| p.to_string()).unwrap_or_else(|| "-".into());
+
+    let mut line = format!(
+        "{} {:>5} {:<10} {}:{} -> {}:{}",
+        format_timestamp(packet.timestamp),
+        packet.orig_len,
+        packet.packet_type.to_string(),
+        src,
+        sport,
+        dst,
+        dport
+    );
+    if !packet.vlan_tags.is_empty() {
+        line.push_str(&format!(" vlan {}", packet.vlan_label()));
+    }
+    let summary = packet.summary();
+    if !summary.is_empty() {
+        line.push(' ');
+        line.push_str(&summary);
+    }
+    line
+}
+
+fn format_json(packet: &PacketInfo) -> String {
+    let mut fields = vec![
+        format!("\"timestamp\":{}", epoch_seconds(packet)),
+        format!("\"caplen\":{}", packet.caplen),
+        format!("\"len\":{}", packet.orig_len),
+        format!("\"type\":{}", json_string(&packet.packet_type.to_string())),
+        format!("\"src_mac\":{}", json_opt_string(packet.src_mac.map(|mac| format_mac(&mac)))),
+        format!("\"dst_mac\":{}", json_opt_string(packet.dst_mac.map(|mac| format_mac(&mac)))),
+        format!(
+            "\"vlan\":[{}]",
+            packet
+                .vlan_tags
+                .iter()
+                .map(|tag| format!("{{\"tpid\":{},\"id\":{},\"pcp\":{},\"dei\":{}}}", tag.tpid, tag.id, tag.pcp, tag.dei))
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+        format!("\"src_ip\":{}", json_opt_string(packet.src_ip.clone())),
+        format!("\"src_port\":{}", json_opt_number(packet.src_port)),
+        format!("\"dst_ip\":{}", json_opt_string(packet.dst_ip.clone())),
+        format!("\"dst_port\":{}", json_opt_number(packet.dst_port)),
+        format!(
+            "\"ipv6_ext_headers\":[{}]",
+            packet
+                .ipv6_ext_headers
+                .iter()
+                .map(|header| json_string(&header.to_string()))
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+        format!("\"tcp_flags\":{}", json_opt_string(packet.tcp_flags.map(|f| f.to_string()))),
+    ];
+
+    let queries = match &packet.dns_queries {
+        Some(queries) => format!(
+            "[{}]",
+            queries.iter().map(|q| json_string(q)).collect::<Vec<_>>().join(",")
+        ),
+        None => "null".into(),
+    };
+    fields.push(format!("\"dns_queries\":{}", queries));
+
+    let dns = packet.dns.iter().map(|dns| {
+        format!(
+            "{{\"protocol\":{},\"id\":{},\"response\":{},\"opcode\":{},\"rcode\":{},\"truncated\":{},\"questions\":[{}],\"answers\":[{}],\"authorities\":[{}],\"additionals\":[{}],\"edns\":{}}}",
+            json_string(&dns.protocol.to_string()),
+            dns.id,
+            dns.is_response,
+            dns.opcode,
+            json_string(&rcode_name(dns.rcode)),
+            dns.truncated,
+            dns.questions
+                .iter()
+                .map(|q| format!(
+                    "{{\"name\":{},\"type\":{},\"class\":{}}}",
+                    json_string(&q.name),
+                    json_string(&type_name(q.qtype)),
+                    q.qclass
+                ))
+                .collect::<Vec<_>>()
+                .join(","),
+            json_records(&dns.answers),
+            json_records(&dns.authorities),
+            json_records(&dns.additionals),
+            json_opt_string(dns.edns.as_ref().map(|edns| edns.to_string())),
+        )
+    });
+    fields.push(format!("\"dns\":[{}]", dns.collect::<Vec<_>>().join(",")));
+
+    let ipv4 = match &packet.ipv4 {
+        Some(header) => format!(
+            "{{\"version\":{},\"header_len\":{},\"dscp\":{},\"ecn\":{},\"total_length\":{},\"id\":{},\"df\":{},\"mf\":{},\"fragment_offset\":{},\"ttl\":{},\"protocol\":{},\"checksum\":{},\"checksum_valid\":{},\"options\":[{}]}}",
+            header.version,
+            header.header_len,
+            header.dscp,
+            header.ecn,
+            header.total_length,
+            header.identification,
+            header.dont_fragment,
+            header.more_fragments,
+            header.fragment_offset,
+            header.ttl,
+            header.protocol,
+            header.checksum,
+            header.checksum_valid,
+            header
+                .options
+                .iter()
+                .map(|option| json_string(&option.name()))
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        None => "null".into(),
+    };
+    fields.push(format!("\"ipv4\":{}", ipv4));
+
+    let tcp = match &packet.tcp {
+        Some(tcp) => format!(
+            "{{\"seq\":{},\"ack\":{},\"data_offset\":{},\"window\":{},\"checksum\":{},\"urgent_pointer\":{},\"payload_len\":{},\"options\":[{}]}}",
+            tcp.seq,
+            tcp.ack,
+            tcp.data_offset,
+            tcp.window,
+            tcp.checksum,
+            tcp.urgent_pointer,
+            tcp.payload_len,
+            tcp.options
+                .iter()
+                .map(|option| json_string(&option.to_string()))
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        None => "null".into(),
+    };
+    fields.push(format!("\"tcp\":{}", tcp));
+
+    let fragment = match &packet.fragment {
+        Some(fragment) => format!(
+            "{{\"id\":{},\"offset\":{},\"more_fragments\":{},\"protocol\":{},\"reassembled_length\":{}}}",
+            fragment.identification,
+            fragment.offset,
+            fragment.more_fragments,
+            fragment.protocol,
+            json_opt_number(fragment.reassembled.map(|r| r.length)),
+        ),
+        None => "null".into(),
+    };
+    fields.push(format!("\"fragment\":{}", fragment));
+
+    let dhcp = match &packet.dhcp {
+        Some(dhcp) => {
+            let addresses = |list: &[std::net::Ipv4Addr]| {
+                list.iter().map(|a| json_string(&a.to_string())).collect::<Vec<_>>().join(",")
+            };
+            format!(
+                "{{\"op\":{},\"xid\":{},\"message_type\":{},\"client_mac\":{},\"ciaddr\":{},\"yiaddr\":{},\"giaddr\":{},\"requested_ip\":{},\"server_id\":{},\"lease_time\":{},\"subnet_mask\":{},\"routers\":[{}],\"dns_servers\":[{}],\"hostname\":{},\"client_id\":{},\"vendor_class\":{}}}",
+                dhcp.op,
+                dhcp.xid,
+                json_opt_string(dhcp.message_type.map(|t| t.to_string())),
+                json_opt_string(dhcp.client_mac.as_ref().map(format_mac)),
+                json_string(&dhcp.ciaddr.to_string()),
+                json_string(&dhcp.yiaddr.to_string()),
+                json_string(&dhcp.giaddr.to_string()),
+                json_opt_string(dhcp.requested_ip.map(|ip| ip.to_string())),
+                json_opt_string(dhcp.server_id.map(|ip| ip.to_string())),
+                json_opt_number(dhcp.lease_time),
+                json_opt_string(dhcp.subnet_mask.map(|ip| ip.to_string())),
+                addresses(&dhcp.routers),
+                addresses(&dhcp.dns_servers),
+                json_opt_string(dhcp.hostname.clone()),
+                json_opt_string(dhcp.client_id.as_ref().map(|id| id.iter().map(|b| format!("{:02x}", b)).collect())),
+                json_opt_string(dhcp.vendor_class.clone()),
+            )
+        }
+        None => "null".into(),
+    };
+    fields.push(format!("\"dhcp\":{}", dhcp));
+
+    let dhcpv6 = match &packet.dhcpv6 {
+        Some(dhcpv6) => {
+            let list = |items: Vec<String>| items.iter().map(|item| json_string(item)).collect::<Vec<_>>().join(",");
+            format!(
+                "{{\"message_type\":{},\"transaction_id\":{},\"relay_hops\":{},\"client_duid\":{},\"server_duid\":{},\"addresses\":[{}],\"prefixes\":[{}],\"dns_servers\":[{}],\"domain_list\":[{}],\"status_code\":{}}}",
+                json_string(&dhcpv6.message_type.to_string()),
+                dhcpv6.transaction_id,
+                dhcpv6.relay_hops,
+                json_opt_string(dhcpv6.client_duid.as_deref().map(format_duid)),
+                json_opt_string(dhcpv6.server_duid.as_deref().map(format_duid)),
+                list(dhcpv6.addresses().map(|a| a.address.to_string()).collect()),
+                list(dhcpv6.prefixes().map(|p| p.to_string()).collect()),
+                list(dhcpv6.dns_servers.iter().map(|a| a.to_string()).collect()),
+                list(dhcpv6.domain_list.clone()),
+                json_opt_number(dhcpv6.status.as_ref().map(|(code, _)| *code)),
+            )
+        }
+        None => "null".into(),
+    };
+    fields.push(format!("\"dhcpv6\":{}", dhcpv6));
+
+    let http = packet.http.iter().map(|http| {
+        format!(
+            "{{\"method\":{},\"uri\":{},\"status\":{},\"version\":{},\"host\":{},\"content_type\":{},\"content_length\":{},\"user_agent\":{},\"keep_alive\":{},\"pipelined\":{},\"sequence\":{}}}",
+            json_opt_string(http.method().map(str::to_string)),
+            json_opt_string(match &http.start {
+                HttpStartLine::Request { uri, .. } => Some(uri.clone()),
+                HttpStartLine::Response { .. } => http.request.as_ref().map(|(_, uri)| uri.clone()),
+            }),
+            json_opt_number(http.status()),
+            json_string(&http.version),
+            json_opt_string(http.host.clone()),
+            json_opt_string(http.content_type.clone()),
+            json_opt_number(http.content_length),
+            json_opt_string(http.user_agent.clone()),
+            http.keep_alive,
+            http.pipelined,
+            http.sequence,
+        )
+    });
+    fields.push(format!("\"http\":[{}]", http.collect::<Vec<_>>().join(",")));
+
+    let http2 = packet.http2.iter().map(|frame| {
+        let headers = match frame.headers() {
+            Some(headers) => format!(
+                "[{}]",
+                headers
+                    .iter()
+                    .map(|(name, value)| format!("{{\"name\":{},\"value\":{}}}", json_string(name), json_string(value)))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+            None => "null".into(),
+        };
+        let error_code = match frame.kind {
+            Http2FrameKind::RstStream { error_code } | Http2FrameKind::GoAway { error_code, .. } => Some(error_code),
+            _ => None,
+        };
+        format!(
+            "{{\"type\":{},\"stream_id\":{},\"flags\":{},\"length\":{},\"end_stream\":{},\"headers\":{},\"grpc\":{},\"error_code\":{}}}",
+            json_string(&frame.type_name()),
+            frame.stream_id,
+            frame.flags,
+            frame.length,
+            frame.end_stream(),
+            headers,
+            json_opt_string(frame.headers().and_then(grpc_call).map(|(service, method)| format!("{}/{}", service, method))),
+            json_opt_number(error_code),
+        )
+    });
+    fields.push(format!("\"http2\":[{}]", http2.collect::<Vec<_>>().join(",")));
+
+    let tls = packet.tls.iter().map(|message| match message {
+        TlsInfo::ClientHello(hello) => {
+            let numbers = |values: &[u16]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(",");
+            format!(
+                "{{\"handshake\":\"client_hello\",\"version\":{},\"sni\":{},\"alpn\":[{}],\"cipher_suites\":[{}],\"supported_groups\":[{}],\"signature_algorithms\":[{}],\"supported_versions\":[{}],\"ja3\":{},\"ja3_hash\":{},\"ja4\":{}}}",
+                hello.legacy_version,
+                json_opt_string(hello.sni.clone()),
+                hello.alpn.iter().map(|p| json_string(p)).collect::<Vec<_>>().join(","),
+                numbers(&hello.cipher_suites),
+                numbers(&hello.supported_groups),
+                numbers(&hello.signature_algorithms),
+                numbers(&hello.supported_versions),
+                json_string(&hello.ja3),
+                json_string(&hello.ja3_hash),
+                json_string(&hello.ja4),
+            )
+        }
+        TlsInfo::ServerHello(hello) => format!(
+            "{{\"handshake\":{},\"version\":{},\"cipher_suite\":{},\"alpn\":{},\"ja3s\":{},\"ja3s_hash\":{}}}",
+            json_string(if hello.hello_retry_request { "hello_retry_request" } else { "server_hello" }),
+            hello.version(),
+            hello.cipher_suite,
+            json_opt_string(hello.alpn.clone()),
+            json_string(&hello.ja3s),
+            json_string(&hello.ja3s_hash),
+        ),
+        TlsInfo::Certificate(chain) => format!(
+            "{{\"handshake\":\"certificate\",\"certificates\":[{}]}}",
+            chain
+                .iter()
+                .map(|cert| format!(
+                    "{{\"subject\":{},\"issuer\":{},\"serial\":{},\"not_before\":{},\"not_after\":{},\"subject_alt_names\":[{}],\"key_type\":{},\"self_signed\":{},\"sha256\":{}}}",
+                    json_string(&cert.subject),
+                    json_string(&cert.issuer),
+                    json_string(&cert.serial),
+                    json_string(&format_unix_time(cert.not_before)),
+                    json_string(&format_unix_time(cert.not_after)),
+                    cert.subject_alt_names.iter().map(|name| json_string(name)).collect::<Vec<_>>().join(","),
+                    json_string(&cert.key_type),
+                    cert.self_signed,
+                    json_string(&cert.fingerprint),
+                ))
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+    });
+    fields.push(format!("\"tls\":[{}]", tls.collect::<Vec<_>>().join(",")));
+
+    let quic = match &packet.quic {
+        Some(quic) => {
+            let hex = |cid: &Option<Vec<u8>>| json_opt_string(cid.as_ref().map(|cid| cid.iter().map(|b| format!("{:02x}", b)).collect()));
+            let packets: Vec<_> = quic
+                .packets
+                .iter()
+                .map(|packet| {
+                    format!(
+                        "{{\"type\":{},\"version\":{},\"dcid\":{},\"scid\":{},\"decrypted\":{}}}",
+                        json_string(&packet.packet_type.to_string()),
+                        json_opt_number(packet.version),
+                        hex(&packet.dcid),
+                        hex(&packet.scid),
+                        packet.decrypted,
+                    )
+                })
+                .collect();
+            format!("{{\"packets\":[{}]}}", packets.join(","))
+        }
+        None => "null".into(),
+    };
+    fields.push(format!("\"quic\":{}", quic));
+
+    let arp = match &packet.arp {
+        Some(arp) => format!(
+            "{{\"operation\":{},\"sender_mac\":{},\"sender_ip\":{},\"target_mac\":{},\"target_ip\":{},\"gratuitous\":{},\"probe\":{}}}",
+            json_string(&format!("{:?}", arp.operation)),
+            json_string(&format_mac(&arp.sender_mac)),
+            json_string(&arp.sender_ip.to_string()),
+            json_string(&format_mac(&arp.target_mac)),
+            json_string(&arp.target_ip.to_string()),
+            arp.is_gratuitous(),
+            arp.is_probe(),
+        ),
+        None => "null".into(),
+    };
+    fields.push(format!("\"arp\":{}", arp));
+
+    let icmp = match &packet.icmp {
+        Some(icmp) => {
+            let quoted = match &icmp.quoted {
+                Some(q) => format!(
+                    "{{\"src_ip\":{},\"dst_ip\":{},\"protocol\":{},\"src_port\":{},\"dst_port\":{}}}",
+                    json_string(&q.src_ip),
+                    json_string(&q.dst_ip),
+                    q.protocol,
+                    json_opt_number(q.src_port),
+                    json_opt_number(q.dst_port),
+                ),
+                None => "null".into(),
+            };
+            format!(
+                "{{\"type\":{},\"code\":{},\"description\":{},\"id\":{},\"seq\":{},\"rtt_ms\":{},\"quoted\":{}}}",
+                icmp.icmp_type,
+                icmp.code,
+                json_string(&icmp.description()),
+                json_opt_number(icmp.echo.map(|e| e.identifier)),
+                json_opt_number(icmp.echo.map(|e| e.sequence)),
+                json_opt_number(icmp.rtt.map(|rtt| rtt.as_secs_f64() * 1000.0)),
+                quoted,
+            )
+        }
+        None => "null".into(),
+    };
+    fields.push(format!("\"icmp\":{}", icmp));
+
+    let icmpv6 = match &packet.icmpv6 {
+        Some(icmpv6) => format!(
+            "{{\"type\":{},\"code\":{},\"description\":{},\"rtt_ms\":{},\"options\":[{}]}}",
+            icmpv6.icmp_type,
+            icmpv6.code,
+            json_string(&icmpv6.description()),
+            json_opt_number(icmpv6.rtt.map(|rtt| rtt.as_secs_f64() * 1000.0)),
+            icmpv6
+                .options
+                .iter()
+                .map(|option| json_string(&option.summary()))
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        None => "null".into(),
+    };
+    fields.push(format!("\"icmpv6\":{}", icmpv6));
+    fields.push(format!("\"info\":{}", json_string(&packet.summary())));
+
+    format!("{{{}}}", fields.join(","))
+}
+
+fn format_csv(packet: &PacketInfo) -> String {
+    let columns = [
+        epoch_seconds(packet),
+        packet.caplen.to_string(),
+        packet.orig_len.to_string(),
+        packet.packet_type.to_string(),
+        packet.src_mac.map(|mac| format_mac(&mac)).unwrap_or_default(),
+        packet.dst_mac.map(|mac| format_mac(&mac)).unwrap_or_default(),
+        packet.vlan_label(),
+        packet.src_ip.clone().unwrap_or_default(),
+        packet.src_port.map(|p| p.to_string()).unwrap_or_default(),
+        packet.dst_ip.clone().unwrap_or_default(),
+        packet.dst_port.map(|p| p.to_string()).unwrap_or_default(),
+        packet.tcp_flags.map(|f| f.to_string()).unwrap_or_default(),
+        packet.dns_queries.as_ref().map(|q| q.join(";")).unwrap_or_default(),
+        packet.summary(),
+    ];
+
+    columns.iter().map(|c| csv_field(c)).collect::<Vec<_>>().join(",")
+}
+
+/// Capture time as fractional seconds since the Unix epoch, keeping nanosecond resolution.
+fn epoch_seconds(packet: &PacketInfo) -> String {
+    format!("{}.{:09}", packet.timestamp.as_secs(), packet.timestamp.subsec_nanos())
+}
+
+fn json_records(records: &[DnsRecord]) -> String {
+    records
+        .iter()
+        .map(|r| {
+            format!(
+                "{{\"name\":{},\"type\":{},\"ttl\":{},\"data\":{}}}",
+                json_string(&r.name),
+                json_string(&type_name(r.rtype)),
+                r.ttl,
+                json_string(&r.data.to_string())
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn json_opt_string(value: Option<String>) -> String {
+    value.map(|v| json_string(&v)).unwrap_or_else(|| "null".into())
+}
+
+fn json_opt_number<N: ToString>(value: Option<N>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_else(|| "null".into())
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
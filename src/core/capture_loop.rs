@@ -1,4 +1,4 @@
-use crate::capture::open_device_capture;
+use crate::capture::{open_device_capture, open_file_capture};
 use pcap::{Capture, Device};
 
 /// Returns a list of available network capture devices.
@@ -19,10 +19,16 @@ pub fn reinitialize_capture(device_name: &str) -> Result<Capture<pcap::Active>,
         .map_err(|e| format!("Failed to open device {}: {}", device.name, e))
 }
 
-/// Applies a BPF filter to the provided active capture session.
+/// Opens a capture file so it can be replayed through the packet loop.
+pub fn open_offline_capture(path: &str) -> Result<Capture<pcap::Offline>, String> {
+    open_file_capture(path)
+        .map_err(|e| format!("Failed to open capture file {}: {}", path, e))
+}
+
+/// Applies a BPF filter to the provided capture session (live or offline).
 /// Returns `Ok(())` on success, or a formatted error string on failure.
-pub fn apply_bpf_filter(
-    cap: &mut Capture<pcap::Active>,
+pub fn apply_bpf_filter<T: pcap::Activated + ?Sized>(
+    cap: &mut Capture<T>,
     filter: &str,
 ) -> Result<(), String> {
     if filter.trim().is_empty() {
@@ -0,0 +1,26 @@
+use std::sync::{mpsc::Sender, Mutex};
+
+use lazy_static::lazy_static;
+
+lazy_static! {
+    static ref SINK: Mutex<Option<Sender<String>>> = Mutex::new(None);
+}
+
+/// Routes messages from the capture thread to `sink` instead of stderr, for front-ends
+/// that own the terminal. `None` restores stderr.
+pub fn set_diagnostics_sink(sink: Option<Sender<String>>) {
+    if let Ok(mut current) = SINK.lock() {
+        *current = sink;
+    }
+}
+
+/// Reports a capture problem on stderr, or to the sink a front-end installed.
+pub fn report(message: String) {
+    if let Ok(sink) = SINK.lock()
+        && let Some(sink) = sink.as_ref()
+    {
+        let _ = sink.send(message);
+        return;
+    }
+    eprintln!("{}", message);
+}
@@ -1,3 +1,4 @@
 pub mod capture_loop;
+pub mod diagnostics;
 pub mod signal;
 pub mod runner;
\ No newline at end of file
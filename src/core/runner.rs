@@ -1,20 +1,30 @@
 use std::{
     collections::HashMap,
     sync::{
-        atomic::Ordering,
+        atomic::{AtomicBool, Ordering},
         mpsc::Sender,
         Arc, Mutex,
     },
-    thread,
-    time,
+    thread::{self, JoinHandle},
+    time::{self, Duration},
 };
 
 use clap::Error;
 use pcap;
-use crate::packet::{parse_packet, PacketType, PacketInfo};
+use crate::analysis::dns_tcp::DnsTcpDecoder;
+use crate::analysis::http2_stream::Http2StreamDecoder;
+use crate::analysis::http_stream::HttpStreamDecoder;
+use crate::analysis::ip_reassembly::FragmentReassembler;
+use crate::analysis::ping::PingTracker;
+use crate::analysis::quic::QuicTracker;
+use crate::analysis::tcp_reassembly::TcpReassembler;
+use crate::analysis::tls_stream::TlsStreamDecoder;
+use crate::core::capture_loop::apply_bpf_filter;
+use crate::core::diagnostics::report;
+use crate::packet::{parse_packet, FrameMeta, PacketType, PacketInfo};
 
-pub fn setup_savefile(
-    cap: &Arc<Mutex<pcap::Capture<pcap::Active>>>,
+pub fn setup_savefile<T: pcap::Activated + ?Sized>(
+    cap: &Arc<Mutex<pcap::Capture<T>>>,
     filename: &str,
 ) -> Option<pcap::Savefile> {
     if filename.trim().is_empty() {
@@ -24,35 +34,110 @@ pub fn setup_savefile(
     match cap.lock() {
         Ok(cap_guard) => match cap_guard.savefile(filename) {
             Ok(sf) => {
-                println!("Exporting packets to {}", filename);
+                report(format!("Exporting packets to {}", filename));
                 Some(sf)
             }
             Err(e) => {
-                eprintln!("Failed to create savefile {}: {}", filename, e);
+                report(format!("Failed to create savefile {}: {}", filename, e));
                 None
             }
         },
         Err(e) => {
-            eprintln!("Failed to lock capture mutex: {}", e);
+            report(format!("Failed to lock capture mutex: {}", e));
             None
         }
     }
 } // setup_savefile
 
-pub fn run_packet_loop(
-    running: Arc<std::sync::atomic::AtomicBool>,
-    cap: Arc<Mutex<pcap::Capture<pcap::Active>>>,
+/// Spawns a background thread that opens a capture with `open_capture`,
+/// applies the BPF filter, sets up the optional savefile and then runs the
+/// packet loop until `running` is cleared or the capture runs out of packets.
+///
+/// Works for both live (`pcap::Active`) and file-backed (`pcap::Offline`) captures.
+/// `precision` must match the timestamp precision the capture was opened with.
+pub fn spawn_capture_thread<T, F>(
+    open_capture: F,
+    precision: pcap::Precision,
+    bpf_filter: String,
+    savefile_name: Option<String>,
+    running: Arc<AtomicBool>,
+    packet_counts: Arc<Mutex<HashMap<PacketType, usize>>>,
+    packet_tx: Option<Sender<PacketInfo>>,
+) -> JoinHandle<()>
+where
+    T: pcap::Activated + ?Sized + 'static,
+    F: FnOnce() -> Result<pcap::Capture<T>, String> + Send + 'static,
+{
+    thread::spawn(move || {
+        let cap = match open_capture() {
+            Ok(c) => Arc::new(Mutex::new(c)),
+            Err(e) => {
+                report(format!("Failed to start capture: {e}"));
+                return;
+            }
+        };
+
+        {
+            let mut cap_guard = cap.lock().unwrap();
+            if let Err(e) = apply_bpf_filter(&mut cap_guard, &bpf_filter) {
+                report(format!("Error applying BPF filter: {e}"));
+            }
+        }
+
+        let savefile = savefile_name.and_then(|filename| {
+            setup_savefile(&cap, &filename)
+        });
+        let debug = false;
+
+        if let Err(e) = run_packet_loop(
+            running,
+            cap,
+            precision,
+            savefile,
+            packet_counts,
+            debug,
+            None,
+            packet_tx,
+        ) {
+            report(format!("Packet loop error: {e}"));
+        }
+    })
+} // spawn_capture_thread
+
+/// Reads packets from the capture until `running` is cleared, `packet_limit`
+/// packets have been read or, for offline captures, the end of the file is reached.
+#[allow(clippy::too_many_arguments)]
+pub fn run_packet_loop<T: pcap::Activated + ?Sized>(
+    running: Arc<AtomicBool>,
+    cap: Arc<Mutex<pcap::Capture<T>>>,
+    precision: pcap::Precision,
     mut savefile: Option<pcap::Savefile>,
     packet_counts: Arc<Mutex<HashMap<PacketType, usize>>>,
     debug_enabled: bool,
-    tx_gui: Option<Sender<PacketInfo>>,
+    packet_limit: Option<usize>,
+    packet_tx: Option<Sender<PacketInfo>>,
 ) -> Result<(), Error> {
+    let mut packets_read = 0usize;
+    let mut ip_reassembler = FragmentReassembler::new();
+    let mut tcp_reassembler = TcpReassembler::new();
+    let mut dns_tcp = DnsTcpDecoder::new();
+    let mut http = HttpStreamDecoder::new();
+    let mut http2 = Http2StreamDecoder::new();
+    let mut tls = TlsStreamDecoder::new();
+    let mut ping_tracker = PingTracker::new();
+    let mut quic_tracker = QuicTracker::new();
+    // The datalink type decides how each frame's link-layer header is decoded
+    let linktype = cap
+        .lock()
+        .map(|guard| guard.get_datalink())
+        .unwrap_or(pcap::Linktype::ETHERNET);
+
     while running.load(Ordering::SeqCst) {
-        let packet_data = {
+        let frame = {
             let mut guard = match cap.lock() {
                 Ok(g) => g,
                 Err(poisoned) => {
-                    eprintln!("⚠️ Failed to lock capture mutex (poisoned): {}", poisoned);
+                    report(format!("⚠️ Failed to lock capture mutex (poisoned): {}", poisoned));
                     thread::sleep(time::Duration::from_millis(100));
                     continue;
                 }
@@ -63,39 +148,74 @@ pub fn run_packet_loop(
                     if let Some(sf) = &mut savefile {
                         sf.write(&packet);
                     }
-                    Some(packet.data.to_vec())
+                    let meta = FrameMeta {
+                        timestamp: header_timestamp(packet.header, precision),
+                        caplen: packet.header.caplen,
+                        orig_len: packet.header.len,
+                    };
+                    Some((meta, packet.data.to_vec()))
                 }
                 Err(pcap::Error::TimeoutExpired) => {
                     thread::yield_now();
                     None
                 }
+                Err(pcap::Error::NoMorePackets) => {
+                    // Offline capture exhausted
+                    break;
+                }
                 Err(e) => {
-                    eprintln!("Capture error: {}", e);
+                    report(format!("Capture error: {}", e));
                     thread::sleep(time::Duration::from_millis(10));
                     None
                 }
             }
         };
 
-        if let Some(data) = packet_data {
-            match parse_packet(&data) {
-                Ok(info) => {
-                    if let Some(gui_sender) = &tx_gui {
-                        let _ = gui_sender.send(info.clone());
+        if let Some((meta, data)) = frame {
+            match parse_packet(linktype, &data, meta) {
+                Ok(mut info) => {
+                    ip_reassembler.observe(&mut info);
+                    if let Some(stream_data) = tcp_reassembler.observe(&mut info) {
+                        dns_tcp.observe(&mut info, &stream_data);
+                        http.observe(&mut info, &stream_data);
+                        http2.observe(&mut info, &stream_data);
+                        tls.observe(&mut info, &stream_data);
+                    }
+                    ping_tracker.observe(&mut info);
+                    quic_tracker.observe(&mut info);
+
+                    if let Some(sender) = &packet_tx {
+                        let _ = sender.send(info.clone());
                     }
 
                     if let Ok(mut counts) = packet_counts.lock() {
                         *counts.entry(info.packet_type.clone()).or_insert(0) += 1;
                     } else {
-                        eprintln!("⚠️ Failed to lock packet counts mutex");
+                        report("⚠️ Failed to lock packet counts mutex".into());
                     }
                 }
                 Err(e) => {
-                    eprintln!("Parse error: {}", e);
+                    report(format!("Parse error: {}", e));
                 }
             }
+
+            packets_read += 1;
+            if packet_limit.is_some_and(|limit| packets_read >= limit) {
+                break;
+            }
         }
     }
 
     Ok(())
+}
+
+/// Converts a pcap record timestamp into a duration since the Unix epoch.
+/// With nanosecond precision libpcap stores nanoseconds in the `tv_usec` field.
+fn header_timestamp(header: &pcap::PacketHeader, precision: pcap::Precision) -> Duration {
+    let secs = header.ts.tv_sec.max(0) as u64;
+    let frac = header.ts.tv_usec.max(0) as u64;
+    match precision {
+        pcap::Precision::Micro => Duration::from_secs(secs) + Duration::from_micros(frac),
+        pcap::Precision::Nano => Duration::from_secs(secs) + Duration::from_nanos(frac),
+    }
 }
\ No newline at end of file
@@ -1,36 +1,69 @@
 use eframe::egui;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     sync::{
         atomic::AtomicBool,
         mpsc::{self, Receiver, Sender},
         Arc, Mutex,
     },
-    thread::{self, JoinHandle},
+    thread::JoinHandle,
 };
 
-use crate::core::capture_loop::{get_available_devices, reinitialize_capture, apply_bpf_filter};
-use crate::core::runner::{setup_savefile, run_packet_loop};
+use crate::analysis::arp_table::ArpTable;
+use crate::analysis::connections::ConnectionTracker;
+use crate::analysis::certificates::CertificateInventory;
+use crate::analysis::dhcp_leases::DhcpLeaseTable;
+use crate::analysis::dhcpv6_leases::Dhcpv6LeaseTable;
+use crate::analysis::dns_stats::DnsStats;
+use crate::analysis::http2_streams::Http2StreamTable;
+use crate::analysis::tcp_reassembly::{follow_stream, TcpStream};
+use crate::capture::{DEVICE_PRECISION, FILE_PRECISION};
+use crate::core::capture_loop::{get_available_devices, reinitialize_capture, open_offline_capture};
+use crate::core::runner::spawn_capture_thread;
 use crate::core::signal::setup_ctrlc_handler;
 use crate::packet::{PacketInfo, PacketType};
 use crate::gui::components::{
+    arp_panel::arp_panel,
+    certificates_panel::certificates_panel,
+    connections_panel::connections_panel,
+    dhcp_panel::dhcp_panel,
+    dhcpv6_panel::dhcpv6_panel,
+    dns_panel::dns_panel,
+    follow_stream::follow_stream_window,
+    http2_panel::http2_panel,
     interface_selector::interface_selector,
     filter_input::filter_input,
     stats_panel::stats_panel,
     packet_log::packet_log,
+    vlan_filter::vlan_filter,
     gui_state::*
 };
 
+/// Maximum number of packets kept in the packet log. Follow-stream views are
+/// rebuilt from these, so older packets of a long connection drop out of them too.
+const MAX_LOG_ENTRIES: usize = 5000;
+
 /// The main GUI app for Sniffy
 pub struct SniffyApp {
     running: Arc<AtomicBool>,
     packet_rx: Receiver<PacketInfo>,
     packet_tx: Sender<PacketInfo>,
     packet_counts: Arc<Mutex<HashMap<PacketType, usize>>>,
-    log: Arc<Mutex<Vec<PacketInfo>>>,
+    log: Arc<Mutex<VecDeque<PacketInfo>>>,
+    arp_table: ArpTable,
+    connections: ConnectionTracker,
+    connection_sort: ConnectionSort,
+    dns_stats: DnsStats,
+    dhcp_leases: DhcpLeaseTable,
+    dhcpv6_leases: Dhcpv6LeaseTable,
+    certificates: CertificateInventory,
+    http2_streams: Http2StreamTable,
+    view: View,
     selected_interface: Option<String>,
     available_interfaces: Vec<String>,
     selected_filter: Option<String>,
+    vlan_filter: String,
+    followed_stream: Option<TcpStream>,
     save_pcap: bool,
     pcap_filename: String,
     capture_state: CaptureState,
@@ -41,7 +74,7 @@ impl SniffyApp {
     pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
         let running = Arc::new(AtomicBool::new(true));
         let packet_counts = Arc::new(Mutex::new(HashMap::new()));
-        let log = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::new(Mutex::new(VecDeque::new()));
         let (tx, rx) = mpsc::channel();
 
         setup_ctrlc_handler(running.clone());
@@ -59,8 +92,19 @@ impl SniffyApp {
             packet_tx: tx,
             packet_counts,
             log,
+            arp_table: ArpTable::new(),
+            connections: ConnectionTracker::new(),
+            connection_sort: ConnectionSort::default(),
+            dns_stats: DnsStats::new(),
+            dhcp_leases: DhcpLeaseTable::new(),
+            dhcpv6_leases: Dhcpv6LeaseTable::new(),
+            certificates: CertificateInventory::new(),
+            http2_streams: Http2StreamTable::new(),
+            view: View::Packets,
             selected_interface: None,
             selected_filter: None,
+            vlan_filter: String::new(),
+            followed_stream: None,
             save_pcap: false,
             pcap_filename: "capture.pcap".into(),
             available_interfaces,
@@ -99,42 +143,52 @@ impl SniffyApp {
             None
         };
 
-        let handle = thread::spawn(move || {
-            let cap = match reinitialize_capture(&device_name) {
-                Ok(c) => Arc::new(Mutex::new(c)),
-                Err(e) => {
-                    eprintln!("Failed to start capture: {e}");
-                    return;
-                }
-            };
+        let handle = spawn_capture_thread(
+            move || reinitialize_capture(&device_name),
+            DEVICE_PRECISION,
+            bpf_filter,
+            savefile_name,
+            running,
+            packet_counts,
+            Some(tx),
+        );
 
-            {
-                let mut cap_guard = cap.lock().unwrap();
-                if let Err(e) = apply_bpf_filter(&mut cap_guard, &bpf_filter) {
-                    eprintln!("Error applying BPF filter: {e}");
-                }
-            }
+        self.capture_thread_handle = Some(handle);
+        self.capture_state = CaptureState::Capturing;
+    } // start_capture
 
-            let savefile = savefile_name.and_then(|filename| {
-                setup_savefile(&cap, &filename)
-            });
-            let debug = false;
-
-            if let Err(e) = run_packet_loop(
-                running,
-                cap,
-                savefile,
-                packet_counts,
-                debug,
-                Some(tx),
-            ) {
-                eprintln!("Packet loop error: {e}");
-            }
-        });
+    /// Replays a saved capture file through the same decode and stats pipeline
+    /// as a live capture. The capture returns to idle once the file is exhausted.
+    fn start_file_capture(&mut self) {
+        if self.capture_state == CaptureState::Capturing {
+            return;
+        }
+
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Capture file", &["pcap", "pcapng", "cap"])
+            .pick_file()
+            .map(|path| path.to_string_lossy().to_string())
+        else {
+            return;
+        };
+
+        let bpf_filter = self.selected_filter.clone().unwrap_or_default();
+
+        self.running.store(true, std::sync::atomic::Ordering::SeqCst);
+
+        let handle = spawn_capture_thread(
+            move || open_offline_capture(&path),
+            FILE_PRECISION,
+            bpf_filter,
+            None,
+            self.running.clone(),
+            self.packet_counts.clone(),
+            Some(self.packet_tx.clone()),
+        );
 
         self.capture_thread_handle = Some(handle);
         self.capture_state = CaptureState::Capturing;
-    } // start_capture
+    } // start_file_capture
 
     fn stop_capture(&mut self) {
         if self.capture_state != CaptureState::Capturing {
@@ -157,8 +211,19 @@ impl SniffyApp {
 impl eframe::App for SniffyApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         while let Ok(packet) = self.packet_rx.try_recv() {
+            self.arp_table.observe(&packet);
+            self.connections.observe(&packet);
+            self.dns_stats.observe(&packet);
+            self.dhcp_leases.observe(&packet);
+            self.dhcpv6_leases.observe(&packet);
+            self.certificates.observe(&packet);
+            self.http2_streams.observe(&packet);
+
             if let Ok(mut log) = self.log.lock() {
-                log.push(packet.clone());
+                log.push_back(packet.clone());
+                if log.len() > MAX_LOG_ENTRIES {
+                    log.pop_front();
+                }
             }
 
             if let Ok(mut counts) = self.packet_counts.lock() {
@@ -166,6 +231,15 @@ impl eframe::App for SniffyApp {
             }
         }
 
+        // An offline capture ends on its own when the file runs out of packets
+        if self
+            .capture_thread_handle
+            .as_ref()
+            .is_some_and(|handle| handle.is_finished())
+        {
+            self.stop_capture();
+        }
+
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             ui.heading("Sniffy - GUI Packet Sniffer");
 
@@ -184,9 +258,14 @@ impl eframe::App for SniffyApp {
             });
 
             if self.capture_state == CaptureState::Idle {
-            if ui.button("Start Capture").clicked() {
-                self.start_capture();
-                }
+                ui.horizontal(|ui| {
+                    if ui.button("Start Capture").clicked() {
+                        self.start_capture();
+                    }
+                    if ui.button("Open PCAP File...").clicked() {
+                        self.start_file_capture();
+                    }
+                });
             } else {
                 if ui.button("Stop Capture").clicked() {
                     self.stop_capture();
@@ -197,9 +276,40 @@ impl eframe::App for SniffyApp {
         egui::CentralPanel::default().show(ctx, |ui| {
             stats_panel(ui, &self.packet_counts);
             ui.separator();
-            packet_log(ui, &self.log);
+
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.view, View::Packets, "Packets");
+                ui.selectable_value(&mut self.view, View::Connections, "Connections");
+                ui.selectable_value(&mut self.view, View::Dns, "DNS");
+                ui.selectable_value(&mut self.view, View::Dhcp, "DHCP");
+                ui.selectable_value(&mut self.view, View::Dhcpv6, "DHCPv6");
+                ui.selectable_value(&mut self.view, View::Certificates, "Certificates");
+                ui.selectable_value(&mut self.view, View::Http2, "HTTP/2");
+                ui.selectable_value(&mut self.view, View::ArpTable, "ARP Table");
+            });
+            ui.separator();
+
+            match self.view {
+                View::Packets => {
+                    let vlan = vlan_filter(ui, &mut self.vlan_filter);
+                    if let Some(key) = packet_log(ui, &self.log, vlan)
+                        && let Ok(log) = self.log.lock()
+                    {
+                        self.followed_stream = follow_stream(log.iter(), &key);
+                    }
+                }
+                View::Connections => connections_panel(ui, &self.connections, &mut self.connection_sort),
+                View::Dns => dns_panel(ui, &self.dns_stats),
+                View::Dhcp => dhcp_panel(ui, &self.dhcp_leases),
+                View::Dhcpv6 => dhcpv6_panel(ui, &self.dhcpv6_leases),
+                View::Certificates => certificates_panel(ui, &self.certificates),
+                View::Http2 => http2_panel(ui, &self.http2_streams),
+                View::ArpTable => arp_panel(ui, &self.arp_table),
+            }
         });
 
+        follow_stream_window(ctx, &mut self.followed_stream);
+
         ctx.request_repaint();
     }
 }
@@ -0,0 +1,47 @@
+use eframe::egui::{self, Color32, Ui};
+
+use crate::analysis::arp_table::ArpTable;
+use crate::packet::{format_mac, format_time_of_day};
+
+/// Shows the IP → MAC bindings learned from ARP traffic.
+pub fn arp_panel(ui: &mut Ui, table: &ArpTable) {
+    ui.label(format!("ARP Table ({} entries):", table.len()));
+
+    egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+        egui::Grid::new("arp_table_grid")
+            .striped(true)
+            .num_columns(7)
+            .show(ui, |ui| {
+                ui.strong("IP Address");
+                ui.strong("MAC Address");
+                ui.strong("Packets");
+                ui.strong("Gratuitous");
+                ui.strong("First Seen (UTC)");
+                ui.strong("Last Seen (UTC)");
+                ui.strong("Notes");
+                ui.end_row();
+
+                for entry in table.entries() {
+                    ui.label(entry.ip.to_string());
+                    ui.monospace(format_mac(&entry.mac));
+                    ui.label(entry.packets.to_string());
+                    ui.label(entry.gratuitous.to_string());
+                    ui.label(format_time_of_day(entry.first_seen));
+                    ui.label(format_time_of_day(entry.last_seen));
+
+                    if entry.has_conflict() {
+                        let previous = entry
+                            .previous_macs
+                            .iter()
+                            .map(format_mac)
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        ui.colored_label(Color32::RED, format!("MAC changed (was {})", previous));
+                    } else {
+                        ui.label("");
+                    }
+                    ui.end_row();
+                }
+            });
+    });
+}
@@ -0,0 +1,65 @@
+use eframe::egui::{self, Color32, Ui};
+
+use crate::analysis::certificates::{unix_now, CertificateInventory};
+use crate::packet::{format_time_of_day, format_unix_time};
+
+/// Lists every TLS certificate observed per server, flagging expired, soon to
+/// expire and self-signed ones, with DER export.
+pub fn certificates_panel(ui: &mut Ui, inventory: &CertificateInventory) {
+    let now = unix_now();
+
+    ui.horizontal(|ui| {
+        ui.strong(format!("Certificates ({}):", inventory.len()));
+        if ui.button("Export all DER...").clicked()
+            && let Some(dir) = rfd::FileDialog::new().pick_folder()
+            && let Err(e) = inventory.export_der(&dir)
+        {
+            eprintln!("Failed to export certificates to {}: {e}", dir.display());
+        }
+    });
+
+    egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+        egui::Grid::new("certificates_grid")
+            .striped(true)
+            .num_columns(10)
+            .show(ui, |ui| {
+                ui.strong("Server");
+                ui.strong("#");
+                ui.strong("Subject");
+                ui.strong("Issuer");
+                ui.strong("SAN");
+                ui.strong("Not Before (UTC)");
+                ui.strong("Not After (UTC)");
+                ui.strong("Key");
+                ui.strong("Notes");
+                ui.strong("");
+                ui.end_row();
+
+                for observed in inventory.certificates() {
+                    let cert = &observed.certificate;
+                    ui.label(&observed.server);
+                    ui.label(observed.chain_position.to_string());
+                    ui.label(&cert.subject)
+                        .on_hover_text(format!("SHA-256 {}\nFirst seen {}", cert.fingerprint, format_time_of_day(observed.first_seen)));
+                    ui.label(&cert.issuer);
+                    ui.label(cert.subject_alt_names.join("\n"));
+                    ui.label(format_unix_time(cert.not_before));
+                    ui.label(format_unix_time(cert.not_after));
+                    ui.label(&cert.key_type);
+                    let warnings = observed.warnings(now);
+                    if warnings.is_empty() {
+                        ui.label("");
+                    } else {
+                        ui.colored_label(Color32::RED, warnings.join(", "));
+                    }
+                    if ui.small_button("Save DER").clicked()
+                        && let Some(path) = rfd::FileDialog::new().set_file_name(observed.der_file_name()).save_file()
+                        && let Err(e) = std::fs::write(&path, &cert.der)
+                    {
+                        eprintln!("Failed to save certificate to {}: {e}", path.display());
+                    }
+                    ui.end_row();
+                }
+            });
+    });
+}
@@ -0,0 +1,93 @@
+use eframe::egui::{self, Ui};
+use std::cmp::Ordering;
+
+use crate::analysis::connections::{Connection, ConnectionTracker, Endpoint};
+use crate::gui::components::gui_state::{ConnectionColumn, ConnectionSort};
+use crate::packet::format_time_of_day;
+
+const COLUMNS: [(ConnectionColumn, &str); 10] = [
+    (ConnectionColumn::Protocol, "Protocol"),
+    (ConnectionColumn::Client, "Client"),
+    (ConnectionColumn::Server, "Server"),
+    (ConnectionColumn::State, "State"),
+    (ConnectionColumn::Start, "Start (UTC)"),
+    (ConnectionColumn::Duration, "Duration"),
+    (ConnectionColumn::PacketsToServer, "Packets →"),
+    (ConnectionColumn::BytesToServer, "Bytes →"),
+    (ConnectionColumn::PacketsToClient, "Packets ←"),
+    (ConnectionColumn::BytesToClient, "Bytes ←"),
+];
+
+/// Shows TCP connections and UDP conversations. Clicking a column header sorts
+/// by it; clicking it again reverses the order.
+pub fn connections_panel(ui: &mut Ui, tracker: &ConnectionTracker, sort: &mut ConnectionSort) {
+    ui.label(format!("Connections ({}):", tracker.len()));
+
+    let mut connections = tracker.connections();
+    connections.sort_by(|a, b| {
+        let ordering = compare(a, b, sort.column);
+        if sort.descending { ordering.reverse() } else { ordering }
+    });
+
+    egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+        egui::Grid::new("connections_grid")
+            .striped(true)
+            .num_columns(COLUMNS.len())
+            .show(ui, |ui| {
+                for (column, title) in COLUMNS {
+                    let selected = sort.column == column;
+                    let text = match (selected, sort.descending) {
+                        (true, false) => format!("{} ▲", title),
+                        (true, true) => format!("{} ▼", title),
+                        (false, _) => title.to_string(),
+                    };
+                    if ui.selectable_label(selected, text).clicked() {
+                        if selected {
+                            sort.descending = !sort.descending;
+                        } else {
+                            *sort = ConnectionSort { column, descending: false };
+                        }
+                    }
+                }
+                ui.end_row();
+
+                for connection in connections {
+                    ui.label(connection.protocol_name());
+                    ui.label(format_endpoint(&connection.client));
+                    ui.label(format_endpoint(&connection.server));
+                    ui.label(connection.state.map(|state| state.to_string()).unwrap_or_default());
+                    ui.label(format_time_of_day(connection.start));
+                    ui.label(format!("{:.3} s", connection.duration().as_secs_f64()));
+                    ui.label(connection.client_to_server.packets.to_string());
+                    ui.label(connection.client_to_server.bytes.to_string());
+                    ui.label(connection.server_to_client.packets.to_string());
+                    ui.label(connection.server_to_client.bytes.to_string());
+                    ui.end_row();
+                }
+            });
+    });
+}
+
+fn compare(a: &Connection, b: &Connection, column: ConnectionColumn) -> Ordering {
+    match column {
+        ConnectionColumn::Protocol => a.protocol.cmp(&b.protocol),
+        ConnectionColumn::Client => a.client.cmp(&b.client),
+        ConnectionColumn::Server => a.server.cmp(&b.server),
+        ConnectionColumn::State => a.state.cmp(&b.state),
+        ConnectionColumn::Start => a.start.cmp(&b.start),
+        ConnectionColumn::Duration => a.duration().cmp(&b.duration()),
+        ConnectionColumn::PacketsToServer => a.client_to_server.packets.cmp(&b.client_to_server.packets),
+        ConnectionColumn::BytesToServer => a.client_to_server.bytes.cmp(&b.client_to_server.bytes),
+        ConnectionColumn::PacketsToClient => a.server_to_client.packets.cmp(&b.server_to_client.packets),
+        ConnectionColumn::BytesToClient => a.server_to_client.bytes.cmp(&b.server_to_client.bytes),
+    }
+}
+
+/// `addr:port`, with IPv6 addresses in brackets
+fn format_endpoint((ip, port): &Endpoint) -> String {
+    if ip.contains(':') {
+        format!("[{}]:{}", ip, port)
+    } else {
+        format!("{}:{}", ip, port)
+    }
+}
@@ -0,0 +1,79 @@
+use eframe::egui::{self, Color32, Ui};
+
+use crate::analysis::dhcp_leases::DhcpLeaseTable;
+use crate::packet::{format_mac, format_time_of_day};
+
+/// Shows DHCP leases learned from DORA exchanges and the servers handing them
+/// out, warning when more than one server answers.
+pub fn dhcp_panel(ui: &mut Ui, table: &DhcpLeaseTable) {
+    let servers = table.servers();
+    if servers.len() > 1 {
+        ui.colored_label(
+            Color32::RED,
+            format!("{} DHCP servers are answering clients; check for a rogue server", servers.len()),
+        );
+    }
+
+    egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+        ui.strong(format!("Servers ({}):", servers.len()));
+        egui::Grid::new("dhcp_servers_grid")
+            .striped(true)
+            .num_columns(7)
+            .show(ui, |ui| {
+                ui.strong("Server ID");
+                ui.strong("MAC Address");
+                ui.strong("Offers");
+                ui.strong("ACKs");
+                ui.strong("NAKs");
+                ui.strong("First Seen (UTC)");
+                ui.strong("Notes");
+                ui.end_row();
+
+                for server in &servers {
+                    ui.label(server.server_id.to_string());
+                    ui.monospace(server.mac.as_ref().map(format_mac).unwrap_or_default());
+                    ui.label(server.offers.to_string());
+                    ui.label(server.acks.to_string());
+                    ui.label(server.naks.to_string());
+                    ui.label(format_time_of_day(server.first_seen));
+                    if table.is_suspected_rogue(server.server_id) {
+                        ui.colored_label(Color32::RED, "possible rogue server");
+                    } else {
+                        ui.label("");
+                    }
+                    ui.end_row();
+                }
+            });
+        ui.separator();
+
+        ui.strong(format!("Leases ({}):", table.len()));
+        egui::Grid::new("dhcp_leases_grid")
+            .striped(true)
+            .num_columns(9)
+            .show(ui, |ui| {
+                ui.strong("MAC Address");
+                ui.strong("IP Address");
+                ui.strong("Hostname");
+                ui.strong("Vendor Class");
+                ui.strong("Server");
+                ui.strong("Lease Time");
+                ui.strong("First Seen (UTC)");
+                ui.strong("Last ACK (UTC)");
+                ui.strong("Notes");
+                ui.end_row();
+
+                for lease in table.leases() {
+                    ui.monospace(format_mac(&lease.mac));
+                    ui.label(lease.ip.to_string());
+                    ui.label(lease.hostname.as_deref().unwrap_or(""));
+                    ui.label(lease.vendor_class.as_deref().unwrap_or(""));
+                    ui.label(lease.server.map(|server| server.to_string()).unwrap_or_default());
+                    ui.label(lease.lease_time.map(|secs| format!("{} s", secs)).unwrap_or_default());
+                    ui.label(format_time_of_day(lease.first_seen));
+                    ui.label(format_time_of_day(lease.last_ack));
+                    ui.label(if lease.released { "released" } else { "" });
+                    ui.end_row();
+                }
+            });
+    });
+}
@@ -0,0 +1,52 @@
+use eframe::egui::{self, Ui};
+
+use crate::analysis::dhcpv6_leases::Dhcpv6LeaseTable;
+use crate::packet::{format_duid, format_time_of_day};
+
+/// Shows DHCPv6 clients by DUID with the addresses and prefixes assigned to them.
+pub fn dhcpv6_panel(ui: &mut Ui, table: &Dhcpv6LeaseTable) {
+    ui.strong(format!("Clients ({}):", table.len()));
+
+    egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+        egui::Grid::new("dhcpv6_clients_grid")
+            .striped(true)
+            .num_columns(8)
+            .show(ui, |ui| {
+                ui.strong("Client DUID");
+                ui.strong("Client Address");
+                ui.strong("Addresses (IA_NA)");
+                ui.strong("Prefixes (IA_PD)");
+                ui.strong("Server DUID");
+                ui.strong("First Seen (UTC)");
+                ui.strong("Last Reply (UTC)");
+                ui.strong("Notes");
+                ui.end_row();
+
+                for client in table.clients() {
+                    ui.monospace(format_duid(&client.duid));
+                    ui.label(client.client_ip.as_deref().unwrap_or(""));
+                    ui.label(
+                        client
+                            .addresses
+                            .iter()
+                            .map(|a| format!("{} (valid {} s)", a.address, a.valid_lifetime))
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    );
+                    ui.label(
+                        client
+                            .prefixes
+                            .iter()
+                            .map(|p| format!("{} (valid {} s)", p, p.valid_lifetime))
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    );
+                    ui.monospace(client.server_duid.as_deref().map(format_duid).unwrap_or_default());
+                    ui.label(format_time_of_day(client.first_seen));
+                    ui.label(client.last_reply.map(format_time_of_day).unwrap_or_default());
+                    ui.label(if client.released { "released" } else { "" });
+                    ui.end_row();
+                }
+            });
+    });
+}
@@ -0,0 +1,122 @@
+use eframe::egui::{self, Color32, Ui};
+use std::time::Duration;
+
+use crate::analysis::dns_stats::DnsStats;
+use crate::packet::rcode_name;
+
+/// Number of entries shown in the top domains and top clients lists
+const TOP_ENTRIES: usize = 10;
+
+/// Shows DNS transaction statistics: volumes, latency, response codes,
+/// busiest domains and clients, and per-resolver health.
+pub fn dns_panel(ui: &mut Ui, stats: &DnsStats) {
+    ui.label(format!(
+        "{} queries, {} responses ({} matched, {} unmatched), {} timed out, {} outstanding",
+        stats.queries,
+        stats.responses,
+        stats.matched,
+        stats.unmatched_responses,
+        stats.timeouts,
+        stats.outstanding()
+    ));
+
+    match stats.latency() {
+        Some(latency) => ui.label(format!(
+            "Latency: mean {}, median {}, p95 {}, max {}",
+            format_ms(latency.mean),
+            format_ms(latency.median),
+            format_ms(latency.p95),
+            format_ms(latency.max)
+        )),
+        None => ui.label("Latency: no answered queries yet"),
+    };
+
+    ui.horizontal(|ui| {
+        rate_label(ui, "NXDOMAIN", stats.rcode_rate(3));
+        rate_label(ui, "SERVFAIL", stats.rcode_rate(2));
+    });
+    ui.separator();
+
+    egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+        ui.horizontal_top(|ui| {
+            ui.vertical(|ui| {
+                ui.strong("Top domains");
+                count_grid(ui, "dns_top_domains", &stats.top_domains(TOP_ENTRIES));
+            });
+            ui.separator();
+            ui.vertical(|ui| {
+                ui.strong("Top clients");
+                count_grid(ui, "dns_top_clients", &stats.top_clients(TOP_ENTRIES));
+            });
+            ui.separator();
+            ui.vertical(|ui| {
+                ui.strong("Response codes");
+                let rcodes: Vec<(String, usize)> = stats
+                    .rcodes()
+                    .into_iter()
+                    .map(|(rcode, count)| (rcode_name(rcode), count))
+                    .collect();
+                let rcodes: Vec<(&str, usize)> = rcodes.iter().map(|(name, count)| (name.as_str(), *count)).collect();
+                count_grid(ui, "dns_rcodes", &rcodes);
+            });
+        });
+        ui.separator();
+
+        ui.strong("Resolvers");
+        egui::Grid::new("dns_resolvers")
+            .striped(true)
+            .num_columns(7)
+            .show(ui, |ui| {
+                ui.strong("Server");
+                ui.strong("Queries");
+                ui.strong("Responses");
+                ui.strong("Timeouts");
+                ui.strong("Failures");
+                ui.strong("Mean");
+                ui.strong("Max");
+                ui.end_row();
+
+                for server in stats.servers() {
+                    ui.label(&server.address);
+                    ui.label(server.queries.to_string());
+                    ui.label(server.responses.to_string());
+                    if server.timeouts > 0 {
+                        ui.colored_label(Color32::RED, server.timeouts.to_string());
+                    } else {
+                        ui.label("0");
+                    }
+                    if server.failures > 0 {
+                        ui.colored_label(Color32::RED, server.failures.to_string());
+                    } else {
+                        ui.label("0");
+                    }
+                    ui.label(server.mean_latency().map(format_ms).unwrap_or_else(|| "-".into()));
+                    ui.label(format_ms(server.max_latency));
+                    ui.end_row();
+                }
+            });
+    });
+}
+
+fn count_grid(ui: &mut Ui, id: &str, entries: &[(&str, usize)]) {
+    egui::Grid::new(id).striped(true).num_columns(2).show(ui, |ui| {
+        for (name, count) in entries {
+            ui.label(*name);
+            ui.label(count.to_string());
+            ui.end_row();
+        }
+    });
+}
+
+fn rate_label(ui: &mut Ui, name: &str, rate: f64) {
+    let text = format!("{}: {:.1}%", name, rate);
+    if rate > 0.0 {
+        ui.colored_label(Color32::from_rgb(255, 165, 0), text);
+    } else {
+        ui.label(text);
+    }
+}
+
+fn format_ms(duration: Duration) -> String {
+    format!("{:.1} ms", duration.as_secs_f64() * 1000.0)
+}
@@ -0,0 +1,90 @@
+use eframe::egui::{self, Color32, RichText};
+
+use crate::analysis::tcp_reassembly::{Direction, HalfStream, TcpStream};
+
+const CLIENT_COLOR: Color32 = Color32::from_rgb(220, 80, 80);
+const SERVER_COLOR: Color32 = Color32::from_rgb(80, 120, 230);
+
+/// Shows the reassembled conversation of a TCP connection, with client data in
+/// red and server data in blue, and lets either direction be saved to a file.
+/// Closing the window clears `followed`.
+pub fn follow_stream_window(ctx: &egui::Context, followed: &mut Option<TcpStream>) {
+    let Some(stream) = followed.as_ref() else {
+        return;
+    };
+
+    let mut open = true;
+    egui::Window::new(format!("Follow TCP Stream #{}", stream.id))
+        .open(&mut open)
+        .default_size([640.0, 480.0])
+        .show(ctx, |ui| {
+            let client = format!("{}:{}", stream.client.0, stream.client.1);
+            let server = format!("{}:{}", stream.server.0, stream.server.1);
+
+            direction_label(ui, &client, &server, &stream.client_to_server, CLIENT_COLOR);
+            direction_label(ui, &server, &client, &stream.server_to_client, SERVER_COLOR);
+            ui.label(format!(
+                "Duration: {:.3} s{}",
+                stream.last_seen.saturating_sub(stream.first_seen).as_secs_f64(),
+                if stream.reset { ", reset" } else { "" }
+            ));
+
+            ui.horizontal(|ui| {
+                if ui.button("Save client data...").clicked() {
+                    save_bytes(&stream.client_to_server.data, &format!("stream-{}-client.bin", stream.id));
+                }
+                if ui.button("Save server data...").clicked() {
+                    save_bytes(&stream.server_to_client.data, &format!("stream-{}-server.bin", stream.id));
+                }
+            });
+            ui.separator();
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (direction, bytes) in stream.transcript() {
+                    let color = match direction {
+                        Direction::ClientToServer => CLIENT_COLOR,
+                        Direction::ServerToClient => SERVER_COLOR,
+                    };
+                    ui.label(RichText::new(printable(bytes)).monospace().color(color));
+                }
+            });
+        });
+
+    if !open {
+        *followed = None;
+    }
+}
+
+fn direction_label(ui: &mut egui::Ui, from: &str, to: &str, half: &HalfStream, color: Color32) {
+    let mut text = format!("{} → {}: {} bytes", from, to, half.len());
+    if half.missing_bytes > 0 {
+        text.push_str(&format!(", {} missing", half.missing_bytes));
+    }
+    if half.retransmitted_bytes > 0 {
+        text.push_str(&format!(", {} retransmitted", half.retransmitted_bytes));
+    }
+    if half.truncated {
+        text.push_str(&format!(", only the first {} shown", half.data.len()));
+    }
+    ui.colored_label(color, text);
+}
+
+/// Renders bytes as text, replacing control and non-ASCII bytes with '.'
+fn printable(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&b| match b {
+            b'\n' | b'\r' | b'\t' | 0x20..=0x7E => b as char,
+            _ => '.',
+        })
+        .collect()
+}
+
+fn save_bytes(data: &[u8], file_name: &str) {
+    let Some(path) = rfd::FileDialog::new().set_file_name(file_name).save_file() else {
+        return;
+    };
+    if let Err(e) = std::fs::write(&path, data) {
+        eprintln!("Failed to save stream data to {}: {e}", path.display());
+    }
+}
@@ -8,6 +8,50 @@ pub enum CaptureState {
     Capturing,
 }
 
+/// The view shown in the central panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    Packets,
+    Connections,
+    Dns,
+    Dhcp,
+    Dhcpv6,
+    Certificates,
+    Http2,
+    ArpTable,
+}
+
+/// A sortable column of the connections table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionColumn {
+    Protocol,
+    Client,
+    Server,
+    State,
+    Start,
+    Duration,
+    PacketsToServer,
+    BytesToServer,
+    PacketsToClient,
+    BytesToClient,
+}
+
+/// Current sort order of the connections table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionSort {
+    pub column: ConnectionColumn,
+    pub descending: bool,
+}
+
+impl Default for ConnectionSort {
+    fn default() -> Self {
+        ConnectionSort {
+            column: ConnectionColumn::Start,
+            descending: false,
+        }
+    }
+}
+
 /// Represents the kind of interface detected
 #[derive(Debug, PartialEq, Eq)]
 pub enum InterfaceKind {
@@ -45,6 +89,8 @@ pub fn suggested_filters(interface: &str) -> Vec<String> {
             "port 53".into(),
             "ip".into(),
             "icmp".into(),
+            "icmp6".into(),
+            "arp".into(),
         ],
         InterfaceKind::Unknown => vec!["ip".into(), "tcp".into()],
     }
@@ -57,6 +103,15 @@ pub enum ProtocolDisplay {
     UDP,
     IPv4,
     IPv6,
+    ICMP,
+    ICMPv6,
+    ARP,
+    DHCP,
+    DHCPv6,
+    HTTP,
+    HTTP2,
+    TLS,
+    QUIC,
     Ethernet,
     Other,
 }
@@ -70,6 +125,15 @@ impl ProtocolDisplay {
             PacketType::UDP => ProtocolDisplay::UDP,
             PacketType::IPv4 => ProtocolDisplay::IPv4,
             PacketType::IPv6 => ProtocolDisplay::IPv6,
+            PacketType::ICMP => ProtocolDisplay::ICMP,
+            PacketType::ICMPv6 => ProtocolDisplay::ICMPv6,
+            PacketType::ARP => ProtocolDisplay::ARP,
+            PacketType::DHCP => ProtocolDisplay::DHCP,
+            PacketType::DHCPv6 => ProtocolDisplay::DHCPv6,
+            PacketType::HTTP => ProtocolDisplay::HTTP,
+            PacketType::HTTP2 => ProtocolDisplay::HTTP2,
+            PacketType::TLS => ProtocolDisplay::TLS,
+            PacketType::QUIC => ProtocolDisplay::QUIC,
             PacketType::Ethernet => ProtocolDisplay::Ethernet,
             PacketType::Other(_) => ProtocolDisplay::Other,
         }
@@ -83,6 +147,15 @@ impl ProtocolDisplay {
             ProtocolDisplay::UDP => "UDP:",
             ProtocolDisplay::IPv4 => "IPv4:",
             ProtocolDisplay::IPv6 => "IPv6:",
+            ProtocolDisplay::ICMP => "ICMP:",
+            ProtocolDisplay::ICMPv6 => "ICMPv6:",
+            ProtocolDisplay::ARP => "ARP:",
+            ProtocolDisplay::DHCP => "DHCP:",
+            ProtocolDisplay::DHCPv6 => "DHCPv6:",
+            ProtocolDisplay::HTTP => "HTTP:",
+            ProtocolDisplay::HTTP2 => "HTTP/2:",
+            ProtocolDisplay::TLS => "TLS:",
+            ProtocolDisplay::QUIC => "QUIC:",
             ProtocolDisplay::Ethernet => "Ethernet:",
             ProtocolDisplay::Other => "Other:",
         }
@@ -95,6 +168,11 @@ impl ProtocolDisplay {
             ProtocolDisplay::TCP => Color32::from_rgb(0, 128, 255),        // Blue
             ProtocolDisplay::UDP => Color32::YELLOW,                        // Yellow
             ProtocolDisplay::IPv4 | ProtocolDisplay::IPv6 => Color32::LIGHT_GRAY,
+            ProtocolDisplay::ICMP | ProtocolDisplay::ICMPv6 => Color32::from_rgb(200, 100, 255), // Purple
+            ProtocolDisplay::ARP => Color32::from_rgb(255, 165, 0),        // Orange
+            ProtocolDisplay::DHCP | ProtocolDisplay::DHCPv6 => Color32::from_rgb(0, 200, 200), // Cyan
+            ProtocolDisplay::HTTP | ProtocolDisplay::HTTP2 => Color32::from_rgb(120, 220, 120), // Light Green
+            ProtocolDisplay::TLS | ProtocolDisplay::QUIC => Color32::from_rgb(230, 120, 200), // Pink
             ProtocolDisplay::Ethernet => Color32::from_rgb(180, 180, 180), // Light Gray
             ProtocolDisplay::Other => Color32::DARK_GRAY,
         }
@@ -0,0 +1,42 @@
+use eframe::egui::{self, Ui};
+
+use crate::analysis::http2_streams::Http2StreamTable;
+use crate::packet::format_time_of_day;
+
+/// Shows HTTP/2 streams with their request, status and byte counts; gRPC calls by service and method.
+pub fn http2_panel(ui: &mut Ui, table: &Http2StreamTable) {
+    ui.strong(format!("Streams ({}):", table.len()));
+
+    egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+        egui::Grid::new("http2_streams_grid")
+            .striped(true)
+            .num_columns(10)
+            .show(ui, |ui| {
+                ui.strong("First Seen (UTC)");
+                ui.strong("Client");
+                ui.strong("Server");
+                ui.strong("Stream");
+                ui.strong("Authority");
+                ui.strong("Request");
+                ui.strong("Status");
+                ui.strong("Request Bytes");
+                ui.strong("Response Bytes");
+                ui.strong("State");
+                ui.end_row();
+
+                for stream in table.streams() {
+                    ui.label(format_time_of_day(stream.first_seen));
+                    ui.label(stream.client.as_deref().unwrap_or(""));
+                    ui.label(stream.server.as_deref().unwrap_or(""));
+                    ui.label(stream.stream_id.to_string());
+                    ui.label(stream.authority.as_deref().unwrap_or(""));
+                    ui.monospace(stream.request_label());
+                    ui.label(stream.status_label());
+                    ui.label(stream.request_bytes.to_string());
+                    ui.label(stream.response_bytes.to_string());
+                    ui.label(stream.state().to_string());
+                    ui.end_row();
+                }
+            });
+    });
+}
@@ -1,5 +1,14 @@
+pub mod arp_panel;
+pub mod certificates_panel;
+pub mod connections_panel;
+pub mod dhcp_panel;
+pub mod dhcpv6_panel;
+pub mod dns_panel;
 pub mod filter_input;
+pub mod follow_stream;
+pub mod http2_panel;
 pub mod interface_selector;
 pub mod packet_log;
 pub mod stats_panel;
-pub mod gui_state;
\ No newline at end of file
+pub mod gui_state;
+pub mod vlan_filter;
\ No newline at end of file
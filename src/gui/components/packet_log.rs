@@ -1,6 +1,9 @@
-use eframe::egui::{RichText, Ui};
+use eframe::egui::{self, RichText, Ui};
+use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
-use crate::packet::{PacketInfo, PacketType};
+use std::time::Duration;
+use crate::analysis::tcp_reassembly::StreamKey;
+use crate::packet::{format_time_of_day, PacketInfo};
 use crate::gui::components::gui_state::ProtocolDisplay;
 
 /// Format a packet line (you can extend this with more info)
@@ -13,24 +16,109 @@ fn format_packet_line_with_label(packet: &PacketInfo) -> String {
     format!("{}:{} -> {}:{} [{:?}]", src, sport, dst, dport, packet.packet_type)
 }
 
-pub fn packet_log(ui: &mut Ui, log: &Arc<Mutex<Vec<PacketInfo>>>) {
+/// Formats a time offset in seconds with microsecond precision
+fn format_offset(offset: Duration) -> String {
+    format!("{:.6}", offset.as_secs_f64())
+}
+
+/// Shows the most recent packets. Returns the connection of a TCP packet whose
+/// "Follow" button was clicked.
+pub fn packet_log(ui: &mut Ui, log: &Arc<Mutex<VecDeque<PacketInfo>>>, vlan_filter: Option<u16>) -> Option<StreamKey> {
+    let mut follow = None;
     ui.label("Live Packet Log:");
     eframe::egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
         if let Ok(log) = log.lock() {
-            for packet in log.iter().rev().take(100) {
-                let proto_disp = ProtocolDisplay::from_packet_type(packet.packet_type.clone());
-                let label = proto_disp.label();
-                let colored_label = RichText::new(label)
-                    .color(proto_disp.color())
-                    .strong();
-
-                let text = format_packet_line_with_label(packet);
-
-                ui.horizontal(|ui| {
-                    ui.label(colored_label);
-                    ui.label(text);
+            let first_ts = log.front().map(|p| p.timestamp).unwrap_or_default();
+
+            egui::Grid::new("packet_log_grid")
+                .striped(true)
+                .num_columns(11)
+                .show(ui, |ui| {
+                    ui.strong("Protocol");
+                    ui.strong("Time (UTC)");
+                    ui.strong("Relative");
+                    ui.strong("Delta");
+                    ui.strong("Length");
+                    ui.strong("VLAN");
+                    ui.strong("TTL");
+                    ui.strong("DSCP / ECN");
+                    ui.strong("Packet");
+                    ui.strong("Info");
+                    ui.strong("");
+                    ui.end_row();
+
+                    let visible = log
+                        .iter()
+                        .enumerate()
+                        .rev()
+                        .filter(|(_, packet)| vlan_filter.is_none_or(|id| packet.has_vlan(id)))
+                        .take(100);
+
+                    for (index, packet) in visible {
+                        let proto_disp = ProtocolDisplay::from_packet_type(packet.packet_type.clone());
+                        let label = proto_disp.label();
+                        let colored_label = RichText::new(label)
+                            .color(proto_disp.color())
+                            .strong();
+
+                        let prev_ts = index
+                            .checked_sub(1)
+                            .map(|i| log[i].timestamp)
+                            .unwrap_or(packet.timestamp);
+
+                        // Wire length, with the captured length when the frame was truncated
+                        let length = if packet.caplen < packet.orig_len {
+                            format!("{} ({} captured)", packet.orig_len, packet.caplen)
+                        } else {
+                            packet.orig_len.to_string()
+                        };
+
+                        ui.label(colored_label);
+                        ui.label(format_time_of_day(packet.timestamp));
+                        ui.label(format_offset(packet.timestamp.saturating_sub(first_ts)));
+                        ui.label(format_offset(packet.timestamp.saturating_sub(prev_ts)));
+                        ui.label(length);
+                        ui.label(packet.vlan_label());
+                        match &packet.ipv4 {
+                            Some(header) => {
+                                ui.label(header.ttl.to_string());
+                                ui.label(format!("{} / {}", header.dscp_label(), header.ecn_label()));
+                            }
+                            None => {
+                                ui.label("");
+                                ui.label("");
+                            }
+                        }
+                        ui.label(format_packet_line_with_label(packet));
+                        let info = ui.label(packet.summary());
+                        if !packet.dns.is_empty() {
+                            let details: Vec<_> = packet.dns.iter().map(|message| message.details()).collect();
+                            info.on_hover_text(RichText::new(details.join("\n\n")).monospace());
+                        } else if !packet.http.is_empty() {
+                            let details: Vec<_> = packet.http.iter().map(|message| message.details()).collect();
+                            info.on_hover_text(RichText::new(details.join("\n\n")).monospace());
+                        } else if !packet.http2.is_empty() {
+                            let details: Vec<_> = packet.http2.iter().map(|frame| frame.details()).collect();
+                            info.on_hover_text(RichText::new(details.join("\n")).monospace());
+                        } else if !packet.tls.is_empty() {
+                            let details: Vec<_> = packet.tls.iter().map(|message| message.details()).collect();
+                            info.on_hover_text(RichText::new(details.join("\n\n")).monospace());
+                        }
+                        match StreamKey::from_packet(packet) {
+                            Some(key) => {
+                                if ui.small_button("Follow").clicked() {
+                                    follow = Some(key);
+                                }
+                            }
+                            None => {
+                                ui.label("");
+                            }
+                        }
+                        ui.end_row();
+                    }
                 });
-            }
         }
     });
+
+    follow
 }
@@ -0,0 +1,28 @@
+use eframe::egui;
+
+/// Text box for restricting the packet log to a single VLAN ID.
+/// Returns the parsed VLAN ID, or `None` when the box is empty or invalid.
+pub fn vlan_filter(ui: &mut egui::Ui, text: &mut String) -> Option<u16> {
+    ui.horizontal(|ui| {
+        ui.label("VLAN:");
+        ui.add(
+            egui::TextEdit::singleline(text)
+                .hint_text("any")
+                .desired_width(60.0),
+        );
+
+        let trimmed = text.trim();
+        if trimmed.is_empty() {
+            return None;
+        }
+
+        match trimmed.parse::<u16>() {
+            Ok(id) if id <= 4095 => Some(id),
+            _ => {
+                ui.colored_label(egui::Color32::RED, "invalid VLAN ID");
+                None
+            }
+        }
+    })
+    .inner
+}
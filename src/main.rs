@@ -1,12 +1,42 @@
+mod analysis;
 mod capture;
 mod core;
 mod packet;
 mod gui;
 mod cli;
+mod tui;
 
+use clap::Parser;
 use eframe::egui;
 
 fn main() -> Result<(), eframe::Error> {
+    let args = cli::args::CliArgs::parse();
+    packet::set_extra_dns_ports(&args.dns_ports);
+    analysis::http2_stream::set_h2c_ports(&args.h2c_ports);
+
+    if args.list_interfaces || args.is_headless() {
+        let result = if args.list_interfaces {
+            cli::headless::list_interfaces()
+        } else {
+            cli::headless::run_headless(&args)
+        };
+
+        if let Err(e) = result {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if args.tui {
+        let filter = args.filter.clone().unwrap_or_default();
+        if let Err(e) = tui::app::run_tui(args.interface.clone(), filter, args.export.clone()) {
+            eprintln!("Terminal UI error: {e}");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([960.0, 640.0]),
@@ -18,4 +48,4 @@ fn main() -> Result<(), eframe::Error> {
         options,
         Box::new(|cc| Ok(Box::new(gui::app::SniffyApp::new(cc)))),
     )
-}
+}
\ No newline at end of file
@@ -0,0 +1,103 @@
+use super::{PacketInfo, PacketType};
+use crate::packet::ethernet::{array_from_slice, format_mac};
+use std::net::Ipv4Addr;
+
+/// ARP operation codes (RFC 826, RFC 903 for RARP)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArpOperation {
+    Request,
+    Reply,
+    Other(u16),
+}
+
+impl ArpOperation {
+    pub fn from_code(code: u16) -> Self {
+        match code {
+            1 => ArpOperation::Request,
+            2 => ArpOperation::Reply,
+            other => ArpOperation::Other(other),
+        }
+    }
+}
+
+/// An Ethernet/IPv4 ARP message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArpPacket {
+    pub operation: ArpOperation,
+    pub sender_mac: [u8; 6],
+    pub sender_ip: Ipv4Addr,
+    pub target_mac: [u8; 6],
+    pub target_ip: Ipv4Addr,
+}
+
+impl ArpPacket {
+    /// A probe (RFC 5227) is a request with an all-zero sender IP, sent to check
+    /// whether an address is already in use before claiming it.
+    pub fn is_probe(&self) -> bool {
+        self.operation == ArpOperation::Request && self.sender_ip.is_unspecified()
+    }
+
+    /// A gratuitous ARP announces the sender's own mapping: sender and target IP are equal.
+    pub fn is_gratuitous(&self) -> bool {
+        !self.sender_ip.is_unspecified() && self.sender_ip == self.target_ip
+    }
+
+    /// Human readable description in the style of common packet analyzers.
+    pub fn summary(&self) -> String {
+        if self.is_probe() {
+            return format!("ARP probe for {}", self.target_ip);
+        }
+        if self.is_gratuitous() {
+            return format!("Gratuitous ARP for {} ({})", self.sender_ip, format_mac(&self.sender_mac));
+        }
+
+        match self.operation {
+            ArpOperation::Request => format!("Who has {}? Tell {}", self.target_ip, self.sender_ip),
+            ArpOperation::Reply => format!("{} is at {}", self.sender_ip, format_mac(&self.sender_mac)),
+            ArpOperation::Other(code) => format!("ARP opcode {}", code),
+        }
+    }
+}
+
+/// Parses an ARP message and updates the provided PacketInfo.
+/// Only Ethernet hardware addresses with IPv4 protocol addresses are decoded;
+/// other combinations are reported as a generic EtherType.
+pub fn parse_arp(data: &[u8], info: &mut PacketInfo) -> Result<(), &'static str> {
+    if data.len() < 8 {
+        return Err("ARP header too short");
+    }
+
+    let hardware_type = u16::from_be_bytes([data[0], data[1]]);
+    let protocol_type = u16::from_be_bytes([data[2], data[3]]);
+    let hardware_len = data[4];
+    let protocol_len = data[5];
+
+    if hardware_type != 1 || protocol_type != 0x0800 || hardware_len != 6 || protocol_len != 4 {
+        info.packet_type = PacketType::Other(0x0806);
+        return Ok(());
+    }
+
+    if data.len() < 28 {
+        return Err("ARP packet too short");
+    }
+
+    let operation = ArpOperation::from_code(u16::from_be_bytes([data[6], data[7]]));
+    let sender_mac = array_from_slice(&data[8..14]).ok_or("Invalid ARP sender address")?;
+    let sender_ip = Ipv4Addr::new(data[14], data[15], data[16], data[17]);
+    let target_mac = array_from_slice(&data[18..24]).ok_or("Invalid ARP target address")?;
+    let target_ip = Ipv4Addr::new(data[24], data[25], data[26], data[27]);
+
+    info.packet_type = PacketType::ARP;
+    info.src_ip = Some(sender_ip.to_string());
+    info.dst_ip = Some(target_ip.to_string());
+    info.arp = Some(ArpPacket {
+        operation,
+        sender_mac,
+        sender_ip,
+        target_mac,
+        target_ip,
+    });
+
+    Ok(())
+}
+
@@ -0,0 +1,209 @@
+use super::{PacketInfo, PacketType};
+use crate::packet::ethernet::{array_from_slice, format_mac};
+use std::fmt;
+use std::net::Ipv4Addr;
+
+/// The DHCP magic cookie that starts the options field (RFC 2131)
+const MAGIC_COOKIE: [u8; 4] = [99, 130, 83, 99];
+
+/// DHCP message types (option 53)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DhcpMessageType {
+    Discover,
+    Offer,
+    Request,
+    Decline,
+    Ack,
+    Nak,
+    Release,
+    Inform,
+    Other(u8),
+}
+
+impl DhcpMessageType {
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            1 => DhcpMessageType::Discover,
+            2 => DhcpMessageType::Offer,
+            3 => DhcpMessageType::Request,
+            4 => DhcpMessageType::Decline,
+            5 => DhcpMessageType::Ack,
+            6 => DhcpMessageType::Nak,
+            7 => DhcpMessageType::Release,
+            8 => DhcpMessageType::Inform,
+            other => DhcpMessageType::Other(other),
+        }
+    }
+}
+
+impl fmt::Display for DhcpMessageType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DhcpMessageType::Discover => write!(f, "Discover"),
+            DhcpMessageType::Offer => write!(f, "Offer"),
+            DhcpMessageType::Request => write!(f, "Request"),
+            DhcpMessageType::Decline => write!(f, "Decline"),
+            DhcpMessageType::Ack => write!(f, "ACK"),
+            DhcpMessageType::Nak => write!(f, "NAK"),
+            DhcpMessageType::Release => write!(f, "Release"),
+            DhcpMessageType::Inform => write!(f, "Inform"),
+            DhcpMessageType::Other(code) => write!(f, "Type {}", code),
+        }
+    }
+}
+
+/// A BOOTP/DHCP message with its commonly used options.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DhcpInfo {
+    /// 1 = BOOTREQUEST (client), 2 = BOOTREPLY (server)
+    pub op: u8,
+    pub xid: u32,
+    /// Client hardware address, when it is an Ethernet MAC
+    pub client_mac: Option<[u8; 6]>,
+    pub ciaddr: Ipv4Addr,
+    /// "Your" address: the address being offered or assigned
+    pub yiaddr: Ipv4Addr,
+    pub siaddr: Ipv4Addr,
+    /// Relay agent address
+    pub giaddr: Ipv4Addr,
+    /// `None` for plain BOOTP
+    pub message_type: Option<DhcpMessageType>,
+    pub requested_ip: Option<Ipv4Addr>,
+    pub server_id: Option<Ipv4Addr>,
+    /// Lease time in seconds
+    pub lease_time: Option<u32>,
+    pub subnet_mask: Option<Ipv4Addr>,
+    pub routers: Vec<Ipv4Addr>,
+    pub dns_servers: Vec<Ipv4Addr>,
+    pub hostname: Option<String>,
+    pub client_id: Option<Vec<u8>>,
+    pub vendor_class: Option<String>,
+}
+
+impl DhcpInfo {
+    pub fn is_reply(&self) -> bool {
+        self.op == 2
+    }
+
+    /// One-line description, e.g. `DHCP ACK 192.168.1.20 to aa:bb:cc:dd:ee:ff (laptop) - xid 0x3903f326`.
+    pub fn summary(&self) -> String {
+        let mut summary = match self.message_type {
+            Some(message_type) => format!("DHCP {}", message_type),
+            None if self.is_reply() => "BOOTP Reply".to_string(),
+            None => "BOOTP Request".to_string(),
+        };
+
+        match self.message_type {
+            Some(DhcpMessageType::Offer | DhcpMessageType::Ack) => {
+                summary.push_str(&format!(" {}", self.yiaddr));
+            }
+            Some(DhcpMessageType::Request) => {
+                if let Some(requested) = self.requested_ip {
+                    summary.push_str(&format!(" {}", requested));
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(mac) = &self.client_mac {
+            let preposition = if self.is_reply() { "to" } else { "from" };
+            summary.push_str(&format!(" {} {}", preposition, format_mac(mac)));
+        }
+        if let Some(hostname) = &self.hostname {
+            summary.push_str(&format!(" ({})", hostname));
+        }
+        summary.push_str(&format!(" - xid 0x{:08x}", self.xid));
+        summary
+    }
+}
+
+/// Parses a BOOTP/DHCP message carried on UDP ports 67/68 and updates the PacketInfo.
+pub fn parse_dhcp(data: &[u8], info: &mut PacketInfo) -> Result<(), &'static str> {
+    // Fixed BOOTP header is 236 bytes, followed by the magic cookie
+    if data.len() < 236 {
+        return Err("DHCP message too short");
+    }
+
+    let ip_at = |at: usize| Ipv4Addr::new(data[at], data[at + 1], data[at + 2], data[at + 3]);
+
+    let htype = data[1];
+    let hlen = data[2];
+    let client_mac = (htype == 1 && hlen == 6)
+        .then(|| array_from_slice(&data[28..34]))
+        .flatten();
+
+    let mut dhcp = DhcpInfo {
+        op: data[0],
+        xid: u32::from_be_bytes([data[4], data[5], data[6], data[7]]),
+        client_mac,
+        ciaddr: ip_at(12),
+        yiaddr: ip_at(16),
+        siaddr: ip_at(20),
+        giaddr: ip_at(24),
+        message_type: None,
+        requested_ip: None,
+        server_id: None,
+        lease_time: None,
+        subnet_mask: None,
+        routers: Vec::new(),
+        dns_servers: Vec::new(),
+        hostname: None,
+        client_id: None,
+        vendor_class: None,
+    };
+
+    if data.len() >= 240 && data[236..240] == MAGIC_COOKIE {
+        parse_options(&data[240..], &mut dhcp);
+    }
+
+    info.packet_type = PacketType::DHCP;
+    info.dhcp = Some(dhcp);
+    Ok(())
+}
+
+/// Decodes the options we care about. Parsing stops at the End option or at a
+/// malformed length; option overloading into sname/file is not followed.
+fn parse_options(mut data: &[u8], dhcp: &mut DhcpInfo) {
+    let addresses = |value: &[u8]| -> Vec<Ipv4Addr> {
+        value
+            .chunks_exact(4)
+            .map(|a| Ipv4Addr::new(a[0], a[1], a[2], a[3]))
+            .collect()
+    };
+
+    while let Some(&code) = data.first() {
+        match code {
+            0 => {
+                data = &data[1..]; // Pad
+                continue;
+            }
+            255 => break, // End
+            _ => {}
+        }
+
+        let Some(&length) = data.get(1) else {
+            break;
+        };
+        let Some(value) = data.get(2..2 + length as usize) else {
+            break;
+        };
+
+        match code {
+            1 => dhcp.subnet_mask = addresses(value).first().copied(),
+            3 => dhcp.routers = addresses(value),
+            6 => dhcp.dns_servers = addresses(value),
+            12 => dhcp.hostname = Some(String::from_utf8_lossy(value).into_owned()),
+            50 => dhcp.requested_ip = addresses(value).first().copied(),
+            51 if value.len() == 4 => {
+                dhcp.lease_time = Some(u32::from_be_bytes([value[0], value[1], value[2], value[3]]));
+            }
+            53 if value.len() == 1 => dhcp.message_type = Some(DhcpMessageType::from_code(value[0])),
+            54 => dhcp.server_id = addresses(value).first().copied(),
+            60 => dhcp.vendor_class = Some(String::from_utf8_lossy(value).into_owned()),
+            61 => dhcp.client_id = Some(value.to_vec()),
+            _ => {}
+        }
+
+        data = &data[2 + length as usize..];
+    }
+}
@@ -0,0 +1,313 @@
+use super::{PacketInfo, PacketType};
+use std::fmt;
+use std::net::Ipv6Addr;
+
+/// Relay messages nest the client message inside; deeper nesting is not followed
+const MAX_RELAY_DEPTH: usize = 8;
+
+/// DHCPv6 message types (RFC 8415)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dhcpv6MessageType {
+    Solicit,
+    Advertise,
+    Request,
+    Confirm,
+    Renew,
+    Rebind,
+    Reply,
+    Release,
+    Decline,
+    Reconfigure,
+    InformationRequest,
+    RelayForward,
+    RelayReply,
+    Other(u8),
+}
+
+impl Dhcpv6MessageType {
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            1 => Dhcpv6MessageType::Solicit,
+            2 => Dhcpv6MessageType::Advertise,
+            3 => Dhcpv6MessageType::Request,
+            4 => Dhcpv6MessageType::Confirm,
+            5 => Dhcpv6MessageType::Renew,
+            6 => Dhcpv6MessageType::Rebind,
+            7 => Dhcpv6MessageType::Reply,
+            8 => Dhcpv6MessageType::Release,
+            9 => Dhcpv6MessageType::Decline,
+            10 => Dhcpv6MessageType::Reconfigure,
+            11 => Dhcpv6MessageType::InformationRequest,
+            12 => Dhcpv6MessageType::RelayForward,
+            13 => Dhcpv6MessageType::RelayReply,
+            other => Dhcpv6MessageType::Other(other),
+        }
+    }
+}
+
+impl fmt::Display for Dhcpv6MessageType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Dhcpv6MessageType::Solicit => write!(f, "Solicit"),
+            Dhcpv6MessageType::Advertise => write!(f, "Advertise"),
+            Dhcpv6MessageType::Request => write!(f, "Request"),
+            Dhcpv6MessageType::Confirm => write!(f, "Confirm"),
+            Dhcpv6MessageType::Renew => write!(f, "Renew"),
+            Dhcpv6MessageType::Rebind => write!(f, "Rebind"),
+            Dhcpv6MessageType::Reply => write!(f, "Reply"),
+            Dhcpv6MessageType::Release => write!(f, "Release"),
+            Dhcpv6MessageType::Decline => write!(f, "Decline"),
+            Dhcpv6MessageType::Reconfigure => write!(f, "Reconfigure"),
+            Dhcpv6MessageType::InformationRequest => write!(f, "Information-request"),
+            Dhcpv6MessageType::RelayForward => write!(f, "Relay-forward"),
+            Dhcpv6MessageType::RelayReply => write!(f, "Relay-reply"),
+            Dhcpv6MessageType::Other(code) => write!(f, "Type {}", code),
+        }
+    }
+}
+
+/// An address from an IA_NA.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IaAddress {
+    pub address: Ipv6Addr,
+    pub preferred_lifetime: u32,
+    pub valid_lifetime: u32,
+}
+
+/// A delegated prefix from an IA_PD.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IaPrefix {
+    pub prefix: Ipv6Addr,
+    pub length: u8,
+    pub preferred_lifetime: u32,
+    pub valid_lifetime: u32,
+}
+
+impl fmt::Display for IaPrefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.prefix, self.length)
+    }
+}
+
+/// An Identity Association for non-temporary addresses (IA_NA) or prefix delegation (IA_PD).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdentityAssociation {
+    pub iaid: u32,
+    pub t1: u32,
+    pub t2: u32,
+    pub addresses: Vec<IaAddress>,
+    pub prefixes: Vec<IaPrefix>,
+}
+
+/// A decoded DHCPv6 message. For relayed messages the fields describe the
+/// client message carried inside the relay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dhcpv6Info {
+    pub message_type: Dhcpv6MessageType,
+    pub transaction_id: u32,
+    /// Number of relay agents the message passed through
+    pub relay_hops: usize,
+    pub client_duid: Option<Vec<u8>>,
+    pub server_duid: Option<Vec<u8>>,
+    pub ia_na: Vec<IdentityAssociation>,
+    pub ia_pd: Vec<IdentityAssociation>,
+    pub dns_servers: Vec<Ipv6Addr>,
+    pub domain_list: Vec<String>,
+    /// Status code and message (option 13), at message level
+    pub status: Option<(u16, String)>,
+}
+
+impl Dhcpv6Info {
+    /// All addresses assigned in IA_NA options.
+    pub fn addresses(&self) -> impl Iterator<Item = &IaAddress> {
+        self.ia_na.iter().flat_map(|ia| ia.addresses.iter())
+    }
+
+    /// All prefixes delegated in IA_PD options.
+    pub fn prefixes(&self) -> impl Iterator<Item = &IaPrefix> {
+        self.ia_pd.iter().flat_map(|ia| ia.prefixes.iter())
+    }
+
+    /// One-line description, e.g. `DHCPv6 Reply XID 0x1a2b3c IA_NA 2001:db8::10 IA_PD 2001:db8:100::/56`.
+    pub fn summary(&self) -> String {
+        let mut summary = format!("DHCPv6 {} XID 0x{:06x}", self.message_type, self.transaction_id);
+        if self.relay_hops > 0 {
+            summary.push_str(" (relayed)");
+        }
+        if let Some(duid) = &self.client_duid {
+            summary.push_str(&format!(" CID {}", format_duid(duid)));
+        }
+        for address in self.addresses() {
+            summary.push_str(&format!(" IA_NA {}", address.address));
+        }
+        for prefix in self.prefixes() {
+            summary.push_str(&format!(" IA_PD {}", prefix));
+        }
+        if let Some((code, message)) = &self.status
+            && *code != 0
+        {
+            summary.push_str(&format!(" status {} {}", code, message));
+        }
+        summary
+    }
+}
+
+/// Formats a DUID as colon-separated hex.
+pub fn format_duid(duid: &[u8]) -> String {
+    duid.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(":")
+}
+
+/// Parses a DHCPv6 message carried on UDP ports 546/547 and updates the PacketInfo.
+pub fn parse_dhcpv6(data: &[u8], info: &mut PacketInfo) -> Result<(), &'static str> {
+    let dhcpv6 = parse_message(data, 0)?;
+    info.packet_type = PacketType::DHCPv6;
+    info.dhcpv6 = Some(dhcpv6);
+    Ok(())
+}
+
+fn parse_message(data: &[u8], depth: usize) -> Result<Dhcpv6Info, &'static str> {
+    let Some(&code) = data.first() else {
+        return Err("DHCPv6 message empty");
+    };
+    let message_type = Dhcpv6MessageType::from_code(code);
+
+    if matches!(message_type, Dhcpv6MessageType::RelayForward | Dhcpv6MessageType::RelayReply) {
+        // msg-type, hop-count, link-address, peer-address, then options
+        if data.len() < 34 {
+            return Err("DHCPv6 relay message too short");
+        }
+        if depth >= MAX_RELAY_DEPTH {
+            return Err("Too many nested DHCPv6 relay messages");
+        }
+        let relayed = options(&data[34..])
+            .find(|(code, _)| *code == 9) // Relay Message
+            .map(|(_, value)| value)
+            .ok_or("DHCPv6 relay message without an inner message")?;
+        let mut inner = parse_message(relayed, depth + 1)?;
+        inner.relay_hops += 1;
+        return Ok(inner);
+    }
+
+    if data.len() < 4 {
+        return Err("DHCPv6 message too short");
+    }
+
+    let mut dhcpv6 = Dhcpv6Info {
+        message_type,
+        transaction_id: u32::from_be_bytes([0, data[1], data[2], data[3]]),
+        relay_hops: 0,
+        client_duid: None,
+        server_duid: None,
+        ia_na: Vec::new(),
+        ia_pd: Vec::new(),
+        dns_servers: Vec::new(),
+        domain_list: Vec::new(),
+        status: None,
+    };
+
+    for (code, value) in options(&data[4..]) {
+        match code {
+            1 => dhcpv6.client_duid = Some(value.to_vec()),
+            2 => dhcpv6.server_duid = Some(value.to_vec()),
+            3 => dhcpv6.ia_na.extend(parse_ia(value)),
+            13 => dhcpv6.status = parse_status(value),
+            23 => dhcpv6.dns_servers = addresses(value),
+            24 => dhcpv6.domain_list = parse_domain_list(value),
+            25 => dhcpv6.ia_pd.extend(parse_ia(value)),
+            _ => {}
+        }
+    }
+
+    Ok(dhcpv6)
+}
+
+/// Iterates over (code, value) options, stopping at the first truncated one.
+fn options(mut data: &[u8]) -> impl Iterator<Item = (u16, &[u8])> {
+    std::iter::from_fn(move || {
+        if data.len() < 4 {
+            return None;
+        }
+        let code = u16::from_be_bytes([data[0], data[1]]);
+        let len = u16::from_be_bytes([data[2], data[3]]) as usize;
+        let value = data.get(4..4 + len)?;
+        data = &data[4 + len..];
+        Some((code, value))
+    })
+}
+
+/// Parses an IA_NA or IA_PD option body: IAID, T1, T2 and nested IA options.
+fn parse_ia(value: &[u8]) -> Option<IdentityAssociation> {
+    if value.len() < 12 {
+        return None;
+    }
+    let u32_at = |at: usize| u32::from_be_bytes([value[at], value[at + 1], value[at + 2], value[at + 3]]);
+
+    let mut ia = IdentityAssociation {
+        iaid: u32_at(0),
+        t1: u32_at(4),
+        t2: u32_at(8),
+        addresses: Vec::new(),
+        prefixes: Vec::new(),
+    };
+
+    for (code, option) in options(&value[12..]) {
+        match code {
+            // IA Address: address, preferred lifetime, valid lifetime
+            5 if option.len() >= 24 => ia.addresses.push(IaAddress {
+                address: ipv6_at(option, 0),
+                preferred_lifetime: u32::from_be_bytes([option[16], option[17], option[18], option[19]]),
+                valid_lifetime: u32::from_be_bytes([option[20], option[21], option[22], option[23]]),
+            }),
+            // IA Prefix: preferred lifetime, valid lifetime, prefix length, prefix
+            26 if option.len() >= 25 => ia.prefixes.push(IaPrefix {
+                prefix: ipv6_at(option, 9),
+                length: option[8],
+                preferred_lifetime: u32::from_be_bytes([option[0], option[1], option[2], option[3]]),
+                valid_lifetime: u32::from_be_bytes([option[4], option[5], option[6], option[7]]),
+            }),
+            _ => {}
+        }
+    }
+
+    Some(ia)
+}
+
+fn parse_status(value: &[u8]) -> Option<(u16, String)> {
+    if value.len() < 2 {
+        return None;
+    }
+    let code = u16::from_be_bytes([value[0], value[1]]);
+    Some((code, String::from_utf8_lossy(&value[2..]).into_owned()))
+}
+
+fn ipv6_at(data: &[u8], at: usize) -> Ipv6Addr {
+    let mut octets = [0u8; 16];
+    octets.copy_from_slice(&data[at..at + 16]);
+    Ipv6Addr::from(octets)
+}
+
+fn addresses(value: &[u8]) -> Vec<Ipv6Addr> {
+    value.chunks_exact(16).map(|chunk| ipv6_at(chunk, 0)).collect()
+}
+
+/// Decodes a list of uncompressed DNS names (RFC 8415 section 10).
+fn parse_domain_list(mut data: &[u8]) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut labels = Vec::new();
+
+    while let Some((&len, rest)) = data.split_first() {
+        if len == 0 {
+            names.push(labels.join("."));
+            labels.clear();
+            data = rest;
+            continue;
+        }
+        let Some(label) = rest.get(..len as usize) else {
+            break;
+        };
+        labels.push(String::from_utf8_lossy(label).into_owned());
+        data = &rest[len as usize..];
+    }
+
+    names
+}
@@ -1,31 +1,645 @@
-/// Parses the DNS queries from a DNS payload (after the 12-byte DNS header).
-/// Returns a vector of query domain names or an error string.
-pub fn parse_dns_queries(payload: &[u8]) -> Result<Vec<String>, &'static str> {
+use lazy_static::lazy_static;
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::sync::RwLock;
+
+lazy_static! {
+    /// Additional ports to decode as DNS, set from the command line
+    static ref EXTRA_DNS_PORTS: RwLock<Vec<u16>> = RwLock::new(Vec::new());
+}
+
+/// Registers extra ports whose traffic should be decoded as DNS.
+pub fn set_extra_dns_ports(ports: &[u16]) {
+    if let Ok(mut extra) = EXTRA_DNS_PORTS.write() {
+        *extra = ports.to_vec();
+    }
+}
+
+/// Which protocol that shares the DNS message format a message belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DnsProtocol {
+    #[default]
+    Dns,
+    /// Multicast DNS (RFC 6762)
+    Mdns,
+    /// Link-Local Multicast Name Resolution (RFC 4795)
+    Llmnr,
+}
+
+impl fmt::Display for DnsProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DnsProtocol::Dns => write!(f, "DNS"),
+            DnsProtocol::Mdns => write!(f, "mDNS"),
+            DnsProtocol::Llmnr => write!(f, "LLMNR"),
+        }
+    }
+}
+
+/// Classifies a port pair as carrying DNS, mDNS or LLMNR.
+pub fn dns_protocol_for_ports(src_port: u16, dst_port: u16) -> Option<DnsProtocol> {
+    let is_extra = |port: u16| EXTRA_DNS_PORTS.read().is_ok_and(|extra| extra.contains(&port));
+
+    for port in [src_port, dst_port] {
+        match port {
+            53 => return Some(DnsProtocol::Dns),
+            5353 => return Some(DnsProtocol::Mdns),
+            5355 => return Some(DnsProtocol::Llmnr),
+            _ => {}
+        }
+    }
+
+    (is_extra(src_port) || is_extra(dst_port)).then_some(DnsProtocol::Dns)
+}
+
+/// A decoded DNS message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DnsMessage {
+    /// Set by the caller from the ports the message was seen on
+    pub protocol: DnsProtocol,
+    pub id: u16,
+    pub is_response: bool,
+    pub opcode: u8,
+    pub authoritative: bool,
+    pub truncated: bool,
+    pub recursion_desired: bool,
+    pub recursion_available: bool,
+    pub authentic_data: bool,
+    pub checking_disabled: bool,
+    /// Response code, including the upper bits carried in the EDNS OPT record
+    pub rcode: u16,
+    pub questions: Vec<DnsQuestion>,
+    pub answers: Vec<DnsRecord>,
+    pub authorities: Vec<DnsRecord>,
+    /// Additional records other than the EDNS OPT pseudo-record
+    pub additionals: Vec<DnsRecord>,
+    pub edns: Option<Edns>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DnsQuestion {
+    pub name: String,
+    pub qtype: u16,
+    pub qclass: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DnsRecord {
+    pub name: String,
+    pub rtype: u16,
+    pub class: u16,
+    pub ttl: u32,
+    pub data: RecordData,
+}
+
+/// Decoded RDATA of a resource record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordData {
+    A(Ipv4Addr),
+    Aaaa(Ipv6Addr),
+    Cname(String),
+    Ns(String),
+    Ptr(String),
+    Mx { preference: u16, exchange: String },
+    Txt(Vec<String>),
+    Soa {
+        mname: String,
+        rname: String,
+        serial: u32,
+        refresh: u32,
+        retry: u32,
+        expire: u32,
+        minimum: u32,
+    },
+    Srv { priority: u16, weight: u16, port: u16, target: String },
+    /// SVCB and HTTPS records share the same layout
+    Svcb { priority: u16, target: String, params: Vec<SvcParam> },
+    /// Any other type, or RDATA that did not decode
+    Unknown(Vec<u8>),
+}
+
+/// A SvcParam key/value pair from an SVCB or HTTPS record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SvcParam {
+    pub key: u16,
+    pub value: Vec<u8>,
+}
+
+/// EDNS(0) information from the OPT pseudo-record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edns {
+    pub udp_payload_size: u16,
+    pub version: u8,
+    pub dnssec_ok: bool,
+    /// (option code, option data)
+    pub options: Vec<(u16, Vec<u8>)>,
+}
+
+/// Mnemonic for a record type, e.g. `AAAA`, or `TYPE<n>` for unlisted ones.
+pub fn type_name(rtype: u16) -> String {
+    let name = match rtype {
+        1 => "A",
+        2 => "NS",
+        5 => "CNAME",
+        6 => "SOA",
+        12 => "PTR",
+        15 => "MX",
+        16 => "TXT",
+        28 => "AAAA",
+        33 => "SRV",
+        41 => "OPT",
+        43 => "DS",
+        46 => "RRSIG",
+        47 => "NSEC",
+        48 => "DNSKEY",
+        64 => "SVCB",
+        65 => "HTTPS",
+        252 => "AXFR",
+        255 => "ANY",
+        _ => return format!("TYPE{}", rtype),
+    };
+    name.to_string()
+}
+
+/// Mnemonic for a response code, e.g. `NXDOMAIN`.
+pub fn rcode_name(rcode: u16) -> String {
+    let name = match rcode {
+        0 => "NOERROR",
+        1 => "FORMERR",
+        2 => "SERVFAIL",
+        3 => "NXDOMAIN",
+        4 => "NOTIMP",
+        5 => "REFUSED",
+        6 => "YXDOMAIN",
+        7 => "YXRRSET",
+        8 => "NXRRSET",
+        9 => "NOTAUTH",
+        10 => "NOTZONE",
+        16 => "BADVERS",
+        _ => return format!("RCODE{}", rcode),
+    };
+    name.to_string()
+}
+
+fn opcode_name(opcode: u8) -> String {
+    match opcode {
+        0 => "Standard query".into(),
+        1 => "Inverse query".into(),
+        2 => "Server status request".into(),
+        4 => "Zone change notification".into(),
+        5 => "Dynamic update".into(),
+        _ => format!("Opcode {} query", opcode),
+    }
+}
+
+/// Formats a domain name for display, showing the root as `<Root>`.
+fn display_name(name: &str) -> &str {
+    if name.is_empty() { "<Root>" } else { name }
+}
+
+impl DnsMessage {
+    /// Question names, for the `dns_queries` column.
+    pub fn query_names(&self) -> Vec<String> {
+        self.questions.iter().map(|q| q.name.clone()).collect()
+    }
+
+    /// Wireshark-style one-liner, e.g.
+    /// `Standard query response 0x1a2b A example.com A 93.184.216.34`.
+    pub fn summary(&self) -> String {
+        let mut summary = match self.protocol {
+            DnsProtocol::Dns => String::new(),
+            protocol => format!("{}: ", protocol),
+        };
+        summary.push_str(&opcode_name(self.opcode));
+        if self.is_response {
+            summary.push_str(" response");
+        }
+        summary.push_str(&format!(" 0x{:04x}", self.id));
+
+        if self.is_response && self.rcode != 0 {
+            summary.push(' ');
+            summary.push_str(&rcode_name(self.rcode));
+        }
+        for question in &self.questions {
+            summary.push_str(&format!(" {} {}", type_name(question.qtype), display_name(&question.name)));
+        }
+        for answer in &self.answers {
+            summary.push_str(&format!(" {} {}", type_name(answer.rtype), answer.data));
+        }
+        summary
+    }
+
+    /// Multi-line listing of the header and all sections.
+    pub fn details(&self) -> String {
+        let mut flags = Vec::new();
+        if self.authoritative { flags.push("aa"); }
+        if self.truncated { flags.push("tc"); }
+        if self.recursion_desired { flags.push("rd"); }
+        if self.recursion_available { flags.push("ra"); }
+        if self.authentic_data { flags.push("ad"); }
+        if self.checking_disabled { flags.push("cd"); }
+
+        let mut lines = vec![format!(
+            "ID 0x{:04x}, {}{}, {}, flags: {}",
+            self.id,
+            opcode_name(self.opcode),
+            if self.is_response { " response" } else { "" },
+            rcode_name(self.rcode),
+            flags.join(" ")
+        )];
+
+        if let Some(edns) = &self.edns {
+            lines.push(edns.to_string());
+        }
+
+        if !self.questions.is_empty() {
+            lines.push("Questions:".into());
+            for question in &self.questions {
+                lines.push(format!("  {}", question));
+            }
+        }
+
+        for (title, records) in [
+            ("Answers:", &self.answers),
+            ("Authority:", &self.authorities),
+            ("Additional:", &self.additionals),
+        ] {
+            if !records.is_empty() {
+                lines.push(title.into());
+                for record in records {
+                    lines.push(format!("  {}", record));
+                }
+            }
+        }
+
+        lines.join("\n")
+    }
+}
+
+impl fmt::Display for DnsQuestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", display_name(&self.name), class_name(self.qclass), type_name(self.qtype))
+    }
+}
+
+impl fmt::Display for DnsRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {}",
+            display_name(&self.name),
+            self.ttl,
+            class_name(self.class),
+            type_name(self.rtype),
+            self.data
+        )
+    }
+}
+
+fn class_name(class: u16) -> String {
+    // The top bit is the mDNS unicast-response / cache-flush flag
+    match class & 0x7FFF {
+        1 => "IN".into(),
+        3 => "CH".into(),
+        4 => "HS".into(),
+        255 => "ANY".into(),
+        other => format!("CLASS{}", other),
+    }
+}
+
+impl fmt::Display for RecordData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordData::A(addr) => write!(f, "{}", addr),
+            RecordData::Aaaa(addr) => write!(f, "{}", addr),
+            RecordData::Cname(name) | RecordData::Ns(name) | RecordData::Ptr(name) => {
+                write!(f, "{}", display_name(name))
+            }
+            RecordData::Mx { preference, exchange } => write!(f, "{} {}", preference, display_name(exchange)),
+            RecordData::Txt(strings) => {
+                let quoted: Vec<String> = strings.iter().map(|s| format!("{:?}", s)).collect();
+                write!(f, "{}", quoted.join(" "))
+            }
+            RecordData::Soa { mname, rname, serial, refresh, retry, expire, minimum } => write!(
+                f,
+                "{} {} {} {} {} {} {}",
+                display_name(mname),
+                display_name(rname),
+                serial,
+                refresh,
+                retry,
+                expire,
+                minimum
+            ),
+            RecordData::Srv { priority, weight, port, target } => {
+                write!(f, "{} {} {} {}", priority, weight, port, display_name(target))
+            }
+            RecordData::Svcb { priority, target, params } => {
+                write!(f, "{} {}", priority, if target.is_empty() { "." } else { target })?;
+                for param in params {
+                    write!(f, " {}", param)?;
+                }
+                Ok(())
+            }
+            RecordData::Unknown(data) => write!(f, "({} bytes)", data.len()),
+        }
+    }
+}
+
+impl fmt::Display for SvcParam {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = &self.value;
+        match self.key {
+            0 => {
+                let keys: Vec<String> = value
+                    .chunks_exact(2)
+                    .map(|key| svc_key_name(u16::from_be_bytes([key[0], key[1]])))
+                    .collect();
+                write!(f, "mandatory={}", keys.join(","))
+            }
+            1 => write!(f, "alpn={}", character_strings(value).join(",")),
+            2 => write!(f, "no-default-alpn"),
+            3 if value.len() == 2 => write!(f, "port={}", u16::from_be_bytes([value[0], value[1]])),
+            4 => {
+                let hints: Vec<String> = value
+                    .chunks_exact(4)
+                    .map(|a| Ipv4Addr::new(a[0], a[1], a[2], a[3]).to_string())
+                    .collect();
+                write!(f, "ipv4hint={}", hints.join(","))
+            }
+            6 => {
+                let hints: Vec<String> = value
+                    .chunks_exact(16)
+                    .filter_map(|a| <[u8; 16]>::try_from(a).ok())
+                    .map(|a| Ipv6Addr::from(a).to_string())
+                    .collect();
+                write!(f, "ipv6hint={}", hints.join(","))
+            }
+            key => write!(f, "{}=({} bytes)", svc_key_name(key), value.len()),
+        }
+    }
+}
+
+fn svc_key_name(key: u16) -> String {
+    match key {
+        0 => "mandatory".into(),
+        1 => "alpn".into(),
+        2 => "no-default-alpn".into(),
+        3 => "port".into(),
+        4 => "ipv4hint".into(),
+        5 => "ech".into(),
+        6 => "ipv6hint".into(),
+        key => format!("key{}", key),
+    }
+}
+
+impl fmt::Display for Edns {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EDNS{} udp={}", self.version, self.udp_payload_size)?;
+        if self.dnssec_ok {
+            write!(f, " do")?;
+        }
+        for (code, data) in &self.options {
+            let name = match code {
+                3 => "NSID".to_string(),
+                8 => "ECS".to_string(),
+                10 => "COOKIE".to_string(),
+                12 => "PADDING".to_string(),
+                15 => "EDE".to_string(),
+                code => format!("OPT{}", code),
+            };
+            write!(f, " {}({})", name, data.len())?;
+        }
+        Ok(())
+    }
+}
+
+/// Splits RDATA into its length-prefixed character-strings.
+fn character_strings(mut data: &[u8]) -> Vec<String> {
+    let mut strings = Vec::new();
+    while let Some((&len, rest)) = data.split_first() {
+        let len = (len as usize).min(rest.len());
+        strings.push(String::from_utf8_lossy(&rest[..len]).into_owned());
+        data = &rest[len..];
+    }
+    strings
+}
+
+/// Parses a complete DNS message (header, question and resource record sections).
+///
+/// The header and questions must decode. A record section that runs past the end
+/// of a truncated capture keeps the records decoded up to that point.
+pub fn parse_dns_message(payload: &[u8]) -> Result<DnsMessage, &'static str> {
+    parse_message(payload).map(|(message, _)| message)
+} // parse_dns_message
+
+/// Recognizes DNS on ports not known to carry it. Only well-formed standard
+/// queries and responses with a single question that span the whole payload qualify.
+pub fn looks_like_dns(payload: &[u8]) -> Option<DnsMessage> {
+    if payload.len() < 12 {
+        return None;
+    }
+    let flags = u16::from_be_bytes([payload[2], payload[3]]);
+    let qdcount = u16::from_be_bytes([payload[4], payload[5]]);
+    // Standard query opcode, reserved Z bit clear, exactly one question
+    if flags & 0x7840 != 0 || qdcount != 1 {
+        return None;
+    }
+
+    match parse_message(payload) {
+        Ok((message, true)) => Some(message),
+        _ => None,
+    }
+}
+
+/// Parses a message and reports whether every record decoded and the message
+/// ended exactly at the end of the payload.
+fn parse_message(payload: &[u8]) -> Result<(DnsMessage, bool), &'static str> {
     if payload.len() < 12 {
         return Err("DNS payload too short");
     }
 
+    let flags = u16::from_be_bytes([payload[2], payload[3]]);
     let qdcount = u16::from_be_bytes([payload[4], payload[5]]);
-    if qdcount == 0 {
-        return Ok(vec![]);
-    }
+    let ancount = u16::from_be_bytes([payload[6], payload[7]]);
+    let nscount = u16::from_be_bytes([payload[8], payload[9]]);
+    let arcount = u16::from_be_bytes([payload[10], payload[11]]);
+
+    let mut message = DnsMessage {
+        protocol: DnsProtocol::Dns,
+        id: u16::from_be_bytes([payload[0], payload[1]]),
+        is_response: flags & 0x8000 != 0,
+        opcode: ((flags >> 11) & 0x0F) as u8,
+        authoritative: flags & 0x0400 != 0,
+        truncated: flags & 0x0200 != 0,
+        recursion_desired: flags & 0x0100 != 0,
+        recursion_available: flags & 0x0080 != 0,
+        authentic_data: flags & 0x0020 != 0,
+        checking_disabled: flags & 0x0010 != 0,
+        rcode: flags & 0x000F,
+        questions: Vec::new(),
+        answers: Vec::new(),
+        authorities: Vec::new(),
+        additionals: Vec::new(),
+        edns: None,
+    };
 
-    let mut queries = Vec::new();
     let mut offset = 12;
 
     for _ in 0..qdcount {
         let (name, next_offset) = parse_dns_name(payload, offset, 0)?;
-        queries.push(name);
-
-        // Skip QTYPE(2 bytes) + QCLASS(2 bytes)
+        if next_offset + 4 > payload.len() {
+            return Err("DNS question truncated");
+        }
+        message.questions.push(DnsQuestion {
+            name,
+            qtype: u16::from_be_bytes([payload[next_offset], payload[next_offset + 1]]),
+            qclass: u16::from_be_bytes([payload[next_offset + 2], payload[next_offset + 3]]),
+        });
         offset = next_offset + 4;
-        if offset > payload.len() {
-            break;
+    }
+
+    let mut complete = true;
+    let sections = [ancount, nscount, arcount];
+    'sections: for (section, count) in sections.into_iter().enumerate() {
+        for _ in 0..count {
+            let Ok((record, next_offset)) = parse_record(payload, offset) else {
+                complete = false;
+                break 'sections;
+            };
+            offset = next_offset;
+
+            match section {
+                0 => message.answers.push(record),
+                1 => message.authorities.push(record),
+                _ if record.rtype == 41 => {
+                    // OPT: class carries the UDP payload size, TTL the extended rcode and flags
+                    message.rcode |= ((record.ttl >> 24) as u16) << 4;
+                    message.edns = Some(Edns {
+                        udp_payload_size: record.class,
+                        version: (record.ttl >> 16) as u8,
+                        dnssec_ok: record.ttl & 0x8000 != 0,
+                        options: match record.data {
+                            RecordData::Unknown(data) => edns_options(&data),
+                            _ => Vec::new(),
+                        },
+                    });
+                }
+                _ => message.additionals.push(record),
+            }
         }
     }
 
-    Ok(queries)
-} // parse_dns_queries
+    Ok((message, complete && offset == payload.len()))
+} // parse_message
+
+/// Parses one resource record at `offset`, returning it and the offset after it.
+fn parse_record(payload: &[u8], offset: usize) -> Result<(DnsRecord, usize), &'static str> {
+    let (name, pos) = parse_dns_name(payload, offset, 0)?;
+    if pos + 10 > payload.len() {
+        return Err("DNS record truncated");
+    }
+
+    let rtype = u16::from_be_bytes([payload[pos], payload[pos + 1]]);
+    let class = u16::from_be_bytes([payload[pos + 2], payload[pos + 3]]);
+    let ttl = u32::from_be_bytes([payload[pos + 4], payload[pos + 5], payload[pos + 6], payload[pos + 7]]);
+    let rdlength = u16::from_be_bytes([payload[pos + 8], payload[pos + 9]]) as usize;
+    let rdata_start = pos + 10;
+    let rdata_end = rdata_start + rdlength;
+    if rdata_end > payload.len() {
+        return Err("DNS record data truncated");
+    }
+
+    let rdata = &payload[rdata_start..rdata_end];
+    let data = parse_rdata(payload, rtype, rdata_start, rdata)
+        .unwrap_or_else(|_| RecordData::Unknown(rdata.to_vec()));
+
+    Ok((DnsRecord { name, rtype, class, ttl, data }, rdata_end))
+}
+
+/// Decodes RDATA. Names inside RDATA may be compressed, so they are read from the
+/// whole message at `start` rather than from the `rdata` slice.
+fn parse_rdata(payload: &[u8], rtype: u16, start: usize, rdata: &[u8]) -> Result<RecordData, &'static str> {
+    let u16_at = |at: usize| -> Result<u16, &'static str> {
+        rdata
+            .get(at..at + 2)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]))
+            .ok_or("DNS record data too short")
+    };
+    let u32_at = |at: usize| -> Result<u32, &'static str> {
+        rdata
+            .get(at..at + 4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+            .ok_or("DNS record data too short")
+    };
+
+    let data = match rtype {
+        1 => {
+            let octets: [u8; 4] = rdata.try_into().map_err(|_| "Invalid A record length")?;
+            RecordData::A(Ipv4Addr::from(octets))
+        }
+        28 => {
+            let octets: [u8; 16] = rdata.try_into().map_err(|_| "Invalid AAAA record length")?;
+            RecordData::Aaaa(Ipv6Addr::from(octets))
+        }
+        2 => RecordData::Ns(parse_dns_name(payload, start, 0)?.0),
+        5 => RecordData::Cname(parse_dns_name(payload, start, 0)?.0),
+        12 => RecordData::Ptr(parse_dns_name(payload, start, 0)?.0),
+        15 => RecordData::Mx {
+            preference: u16_at(0)?,
+            exchange: parse_dns_name(payload, start + 2, 0)?.0,
+        },
+        16 => RecordData::Txt(character_strings(rdata)),
+        6 => {
+            let (mname, pos) = parse_dns_name(payload, start, 0)?;
+            let (rname, pos) = parse_dns_name(payload, pos, 0)?;
+            let fixed = pos - start;
+            RecordData::Soa {
+                mname,
+                rname,
+                serial: u32_at(fixed)?,
+                refresh: u32_at(fixed + 4)?,
+                retry: u32_at(fixed + 8)?,
+                expire: u32_at(fixed + 12)?,
+                minimum: u32_at(fixed + 16)?,
+            }
+        }
+        33 => RecordData::Srv {
+            priority: u16_at(0)?,
+            weight: u16_at(2)?,
+            port: u16_at(4)?,
+            target: parse_dns_name(payload, start + 6, 0)?.0,
+        },
+        64 | 65 => {
+            let (target, pos) = parse_dns_name(payload, start + 2, 0)?;
+            let mut at = pos - start;
+            let mut params = Vec::new();
+            while at < rdata.len() {
+                let key = u16_at(at)?;
+                let len = u16_at(at + 2)? as usize;
+                let value = rdata.get(at + 4..at + 4 + len).ok_or("SvcParam value truncated")?;
+                params.push(SvcParam { key, value: value.to_vec() });
+                at += 4 + len;
+            }
+            RecordData::Svcb { priority: u16_at(0)?, target, params }
+        }
+        _ => RecordData::Unknown(rdata.to_vec()),
+    };
+
+    Ok(data)
+}
+
+/// Splits OPT RDATA into (code, data) options.
+fn edns_options(mut data: &[u8]) -> Vec<(u16, Vec<u8>)> {
+    let mut options = Vec::new();
+    while data.len() >= 4 {
+        let code = u16::from_be_bytes([data[0], data[1]]);
+        let len = (u16::from_be_bytes([data[2], data[3]]) as usize).min(data.len() - 4);
+        options.push((code, data[4..4 + len].to_vec()));
+        data = &data[4 + len..];
+    }
+    options
+}
 
 /// Parses a DNS name from the payload starting at the given offset.
 /// Supports compression pointers.
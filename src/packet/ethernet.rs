@@ -1,8 +1,8 @@
 use super::{PacketInfo, PacketType};
-use crate::packet::{ipv4, ipv6};
+use crate::packet::{arp, ipv4, ipv6, vlan};
 
 /// Entry point for parsing Ethernet-based packets.
-pub fn parse_packet(data: &[u8]) -> Result<PacketInfo, &'static str> {
+pub fn parse_ethernet(data: &[u8], info: &mut PacketInfo) -> Result<(), &'static str> {
     if data.len() < 14 {
         return Err("Packet too short for Ethernet header");
     }
@@ -11,25 +11,35 @@ pub fn parse_packet(data: &[u8]) -> Result<PacketInfo, &'static str> {
     let src_mac = array_from_slice(&data[6..12]);
     let dst_mac = array_from_slice(&data[0..6]);
 
-    let mut info = PacketInfo {
-        packet_type: PacketType::Ethernet,
-        src_mac,
-        dst_mac,
-        src_ip: None,
-        dst_ip: None,
-        src_port: None,
-        dst_port: None,
-        tcp_flags: None,
-        dns_queries: None,
-    };
+    info.packet_type = PacketType::Ethernet;
+    info.src_mac = src_mac;
+    info.dst_mac = dst_mac;
+
+    parse_ethertype(ethertype, &data[14..], info)
+}
+
+/// Dispatches the payload of any link layer that carries an EtherType
+/// (Ethernet, Linux cooked captures) to the matching network-layer parser.
+/// Any number of stacked VLAN tags is peeled off first and recorded in `info`.
+pub fn parse_ethertype(ethertype: u16, payload: &[u8], info: &mut PacketInfo) -> Result<(), &'static str> {
+    let mut ethertype = ethertype;
+    let mut payload = payload;
+
+    while vlan::is_vlan_tpid(ethertype) {
+        let (tag, inner_ethertype, rest) = vlan::parse_vlan_tag(ethertype, payload)?;
+        info.vlan_tags.push(tag);
+        ethertype = inner_ethertype;
+        payload = rest;
+    }
 
     match ethertype {
-        0x0800 => ipv4::parse_ipv4(&data[14..], &mut info)?,
-        0x86DD => ipv6::parse_ipv6(&data[14..], &mut info)?,
+        0x0800 => ipv4::parse_ipv4(payload, info)?,
+        0x86DD => ipv6::parse_ipv6(payload, info)?,
+        0x0806 => arp::parse_arp(payload, info)?,
         other => info.packet_type = PacketType::Other(other),
     }
 
-    Ok(info)
+    Ok(())
 }
 
 /// Utility: turn 6-byte slice into MAC array
@@ -42,3 +52,8 @@ pub fn array_from_slice(slice: &[u8]) -> Option<[u8; 6]> {
         None
     }
 }
+
+/// Formats a MAC address as colon separated hex.
+pub fn format_mac(mac: &[u8; 6]) -> String {
+    mac.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(":")
+}
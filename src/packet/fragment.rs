@@ -0,0 +1,46 @@
+use std::fmt;
+
+/// A fragment of an IPv4 or IPv6 datagram.
+///
+/// The parsers record fragments without decoding the transport layer; the
+/// reassembler in the capture loop collects them and decodes the datagram once
+/// the final missing fragment arrives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IpFragment {
+    /// IPv4 identification (16 bits) or IPv6 fragment identification (32 bits)
+    pub identification: u32,
+    /// Byte offset of this fragment's data within the original datagram
+    pub offset: usize,
+    pub more_fragments: bool,
+    /// Protocol (IPv4) or next header (IPv6) of the fragmented datagram
+    pub protocol: u8,
+    /// Fragment data, taken by the reassembler once buffered
+    pub payload: Vec<u8>,
+    /// Set on the fragment that completed its datagram
+    pub reassembled: Option<Reassembly>,
+}
+
+/// Result of reassembling a fragmented datagram.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reassembly {
+    pub fragments: usize,
+    /// Length of the reassembled transport payload in bytes
+    pub length: usize,
+}
+
+impl fmt::Display for IpFragment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Fragment id 0x{:x} off {}", self.identification, self.offset)?;
+        if self.more_fragments {
+            write!(f, " MF")?;
+        }
+        if let Some(reassembly) = &self.reassembled {
+            write!(
+                f,
+                " (reassembled {} bytes from {} fragments)",
+                reassembly.length, reassembly.fragments
+            )?;
+        }
+        Ok(())
+    }
+}
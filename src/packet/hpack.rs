@@ -0,0 +1,506 @@
+use lazy_static::lazy_static;
+use std::collections::VecDeque;
+
+/// Upper bound on the dynamic table size a peer may select, to bound memory
+const MAX_TABLE_SIZE: usize = 64 * 1024;
+/// Per-entry overhead counted against the table size (RFC 7541 section 4.1)
+const ENTRY_OVERHEAD: usize = 32;
+
+/// Static table (RFC 7541 Appendix A); index 1 is the first entry
+const STATIC_TABLE: [(&str, &str); 61] = [
+    (":authority", ""),
+    (":method", "GET"),
+    (":method", "POST"),
+    (":path", "/"),
+    (":path", "/index.html"),
+    (":scheme", "http"),
+    (":scheme", "https"),
+    (":status", "200"),
+    (":status", "204"),
+    (":status", "206"),
+    (":status", "304"),
+    (":status", "400"),
+    (":status", "404"),
+    (":status", "500"),
+    ("accept-charset", ""),
+    ("accept-encoding", "gzip, deflate"),
+    ("accept-language", ""),
+    ("accept-ranges", ""),
+    ("accept", ""),
+    ("access-control-allow-origin", ""),
+    ("age", ""),
+    ("allow", ""),
+    ("authorization", ""),
+    ("cache-control", ""),
+    ("content-disposition", ""),
+    ("content-encoding", ""),
+    ("content-language", ""),
+    ("content-length", ""),
+    ("content-location", ""),
+    ("content-range", ""),
+    ("content-type", ""),
+    ("cookie", ""),
+    ("date", ""),
+    ("etag", ""),
+    ("expect", ""),
+    ("expires", ""),
+    ("from", ""),
+    ("host", ""),
+    ("if-match", ""),
+    ("if-modified-since", ""),
+    ("if-none-match", ""),
+    ("if-range", ""),
+    ("if-unmodified-since", ""),
+    ("last-modified", ""),
+    ("link", ""),
+    ("location", ""),
+    ("max-forwards", ""),
+    ("proxy-authenticate", ""),
+    ("proxy-authorization", ""),
+    ("range", ""),
+    ("referer", ""),
+    ("refresh", ""),
+    ("retry-after", ""),
+    ("server", ""),
+    ("set-cookie", ""),
+    ("strict-transport-security", ""),
+    ("transfer-encoding", ""),
+    ("user-agent", ""),
+    ("vary", ""),
+    ("via", ""),
+    ("www-authenticate", ""),
+];
+
+/// Huffman code and bit length for each symbol, 256 being EOS (RFC 7541 Appendix B)
+const HUFFMAN_CODES: [(u32, u8); 257] = [
+    (0x1ff8, 13), (0x7fffd8, 23), (0xfffffe2, 28), (0xfffffe3, 28),
+    (0xfffffe4, 28), (0xfffffe5, 28), (0xfffffe6, 28), (0xfffffe7, 28),
+    (0xfffffe8, 28), (0xffffea, 24), (0x3ffffffc, 30), (0xfffffe9, 28),
+    (0xfffffea, 28), (0x3ffffffd, 30), (0xfffffeb, 28), (0xfffffec, 28),
+    (0xfffffed, 28), (0xfffffee, 28), (0xfffffef, 28), (0xffffff0, 28),
+    (0xffffff1, 28), (0xffffff2, 28), (0x3ffffffe, 30), (0xffffff3, 28),
+    (0xffffff4, 28), (0xffffff5, 28), (0xffffff6, 28), (0xffffff7, 28),
+    (0xffffff8, 28), (0xffffff9, 28), (0xffffffa, 28), (0xffffffb, 28),
+    (0x14, 6), (0x3f8, 10), (0x3f9, 10), (0xffa, 12),
+    (0x1ff9, 13), (0x15, 6), (0xf8, 8), (0x7fa, 11),
+    (0x3fa, 10), (0x3fb, 10), (0xf9, 8), (0x7fb, 11),
+    (0xfa, 8), (0x16, 6), (0x17, 6), (0x18, 6),
+    (0x0, 5), (0x1, 5), (0x2, 5), (0x19, 6),
+    (0x1a, 6), (0x1b, 6), (0x1c, 6), (0x1d, 6),
+    (0x1e, 6), (0x1f, 6), (0x5c, 7), (0xfb, 8),
+    (0x7ffc, 15), (0x20, 6), (0xffb, 12), (0x3fc, 10),
+    (0x1ffa, 13), (0x21, 6), (0x5d, 7), (0x5e, 7),
+    (0x5f, 7), (0x60, 7), (0x61, 7), (0x62, 7),
+    (0x63, 7), (0x64, 7), (0x65, 7), (0x66, 7),
+    (0x67, 7), (0x68, 7), (0x69, 7), (0x6a, 7),
+    (0x6b, 7), (0x6c, 7), (0x6d, 7), (0x6e, 7),
+    (0x6f, 7), (0x70, 7), (0x71, 7), (0x72, 7),
+    (0xfc, 8), (0x73, 7), (0xfd, 8), (0x1ffb, 13),
+    (0x7fff0, 19), (0x1ffc, 13), (0x3ffc, 14), (0x22, 6),
+    (0x7ffd, 15), (0x3, 5), (0x23, 6), (0x4, 5),
+    (0x24, 6), (0x5, 5), (0x25, 6), (0x26, 6),
+    (0x27, 6), (0x6, 5), (0x74, 7), (0x75, 7),
+    (0x28, 6), (0x29, 6), (0x2a, 6), (0x7, 5),
+    (0x2b, 6), (0x76, 7), (0x2c, 6), (0x8, 5),
+    (0x9, 5), (0x2d, 6), (0x77, 7), (0x78, 7),
+    (0x79, 7), (0x7a, 7), (0x7b, 7), (0x7ffe, 15),
+    (0x7fc, 11), (0x3ffd, 14), (0x1ffd, 13), (0xffffffc, 28),
+    (0xfffe6, 20), (0x3fffd2, 22), (0xfffe7, 20), (0xfffe8, 20),
+    (0x3fffd3, 22), (0x3fffd4, 22), (0x3fffd5, 22), (0x7fffd9, 23),
+    (0x3fffd6, 22), (0x7fffda, 23), (0x7fffdb, 23), (0x7fffdc, 23),
+    (0x7fffdd, 23), (0x7fffde, 23), (0xffffeb, 24), (0x7fffdf, 23),
+    (0xffffec, 24), (0xffffed, 24), (0x3fffd7, 22), (0x7fffe0, 23),
+    (0xffffee, 24), (0x7fffe1, 23), (0x7fffe2, 23), (0x7fffe3, 23),
+    (0x7fffe4, 23), (0x1fffdc, 21), (0x3fffd8, 22), (0x7fffe5, 23),
+    (0x3fffd9, 22), (0x7fffe6, 23), (0x7fffe7, 23), (0xffffef, 24),
+    (0x3fffda, 22), (0x1fffdd, 21), (0xfffe9, 20), (0x3fffdb, 22),
+    (0x3fffdc, 22), (0x7fffe8, 23), (0x7fffe9, 23), (0x1fffde, 21),
+    (0x7fffea, 23), (0x3fffdd, 22), (0x3fffde, 22), (0xfffff0, 24),
+    (0x1fffdf, 21), (0x3fffdf, 22), (0x7fffeb, 23), (0x7fffec, 23),
+    (0x1fffe0, 21), (0x1fffe1, 21), (0x3fffe0, 22), (0x1fffe2, 21),
+    (0x7fffed, 23), (0x3fffe1, 22), (0x7fffee, 23), (0x7fffef, 23),
+    (0xfffea, 20), (0x3fffe2, 22), (0x3fffe3, 22), (0x3fffe4, 22),
+    (0x7ffff0, 23), (0x3fffe5, 22), (0x3fffe6, 22), (0x7ffff1, 23),
+    (0x3ffffe0, 26), (0x3ffffe1, 26), (0xfffeb, 20), (0x7fff1, 19),
+    (0x3fffe7, 22), (0x7ffff2, 23), (0x3fffe8, 22), (0x1ffffec, 25),
+    (0x3ffffe2, 26), (0x3ffffe3, 26), (0x3ffffe4, 26), (0x7ffffde, 27),
+    (0x7ffffdf, 27), (0x3ffffe5, 26), (0xfffff1, 24), (0x1ffffed, 25),
+    (0x7fff2, 19), (0x1fffe3, 21), (0x3ffffe6, 26), (0x7ffffe0, 27),
+    (0x7ffffe1, 27), (0x3ffffe7, 26), (0x7ffffe2, 27), (0xfffff2, 24),
+    (0x1fffe4, 21), (0x1fffe5, 21), (0x3ffffe8, 26), (0x3ffffe9, 26),
+    (0xffffffd, 28), (0x7ffffe3, 27), (0x7ffffe4, 27), (0x7ffffe5, 27),
+    (0xfffec, 20), (0xfffff3, 24), (0xfffed, 20), (0x1fffe6, 21),
+    (0x3fffe9, 22), (0x1fffe7, 21), (0x1fffe8, 21), (0x7ffff3, 23),
+    (0x3fffea, 22), (0x3fffeb, 22), (0x1ffffee, 25), (0x1ffffef, 25),
+    (0xfffff4, 24), (0xfffff5, 24), (0x3ffffea, 26), (0x7ffff4, 23),
+    (0x3ffffeb, 26), (0x7ffffe6, 27), (0x3ffffec, 26), (0x3ffffed, 26),
+    (0x7ffffe7, 27), (0x7ffffe8, 27), (0x7ffffe9, 27), (0x7ffffea, 27),
+    (0x7ffffeb, 27), (0xffffffe, 28), (0x7ffffec, 27), (0x7ffffed, 27),
+    (0x7ffffee, 27), (0x7ffffef, 27), (0x7fffff0, 27), (0x3ffffee, 26),
+    (0x3fffffff, 30),
+];
+
+lazy_static! {
+    /// Binary decoding tree over HUFFMAN_CODES: each node holds the child for bit 0
+    /// and bit 1, where values of 512 and above are leaves for symbol `value - 512`.
+    static ref HUFFMAN_TREE: Vec<[u16; 2]> = build_huffman_tree();
+}
+
+const LEAF: u16 = 512;
+
+fn build_huffman_tree() -> Vec<[u16; 2]> {
+    let mut tree = vec![[0u16; 2]];
+    for (symbol, &(code, bits)) in HUFFMAN_CODES.iter().enumerate() {
+        let mut node = 0;
+        for i in (0..bits).rev() {
+            let bit = ((code >> i) & 1) as usize;
+            if i == 0 {
+                tree[node][bit] = LEAF + symbol as u16;
+            } else {
+                if tree[node][bit] == 0 {
+                    tree.push([0, 0]);
+                    tree[node][bit] = (tree.len() - 1) as u16;
+                }
+                node = tree[node][bit] as usize;
+            }
+        }
+    }
+    tree
+}
+
+/// Decodes a Huffman-encoded string literal.
+fn huffman_decode(data: &[u8]) -> Result<Vec<u8>, &'static str> {
+    let mut decoded = Vec::with_capacity(data.len() * 8 / 5);
+    let mut node = 0usize;
+    // Bits read since the last complete symbol
+    let mut depth = 0;
+    let mut bits = 0u32;
+    for &byte in data {
+        for i in (0..8).rev() {
+            let bit = (byte >> i) & 1;
+            let next = HUFFMAN_TREE[node][bit as usize];
+            depth += 1;
+            bits = (bits << 1) | bit as u32;
+            if next >= LEAF {
+                if next - LEAF == 256 {
+                    return Err("HPACK Huffman string contains EOS");
+                }
+                decoded.push((next - LEAF) as u8);
+                node = 0;
+                depth = 0;
+                bits = 0;
+            } else if next == 0 {
+                return Err("HPACK Huffman code invalid");
+            } else {
+                node = next as usize;
+            }
+        }
+    }
+    // Padding is the most significant bits of EOS, all ones, shorter than a byte
+    if depth > 7 {
+        return Err("HPACK Huffman padding too long");
+    }
+    if bits != (1 << depth) - 1 {
+        return Err("HPACK Huffman padding is not an EOS prefix");
+    }
+    Ok(decoded)
+}
+
+/// HPACK header block decoder (RFC 7541). Each direction of an HTTP/2
+/// connection has its own decoder, since the dynamic table is per direction.
+#[derive(Debug)]
+pub struct HpackDecoder {
+    /// Newest entry first. Entries stay raw bytes so their sizes match the encoder's
+    /// even when a name or value is not valid UTF-8.
+    dynamic: VecDeque<(Vec<u8>, Vec<u8>)>,
+    size: usize,
+    max_size: usize,
+}
+
+impl Default for HpackDecoder {
+    fn default() -> Self {
+        HpackDecoder {
+            dynamic: VecDeque::new(),
+            size: 0,
+            max_size: 4096,
+        }
+    }
+}
+
+impl HpackDecoder {
+    /// Decodes a complete header block into (name, value) pairs in order.
+    pub fn decode(&mut self, mut block: &[u8]) -> Result<Vec<(String, String)>, &'static str> {
+        let mut headers = Vec::new();
+
+        while let Some(&first) = block.first() {
+            if first & 0x80 != 0 {
+                // Indexed header field
+                let index = decode_integer(&mut block, 7)?;
+                headers.push(to_header(self.lookup(index)?));
+            } else if first & 0x40 != 0 {
+                // Literal with incremental indexing
+                let header = self.decode_literal(&mut block, 6)?;
+                self.insert(header.clone());
+                headers.push(to_header(header));
+            } else if first & 0x20 != 0 {
+                // Dynamic table size update
+                let size = decode_integer(&mut block, 5)?;
+                self.max_size = size.min(MAX_TABLE_SIZE);
+                self.evict();
+            } else {
+                // Literal without indexing (0000) or never indexed (0001)
+                headers.push(to_header(self.decode_literal(&mut block, 4)?));
+            }
+        }
+
+        Ok(headers)
+    }
+
+    fn lookup(&self, index: usize) -> Result<(Vec<u8>, Vec<u8>), &'static str> {
+        match index {
+            0 => Err("HPACK index 0"),
+            1..=61 => {
+                let (name, value) = STATIC_TABLE[index - 1];
+                Ok((name.as_bytes().to_vec(), value.as_bytes().to_vec()))
+            }
+            _ => self
+                .dynamic
+                .get(index - 62)
+                .cloned()
+                .ok_or("HPACK index beyond the dynamic table"),
+        }
+    }
+
+    fn decode_literal(&self, block: &mut &[u8], prefix: u8) -> Result<(Vec<u8>, Vec<u8>), &'static str> {
+        let index = decode_integer(block, prefix)?;
+        let name = if index == 0 {
+            decode_string(block)?
+        } else {
+            self.lookup(index)?.0
+        };
+        let value = decode_string(block)?;
+        Ok((name, value))
+    }
+
+    fn insert(&mut self, header: (Vec<u8>, Vec<u8>)) {
+        self.size += header.0.len() + header.1.len() + ENTRY_OVERHEAD;
+        self.dynamic.push_front(header);
+        self.evict();
+    }
+
+    fn evict(&mut self) {
+        while self.size > self.max_size {
+            let Some((name, value)) = self.dynamic.pop_back() else {
+                break;
+            };
+            self.size -= name.len() + value.len() + ENTRY_OVERHEAD;
+        }
+    }
+}
+
+/// Decodes a prefixed integer (RFC 7541 section 5.1), advancing `block`.
+fn decode_integer(block: &mut &[u8], prefix: u8) -> Result<usize, &'static str> {
+    let (&first, rest) = block.split_first().ok_or("HPACK integer truncated")?;
+    *block = rest;
+    let max_prefix = (1usize << prefix) - 1;
+    let mut value = first as usize & max_prefix;
+    if value < max_prefix {
+        return Ok(value);
+    }
+
+    let mut shift = 0;
+    loop {
+        let (&byte, rest) = block.split_first().ok_or("HPACK integer truncated")?;
+        *block = rest;
+        if shift > 28 {
+            return Err("HPACK integer too large");
+        }
+        value += ((byte & 0x7f) as usize) << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+}
+
+/// Decodes a string literal (RFC 7541 section 5.2), advancing `block`.
+fn decode_string(block: &mut &[u8]) -> Result<Vec<u8>, &'static str> {
+    let huffman = block.first().ok_or("HPACK string truncated")? & 0x80 != 0;
+    let len = decode_integer(block, 7)?;
+    if block.len() < len {
+        return Err("HPACK string truncated");
+    }
+    let (raw, rest) = block.split_at(len);
+    *block = rest;
+    if huffman { huffman_decode(raw) } else { Ok(raw.to_vec()) }
+}
+
+fn to_header((name, value): (Vec<u8>, Vec<u8>)) -> (String, String) {
+    (
+        String::from_utf8_lossy(&name).into_owned(),
+        String::from_utf8_lossy(&value).into_owned(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unhex(hex: &str) -> Vec<u8> {
+        let hex: String = hex.split_whitespace().collect();
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn headers(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|&(name, value)| (name.to_string(), value.to_string())).collect()
+    }
+
+    fn table(decoder: &HpackDecoder) -> Vec<(String, String)> {
+        decoder.dynamic.iter().cloned().map(to_header).collect()
+    }
+
+    /// RFC 7541 Appendix C.4: requests with Huffman coding
+    #[test]
+    fn decodes_rfc7541_requests() {
+        let mut decoder = HpackDecoder::default();
+
+        let first = decoder.decode(&unhex("8286 8441 8cf1 e3c2 e5f2 3a6b a0ab 90f4 ff")).unwrap();
+        assert_eq!(
+            first,
+            headers(&[(":method", "GET"), (":scheme", "http"), (":path", "/"), (":authority", "www.example.com")])
+        );
+        assert_eq!(decoder.size, 57);
+
+        let second = decoder.decode(&unhex("8286 84be 5886 a8eb 1064 9cbf")).unwrap();
+        assert_eq!(
+            second,
+            headers(&[
+                (":method", "GET"),
+                (":scheme", "http"),
+                (":path", "/"),
+                (":authority", "www.example.com"),
+                ("cache-control", "no-cache"),
+            ])
+        );
+        assert_eq!(decoder.size, 110);
+
+        let third = decoder
+            .decode(&unhex("8287 85bf 4088 25a8 49e9 5ba9 7d7f 8925 a849 e95b b8e8 b4bf"))
+            .unwrap();
+        assert_eq!(
+            third,
+            headers(&[
+                (":method", "GET"),
+                (":scheme", "https"),
+                (":path", "/index.html"),
+                (":authority", "www.example.com"),
+                ("custom-key", "custom-value"),
+            ])
+        );
+        assert_eq!(
+            table(&decoder),
+            headers(&[
+                ("custom-key", "custom-value"),
+                ("cache-control", "no-cache"),
+                (":authority", "www.example.com"),
+            ])
+        );
+        assert_eq!(decoder.size, 164);
+    }
+
+    /// RFC 7541 Appendix C.6: responses with Huffman coding and a 256-byte table, which forces evictions
+    #[test]
+    fn decodes_rfc7541_responses_with_eviction() {
+        let mut decoder = HpackDecoder {
+            max_size: 256,
+            ..HpackDecoder::default()
+        };
+
+        let first = decoder
+            .decode(&unhex(
+                "4882 6402 5885 aec3 771a 4b61 96d0 7abe 9410 54d4 44a8 2005 9504 0b81 66e0 82a6 2d1b ff6e 919d 29ad \
+                 1718 63c7 8f0b 97c8 e9ae 82ae 43d3",
+            ))
+            .unwrap();
+        assert_eq!(
+            first,
+            headers(&[
+                (":status", "302"),
+                ("cache-control", "private"),
+                ("date", "Mon, 21 Oct 2013 20:13:21 GMT"),
+                ("location", "https://www.example.com"),
+            ])
+        );
+        assert_eq!(decoder.size, 222);
+
+        let second = decoder.decode(&unhex("4883 640e ffc1 c0bf")).unwrap();
+        assert_eq!(
+            second,
+            headers(&[
+                (":status", "307"),
+                ("cache-control", "private"),
+                ("date", "Mon, 21 Oct 2013 20:13:21 GMT"),
+                ("location", "https://www.example.com"),
+            ])
+        );
+        // ":status: 302" was evicted to make room
+        assert_eq!(
+            table(&decoder),
+            headers(&[
+                (":status", "307"),
+                ("location", "https://www.example.com"),
+                ("date", "Mon, 21 Oct 2013 20:13:21 GMT"),
+                ("cache-control", "private"),
+            ])
+        );
+        assert_eq!(decoder.size, 222);
+
+        let third = decoder
+            .decode(&unhex(
+                "88c1 6196 d07a be94 1054 d444 a820 0595 040b 8166 e084 a62d 1bff c05a 839b d9ab 77ad 94e7 821d d7f2 \
+                 e6c7 b335 dfdf cd5b 3960 d5af 2708 7f36 72c1 ab27 0fb5 291f 9587 3160 65c0 03ed 4ee5 b106 3d50 07",
+            ))
+            .unwrap();
+        assert_eq!(
+            third,
+            headers(&[
+                (":status", "200"),
+                ("cache-control", "private"),
+                ("date", "Mon, 21 Oct 2013 20:13:22 GMT"),
+                ("location", "https://www.example.com"),
+                ("content-encoding", "gzip"),
+                ("set-cookie", "foo=ASDJKHQKBZXOQWEOPIUAXQWEOIU; max-age=3600; version=1"),
+            ])
+        );
+        assert_eq!(
+            table(&decoder),
+            headers(&[
+                ("set-cookie", "foo=ASDJKHQKBZXOQWEOPIUAXQWEOIU; max-age=3600; version=1"),
+                ("content-encoding", "gzip"),
+                ("date", "Mon, 21 Oct 2013 20:13:22 GMT"),
+            ])
+        );
+        assert_eq!(decoder.size, 215);
+    }
+
+    #[test]
+    fn huffman_padding_must_be_eos_prefix() {
+        // 'a' is 00011; the remaining three bits must be ones
+        assert_eq!(huffman_decode(&[0b0001_1111]).unwrap(), b"a");
+        assert!(huffman_decode(&[0b0001_1000]).is_err());
+        // Eight or more bits of padding
+        assert!(huffman_decode(&[0b0001_1111, 0xff]).is_err());
+    }
+
+    #[test]
+    fn non_utf8_entries_keep_their_encoded_size() {
+        let mut decoder = HpackDecoder::default();
+        // Literal with incremental indexing, new name "x", raw value 0xff 0xfe
+        let decoded = decoder.decode(&[0x40, 0x01, b'x', 0x02, 0xff, 0xfe]).unwrap();
+        assert_eq!(decoded[0].0, "x");
+        assert_eq!(decoder.size, 1 + 2 + ENTRY_OVERHEAD);
+    }
+}
@@ -0,0 +1,218 @@
+use std::fmt;
+
+/// Request methods recognized at the start of a stream (RFC 9110, RFC 5789 and common WebDAV)
+const METHODS: &[&str] = &[
+    "GET", "HEAD", "POST", "PUT", "DELETE", "CONNECT", "OPTIONS", "TRACE", "PATCH", "PROPFIND", "PROPPATCH",
+    "MKCOL", "COPY", "MOVE", "LOCK", "UNLOCK",
+];
+
+/// First line of an HTTP/1.x message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HttpStartLine {
+    Request { method: String, uri: String },
+    Response { status: u16, reason: String },
+}
+
+/// A decoded HTTP/1.x message head.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpMessage {
+    pub start: HttpStartLine,
+    /// Protocol version, e.g. `HTTP/1.1`
+    pub version: String,
+    pub headers: Vec<(String, String)>,
+    pub host: Option<String>,
+    pub content_type: Option<String>,
+    pub content_length: Option<u64>,
+    pub user_agent: Option<String>,
+    pub chunked: bool,
+    /// Whether the connection stays open after this message
+    pub keep_alive: bool,
+    /// Method and URI of the request a response answers
+    pub request: Option<(String, String)>,
+    /// 1-based position of this request or response on its connection
+    pub sequence: usize,
+    /// A request sent before the responses to earlier requests arrived
+    pub pipelined: bool,
+}
+
+impl fmt::Display for HttpStartLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HttpStartLine::Request { method, uri } => write!(f, "{} {}", method, uri),
+            HttpStartLine::Response { status, reason } => write!(f, "{} {}", status, reason),
+        }
+    }
+}
+
+impl HttpMessage {
+    pub fn is_request(&self) -> bool {
+        matches!(self.start, HttpStartLine::Request { .. })
+    }
+
+    pub fn method(&self) -> Option<&str> {
+        match &self.start {
+            HttpStartLine::Request { method, .. } => Some(method),
+            HttpStartLine::Response { .. } => None,
+        }
+    }
+
+    pub fn status(&self) -> Option<u16> {
+        match &self.start {
+            HttpStartLine::Request { .. } => None,
+            HttpStartLine::Response { status, .. } => Some(*status),
+        }
+    }
+
+    /// Value of the first header named `name`, compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// One-line description such as `GET /index.html` or `200 OK`.
+    pub fn summary(&self) -> String {
+        let mut summary = self.start.to_string();
+        if self.pipelined {
+            summary.push_str(" [pipelined]");
+        }
+        summary
+    }
+
+    /// Multi-line description with the headers, for tooltips.
+    pub fn details(&self) -> String {
+        let start = match &self.start {
+            HttpStartLine::Request { method, uri } => format!("{} {} {}", method, uri, self.version),
+            HttpStartLine::Response { status, reason } => format!("{} {} {}", self.version, status, reason),
+        };
+        let mut lines = vec![start];
+        for (name, value) in &self.headers {
+            lines.push(format!("  {}: {}", name, value));
+        }
+
+        let kind = if self.is_request() { "Request" } else { "Response" };
+        let mut notes = vec![format!("{} #{} on connection", kind, self.sequence)];
+        if let Some((method, uri)) = &self.request {
+            notes.push(format!("answers {} {}", method, uri));
+        }
+        notes.push(if self.keep_alive { "keep-alive".into() } else { "close".into() });
+        if self.pipelined {
+            notes.push("pipelined".into());
+        }
+        lines.push(notes.join(", "));
+        lines.join("\n")
+    }
+}
+
+/// Whether `data` could be the start of an HTTP/1.x request or response.
+/// Input shorter than a full method or version is matched as a prefix.
+pub fn looks_like_http(data: &[u8]) -> bool {
+    let starts = |token: &[u8]| {
+        let n = data.len().min(token.len());
+        n > 0 && data[..n] == token[..n]
+    };
+    starts(b"HTTP/1.")
+        || METHODS.iter().any(|method| {
+            let mut token = method.as_bytes().to_vec();
+            token.push(b' ');
+            starts(&token)
+        })
+}
+
+/// Parses an HTTP/1.x message head. Returns `Ok(None)` until the blank line
+/// ending the head is present, otherwise the message and the head length in bytes.
+pub fn parse_http_head(data: &[u8]) -> Result<Option<(HttpMessage, usize)>, &'static str> {
+    let Some(end) = head_end(data) else {
+        return Ok(None);
+    };
+    let head = std::str::from_utf8(&data[..end]).map_err(|_| "HTTP head is not valid UTF-8")?;
+    let mut lines = head.lines().map(|line| line.trim_end_matches('\r'));
+
+    let first = lines.next().ok_or("HTTP head empty")?;
+    let (start, version) = parse_start_line(first)?;
+
+    let mut headers = Vec::new();
+    for line in lines.take_while(|line| !line.is_empty()) {
+        let (name, value) = line.split_once(':').ok_or("HTTP header line without a colon")?;
+        headers.push((name.trim().to_string(), value.trim().to_string()));
+    }
+
+    let mut message = HttpMessage {
+        start,
+        version,
+        headers,
+        host: None,
+        content_type: None,
+        content_length: None,
+        user_agent: None,
+        chunked: false,
+        keep_alive: false,
+        request: None,
+        sequence: 0,
+        pipelined: false,
+    };
+    message.host = message.header("Host").map(str::to_string);
+    message.content_type = message.header("Content-Type").map(str::to_string);
+    message.content_length = message.header("Content-Length").and_then(|len| len.parse().ok());
+    message.user_agent = message.header("User-Agent").map(str::to_string);
+    message.chunked = message
+        .header("Transfer-Encoding")
+        .is_some_and(|encoding| encoding.to_ascii_lowercase().contains("chunked"));
+
+    // HTTP/1.1 connections persist unless closed; HTTP/1.0 ones only when asked to
+    let connection = message.header("Connection").unwrap_or("").to_ascii_lowercase();
+    message.keep_alive = if message.version == "HTTP/1.0" {
+        connection.contains("keep-alive")
+    } else {
+        !connection.contains("close")
+    };
+
+    Ok(Some((message, end)))
+}
+
+/// Length of the head including the terminating blank line.
+fn head_end(data: &[u8]) -> Option<usize> {
+    let mut line_start = 0;
+    for (i, &byte) in data.iter().enumerate() {
+        if byte != b'\n' {
+            continue;
+        }
+        let line = &data[line_start..i];
+        if line.is_empty() || line == b"\r" {
+            return Some(i + 1);
+        }
+        line_start = i + 1;
+    }
+    None
+}
+
+fn parse_start_line(line: &str) -> Result<(HttpStartLine, String), &'static str> {
+    if let Some(rest) = line.strip_prefix("HTTP/") {
+        let (version, rest) = rest.split_once(' ').ok_or("HTTP status line malformed")?;
+        let (status, reason) = rest.split_once(' ').unwrap_or((rest, ""));
+        if status.len() != 3 {
+            return Err("HTTP status code malformed");
+        }
+        let status = status.parse().map_err(|_| "HTTP status code malformed")?;
+        let start = HttpStartLine::Response {
+            status,
+            reason: reason.to_string(),
+        };
+        return Ok((start, format!("HTTP/{}", version)));
+    }
+
+    let mut parts = line.split(' ');
+    let (Some(method), Some(uri), Some(version), None) = (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err("HTTP request line malformed");
+    };
+    if !METHODS.contains(&method) || !version.starts_with("HTTP/1.") {
+        return Err("HTTP request line malformed");
+    }
+    let start = HttpStartLine::Request {
+        method: method.to_string(),
+        uri: uri.to_string(),
+    };
+    Ok((start, version.to_string()))
+}
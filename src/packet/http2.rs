@@ -0,0 +1,241 @@
+use std::fmt;
+
+/// Client connection preface that starts every HTTP/2 connection (RFC 9113 section 3.4)
+pub const HTTP2_PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+pub const FLAG_END_STREAM: u8 = 0x01;
+pub const FLAG_ACK: u8 = 0x01;
+pub const FLAG_END_HEADERS: u8 = 0x04;
+pub const FLAG_PADDED: u8 = 0x08;
+pub const FLAG_PRIORITY: u8 = 0x20;
+
+/// Decoded contents of an HTTP/2 frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Http2FrameKind {
+    /// Payload bytes excluding padding
+    Data { len: usize },
+    /// A complete header block, including any CONTINUATION frames. `headers` is
+    /// None when HPACK decoding failed, e.g. because the capture started mid-connection.
+    Headers { headers: Option<Vec<(String, String)>> },
+    Priority,
+    RstStream { error_code: u32 },
+    Settings { settings: Vec<(u16, u32)> },
+    PushPromise { promised_stream_id: u32, headers: Option<Vec<(String, String)>> },
+    Ping,
+    GoAway { last_stream_id: u32, error_code: u32, debug_data: String },
+    WindowUpdate { increment: u32 },
+    Unknown { frame_type: u8 },
+}
+
+/// An HTTP/2 frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Http2Frame {
+    pub stream_id: u32,
+    pub flags: u8,
+    /// Payload length from the frame header
+    pub length: usize,
+    pub kind: Http2FrameKind,
+}
+
+impl Http2Frame {
+    pub fn end_stream(&self) -> bool {
+        matches!(self.kind, Http2FrameKind::Data { .. } | Http2FrameKind::Headers { .. })
+            && self.flags & FLAG_END_STREAM != 0
+    }
+
+    pub fn type_name(&self) -> String {
+        match &self.kind {
+            Http2FrameKind::Data { .. } => "DATA".into(),
+            Http2FrameKind::Headers { .. } => "HEADERS".into(),
+            Http2FrameKind::Priority => "PRIORITY".into(),
+            Http2FrameKind::RstStream { .. } => "RST_STREAM".into(),
+            Http2FrameKind::Settings { .. } => "SETTINGS".into(),
+            Http2FrameKind::PushPromise { .. } => "PUSH_PROMISE".into(),
+            Http2FrameKind::Ping => "PING".into(),
+            Http2FrameKind::GoAway { .. } => "GOAWAY".into(),
+            Http2FrameKind::WindowUpdate { .. } => "WINDOW_UPDATE".into(),
+            Http2FrameKind::Unknown { frame_type } => format!("type 0x{:02x}", frame_type),
+        }
+    }
+
+    /// Decoded headers of a HEADERS or PUSH_PROMISE frame.
+    pub fn headers(&self) -> Option<&[(String, String)]> {
+        match &self.kind {
+            Http2FrameKind::Headers { headers } | Http2FrameKind::PushPromise { headers, .. } => headers.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Short description, e.g. `HEADERS[1] GET /index.html` or `RST_STREAM[3] CANCEL`.
+    pub fn summary(&self) -> String {
+        let name = if self.stream_id == 0 {
+            self.type_name()
+        } else {
+            format!("{}[{}]", self.type_name(), self.stream_id)
+        };
+        let detail = match &self.kind {
+            Http2FrameKind::Data { len } => format!(" {} bytes", len),
+            Http2FrameKind::Headers { headers: None } | Http2FrameKind::PushPromise { headers: None, .. } => {
+                " (HPACK state unknown)".into()
+            }
+            Http2FrameKind::Headers { headers: Some(headers) } => format!(" {}", describe_headers(headers)),
+            Http2FrameKind::PushPromise {
+                promised_stream_id,
+                headers: Some(headers),
+            } => format!(" promised {} {}", promised_stream_id, describe_headers(headers)),
+            Http2FrameKind::RstStream { error_code } => format!(" {}", error_code_name(*error_code)),
+            Http2FrameKind::Settings { .. } | Http2FrameKind::Ping if self.flags & FLAG_ACK != 0 => " ACK".into(),
+            Http2FrameKind::Settings { settings } => settings
+                .iter()
+                .map(|&(id, value)| format!(" {}={}", setting_name(id), value))
+                .collect(),
+            Http2FrameKind::GoAway {
+                last_stream_id,
+                error_code,
+                ..
+            } => format!(" last stream {} {}", last_stream_id, error_code_name(*error_code)),
+            Http2FrameKind::WindowUpdate { increment } => format!(" +{}", increment),
+            _ => String::new(),
+        };
+        let end_stream = if self.end_stream() { " END_STREAM" } else { "" };
+        format!("{}{}{}", name, detail, end_stream)
+    }
+
+    /// Multi-line description with all headers, for tooltips.
+    pub fn details(&self) -> String {
+        let mut lines = vec![self.summary()];
+        if let Some(headers) = self.headers() {
+            for (name, value) in headers {
+                lines.push(format!("  {}: {}", name, value));
+            }
+        }
+        if let Http2FrameKind::GoAway { debug_data, .. } = &self.kind
+            && !debug_data.is_empty()
+        {
+            lines.push(format!("  debug: {}", debug_data));
+        }
+        lines.join("\n")
+    }
+}
+
+impl fmt::Display for Http2Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.summary())
+    }
+}
+
+/// Value of the first header named `name`.
+pub fn header_value<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers.iter().find(|(header, _)| header == name).map(|(_, value)| value.as_str())
+}
+
+/// gRPC service and method of a request, from a `/package.Service/Method` path
+/// on an `application/grpc` content type.
+pub fn grpc_call(headers: &[(String, String)]) -> Option<(String, String)> {
+    let content_type = header_value(headers, "content-type")?;
+    if !content_type.starts_with("application/grpc") {
+        return None;
+    }
+    let path = header_value(headers, ":path")?;
+    let (service, method) = path.strip_prefix('/')?.split_once('/')?;
+    Some((service.to_string(), method.to_string()))
+}
+
+/// Request line, status or gRPC call a header block describes.
+fn describe_headers(headers: &[(String, String)]) -> String {
+    if let Some((service, method)) = grpc_call(headers) {
+        return format!("gRPC {}/{}", service, method);
+    }
+    if let (Some(method), Some(path)) = (header_value(headers, ":method"), header_value(headers, ":path")) {
+        return format!("{} {}", method, path);
+    }
+    if let Some(status) = header_value(headers, ":status") {
+        return status.to_string();
+    }
+    if let Some(status) = header_value(headers, "grpc-status") {
+        return format!("grpc-status {}", status);
+    }
+    format!("{} headers", headers.len())
+}
+
+pub fn error_code_name(code: u32) -> String {
+    let name = match code {
+        0x0 => "NO_ERROR",
+        0x1 => "PROTOCOL_ERROR",
+        0x2 => "INTERNAL_ERROR",
+        0x3 => "FLOW_CONTROL_ERROR",
+        0x4 => "SETTINGS_TIMEOUT",
+        0x5 => "STREAM_CLOSED",
+        0x6 => "FRAME_SIZE_ERROR",
+        0x7 => "REFUSED_STREAM",
+        0x8 => "CANCEL",
+        0x9 => "COMPRESSION_ERROR",
+        0xa => "CONNECT_ERROR",
+        0xb => "ENHANCE_YOUR_CALM",
+        0xc => "INADEQUATE_SECURITY",
+        0xd => "HTTP_1_1_REQUIRED",
+        code => return format!("error 0x{:x}", code),
+    };
+    name.into()
+}
+
+fn setting_name(id: u16) -> String {
+    let name = match id {
+        0x1 => "HEADER_TABLE_SIZE",
+        0x2 => "ENABLE_PUSH",
+        0x3 => "MAX_CONCURRENT_STREAMS",
+        0x4 => "INITIAL_WINDOW_SIZE",
+        0x5 => "MAX_FRAME_SIZE",
+        0x6 => "MAX_HEADER_LIST_SIZE",
+        0x8 => "ENABLE_CONNECT_PROTOCOL",
+        id => return format!("0x{:x}", id),
+    };
+    name.into()
+}
+
+/// Removes the padding of a PADDED frame payload.
+pub fn strip_padding(flags: u8, payload: &[u8]) -> Result<&[u8], &'static str> {
+    if flags & FLAG_PADDED == 0 {
+        return Ok(payload);
+    }
+    let (&pad_len, rest) = payload.split_first().ok_or("HTTP/2 padded frame empty")?;
+    rest.len()
+        .checked_sub(pad_len as usize)
+        .map(|len| &rest[..len])
+        .ok_or("HTTP/2 padding exceeds frame")
+}
+
+/// Decodes a frame whose payload needs no connection state; HEADERS, PUSH_PROMISE
+/// and CONTINUATION carry header blocks and are assembled by the caller.
+pub fn parse_control_frame(frame_type: u8, flags: u8, payload: &[u8]) -> Result<Http2FrameKind, &'static str> {
+    let u32_at = |at: usize| -> Result<u32, &'static str> {
+        payload
+            .get(at..at + 4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+            .ok_or("HTTP/2 frame payload truncated")
+    };
+
+    Ok(match frame_type {
+        0x0 => Http2FrameKind::Data {
+            len: strip_padding(flags, payload)?.len(),
+        },
+        0x2 => Http2FrameKind::Priority,
+        0x3 => Http2FrameKind::RstStream { error_code: u32_at(0)? },
+        0x4 => Http2FrameKind::Settings {
+            settings: payload
+                .chunks_exact(6)
+                .map(|s| (u16::from_be_bytes([s[0], s[1]]), u32::from_be_bytes([s[2], s[3], s[4], s[5]])))
+                .collect(),
+        },
+        0x6 => Http2FrameKind::Ping,
+        0x7 => Http2FrameKind::GoAway {
+            last_stream_id: u32_at(0)? & 0x7fff_ffff,
+            error_code: u32_at(4)?,
+            debug_data: String::from_utf8_lossy(&payload[8..]).into_owned(),
+        },
+        0x8 => Http2FrameKind::WindowUpdate {
+            increment: u32_at(0)? & 0x7fff_ffff,
+        },
+        frame_type => Http2FrameKind::Unknown { frame_type },
+    })
+}
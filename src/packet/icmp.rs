@@ -0,0 +1,235 @@
+use super::{PacketInfo, PacketType};
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+/// Identifier and sequence number of an echo request or reply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IcmpEcho {
+    pub identifier: u16,
+    pub sequence: u16,
+}
+
+/// The start of the datagram quoted by an ICMP error message,
+/// identifying the flow that triggered the error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuotedPacket {
+    pub src_ip: String,
+    pub dst_ip: String,
+    pub protocol: u8,
+    pub src_port: Option<u16>,
+    pub dst_port: Option<u16>,
+}
+
+impl QuotedPacket {
+    /// Describes the quoted flow, e.g. `UDP 10.0.0.1:5353 -> 10.0.0.2:53`.
+    pub fn summary(&self) -> String {
+        let protocol = match self.protocol {
+            1 => "ICMP".to_string(),
+            6 => "TCP".to_string(),
+            17 => "UDP".to_string(),
+            58 => "ICMPv6".to_string(),
+            other => format!("proto {}", other),
+        };
+
+        match (self.src_port, self.dst_port) {
+            (Some(sport), Some(dport)) => format!(
+                "{} {}:{} -> {}:{}",
+                protocol, self.src_ip, sport, self.dst_ip, dport
+            ),
+            _ => format!("{} {} -> {}", protocol, self.src_ip, self.dst_ip),
+        }
+    }
+}
+
+/// A decoded ICMPv4 message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IcmpInfo {
+    pub icmp_type: u8,
+    pub code: u8,
+    pub checksum: u16,
+    /// Set for echo request/reply
+    pub echo: Option<IcmpEcho>,
+    /// Gateway address advertised by a redirect
+    pub gateway: Option<Ipv4Addr>,
+    /// Next-hop MTU of a "fragmentation needed" unreachable (RFC 1191)
+    pub next_hop_mtu: Option<u16>,
+    /// Header of the datagram that caused an error message
+    pub quoted: Option<QuotedPacket>,
+    /// Round-trip time, filled in on echo replies matched to their request
+    pub rtt: Option<Duration>,
+}
+
+impl IcmpInfo {
+    pub fn is_echo_request(&self) -> bool {
+        self.icmp_type == 8
+    }
+
+    pub fn is_echo_reply(&self) -> bool {
+        self.icmp_type == 0
+    }
+
+    /// Name of the message type and, where defined, its code.
+    pub fn description(&self) -> String {
+        let type_name = match self.icmp_type {
+            0 => "Echo (ping) reply",
+            3 => "Destination unreachable",
+            4 => "Source quench",
+            5 => "Redirect",
+            8 => "Echo (ping) request",
+            9 => "Router advertisement",
+            10 => "Router solicitation",
+            11 => "Time exceeded",
+            12 => "Parameter problem",
+            13 => "Timestamp request",
+            14 => "Timestamp reply",
+            other => return format!("ICMP type {} code {}", other, self.code),
+        };
+
+        match code_name(self.icmp_type, self.code) {
+            Some(code) => format!("{} ({})", type_name, code),
+            None => type_name.to_string(),
+        }
+    }
+
+    /// One-line summary including echo ids, RTT and the quoted flow of errors.
+    pub fn summary(&self) -> String {
+        let mut text = self.description();
+
+        if let Some(echo) = &self.echo {
+            text.push_str(&format!(" id=0x{:04x} seq={}", echo.identifier, echo.sequence));
+        }
+        if let Some(rtt) = self.rtt {
+            text.push_str(&format!(" rtt={:.3} ms", rtt.as_secs_f64() * 1000.0));
+        }
+        if let Some(mtu) = self.next_hop_mtu {
+            text.push_str(&format!(" mtu={}", mtu));
+        }
+        if let Some(gateway) = &self.gateway {
+            text.push_str(&format!(" via {}", gateway));
+        }
+        if let Some(quoted) = &self.quoted {
+            text.push_str(&format!(" for {}", quoted.summary()));
+        }
+
+        text
+    }
+}
+
+/// Returns the name of a type-specific code, if the type defines codes.
+fn code_name(icmp_type: u8, code: u8) -> Option<&'static str> {
+    let name = match (icmp_type, code) {
+        (3, 0) => "Network unreachable",
+        (3, 1) => "Host unreachable",
+        (3, 2) => "Protocol unreachable",
+        (3, 3) => "Port unreachable",
+        (3, 4) => "Fragmentation needed",
+        (3, 5) => "Source route failed",
+        (3, 6) => "Destination network unknown",
+        (3, 7) => "Destination host unknown",
+        (3, 8) => "Source host isolated",
+        (3, 9) => "Network administratively prohibited",
+        (3, 10) => "Host administratively prohibited",
+        (3, 11) => "Network unreachable for TOS",
+        (3, 12) => "Host unreachable for TOS",
+        (3, 13) => "Communication administratively prohibited",
+        (3, 14) => "Host precedence violation",
+        (3, 15) => "Precedence cutoff in effect",
+        (5, 0) => "Network",
+        (5, 1) => "Host",
+        (5, 2) => "TOS and network",
+        (5, 3) => "TOS and host",
+        (11, 0) => "TTL exceeded in transit",
+        (11, 1) => "Fragment reassembly time exceeded",
+        (12, 0) => "Pointer indicates the error",
+        (12, 1) => "Missing a required option",
+        (12, 2) => "Bad length",
+        (3, _) | (5, _) | (11, _) | (12, _) => "Unknown code",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Parses an ICMPv4 message and updates the provided PacketInfo.
+///
+/// # Arguments
+/// * `data` - The ICMP message (from IP payload)
+/// * `info` - Mutable reference to the PacketInfo to populate
+pub fn parse_icmp(data: &[u8], info: &mut PacketInfo) -> Result<(), &'static str> {
+    if data.len() < 8 {
+        return Err("ICMP header too short");
+    }
+
+    let icmp_type = data[0];
+    let code = data[1];
+    let checksum = u16::from_be_bytes([data[2], data[3]]);
+
+    let mut icmp = IcmpInfo {
+        icmp_type,
+        code,
+        checksum,
+        echo: None,
+        gateway: None,
+        next_hop_mtu: None,
+        quoted: None,
+        rtt: None,
+    };
+
+    match icmp_type {
+        0 | 8 | 13 | 14 => {
+            icmp.echo = Some(IcmpEcho {
+                identifier: u16::from_be_bytes([data[4], data[5]]),
+                sequence: u16::from_be_bytes([data[6], data[7]]),
+            });
+        }
+        3 | 4 | 5 | 11 | 12 => {
+            if icmp_type == 5 {
+                icmp.gateway = Some(Ipv4Addr::new(data[4], data[5], data[6], data[7]));
+            }
+            if icmp_type == 3 && code == 4 {
+                icmp.next_hop_mtu = Some(u16::from_be_bytes([data[6], data[7]]));
+            }
+            // A truncated quote is not fatal: the outer message is still valid
+            icmp.quoted = parse_quoted_ipv4(&data[8..]);
+        }
+        _ => {}
+    }
+
+    info.packet_type = PacketType::ICMP;
+    info.icmp = Some(icmp);
+
+    Ok(())
+}
+
+/// Parses the IPv4 header and first 8 transport bytes quoted by an ICMP error.
+fn parse_quoted_ipv4(data: &[u8]) -> Option<QuotedPacket> {
+    if data.len() < 20 || data[0] >> 4 != 4 {
+        return None;
+    }
+
+    let header_len = ((data[0] & 0x0F) as usize) * 4;
+    if header_len < 20 || data.len() < header_len {
+        return None;
+    }
+
+    let protocol = data[9];
+    let src_ip = Ipv4Addr::new(data[12], data[13], data[14], data[15]);
+    let dst_ip = Ipv4Addr::new(data[16], data[17], data[18], data[19]);
+
+    let transport = &data[header_len..];
+    let (src_port, dst_port) = if matches!(protocol, 6 | 17) && transport.len() >= 4 {
+        (
+            Some(u16::from_be_bytes([transport[0], transport[1]])),
+            Some(u16::from_be_bytes([transport[2], transport[3]])),
+        )
+    } else {
+        (None, None)
+    };
+
+    Some(QuotedPacket {
+        src_ip: src_ip.to_string(),
+        dst_ip: dst_ip.to_string(),
+        protocol,
+        src_port,
+        dst_port,
+    })
+}
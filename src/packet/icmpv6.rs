@@ -0,0 +1,385 @@
+use super::{PacketInfo, PacketType};
+use crate::packet::ethernet::format_mac;
+use crate::packet::icmp::{IcmpEcho, QuotedPacket};
+use std::net::Ipv6Addr;
+use std::time::Duration;
+
+/// A Neighbor Discovery option (RFC 4861, RFC 8106).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NdpOption {
+    SourceLinkLayerAddress(Vec<u8>),
+    TargetLinkLayerAddress(Vec<u8>),
+    PrefixInformation {
+        prefix: Ipv6Addr,
+        prefix_len: u8,
+        on_link: bool,
+        autonomous: bool,
+        valid_lifetime: u32,
+        preferred_lifetime: u32,
+    },
+    Mtu(u32),
+    RecursiveDnsServers {
+        lifetime: u32,
+        servers: Vec<Ipv6Addr>,
+    },
+    Other {
+        option_type: u8,
+        length: usize,
+    },
+}
+
+impl NdpOption {
+    pub fn summary(&self) -> String {
+        match self {
+            NdpOption::SourceLinkLayerAddress(addr) => format!("src lladdr {}", format_link_address(addr)),
+            NdpOption::TargetLinkLayerAddress(addr) => format!("tgt lladdr {}", format_link_address(addr)),
+            NdpOption::PrefixInformation { prefix, prefix_len, autonomous, valid_lifetime, .. } => format!(
+                "prefix {}/{}{} valid {}s",
+                prefix,
+                prefix_len,
+                if *autonomous { " (SLAAC)" } else { "" },
+                valid_lifetime
+            ),
+            NdpOption::Mtu(mtu) => format!("MTU {}", mtu),
+            NdpOption::RecursiveDnsServers { servers, .. } => format!(
+                "RDNSS {}",
+                servers.iter().map(|s| s.to_string()).collect::<Vec<_>>().join(", ")
+            ),
+            NdpOption::Other { option_type, .. } => format!("option {}", option_type),
+        }
+    }
+}
+
+/// The fixed part of a Neighbor Discovery message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NdpMessage {
+    RouterSolicitation,
+    RouterAdvertisement {
+        cur_hop_limit: u8,
+        managed: bool,
+        other_config: bool,
+        router_lifetime: u16,
+        reachable_time: u32,
+        retrans_timer: u32,
+    },
+    NeighborSolicitation {
+        target: Ipv6Addr,
+    },
+    NeighborAdvertisement {
+        target: Ipv6Addr,
+        router: bool,
+        solicited: bool,
+        override_flag: bool,
+    },
+    Redirect {
+        target: Ipv6Addr,
+        destination: Ipv6Addr,
+    },
+}
+
+/// A decoded ICMPv6 message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Icmpv6Info {
+    pub icmp_type: u8,
+    pub code: u8,
+    pub checksum: u16,
+    /// Set for echo request/reply
+    pub echo: Option<IcmpEcho>,
+    /// MTU reported by "Packet too big"
+    pub mtu: Option<u32>,
+    /// Neighbor Discovery message body
+    pub ndp: Option<NdpMessage>,
+    /// Neighbor Discovery options following the message body
+    pub options: Vec<NdpOption>,
+    /// Header of the packet that caused an error message
+    pub quoted: Option<QuotedPacket>,
+    /// Round-trip time, filled in on echo replies matched to their request
+    pub rtt: Option<Duration>,
+}
+
+impl Icmpv6Info {
+    pub fn is_echo_request(&self) -> bool {
+        self.icmp_type == 128
+    }
+
+    pub fn is_echo_reply(&self) -> bool {
+        self.icmp_type == 129
+    }
+
+    /// Name of the message type and, where defined, its code.
+    pub fn description(&self) -> String {
+        let type_name = match self.icmp_type {
+            1 => "Destination unreachable",
+            2 => "Packet too big",
+            3 => "Time exceeded",
+            4 => "Parameter problem",
+            128 => "Echo (ping) request",
+            129 => "Echo (ping) reply",
+            130 => "Multicast listener query",
+            131 => "Multicast listener report",
+            132 => "Multicast listener done",
+            133 => "Router solicitation",
+            134 => "Router advertisement",
+            135 => "Neighbor solicitation",
+            136 => "Neighbor advertisement",
+            137 => "Redirect",
+            143 => "Multicast listener report v2",
+            other => return format!("ICMPv6 type {} code {}", other, self.code),
+        };
+
+        match code_name(self.icmp_type, self.code) {
+            Some(code) => format!("{} ({})", type_name, code),
+            None => type_name.to_string(),
+        }
+    }
+
+    /// One-line summary in the style of common packet analyzers.
+    pub fn summary(&self) -> String {
+        let mut text = match &self.ndp {
+            Some(NdpMessage::NeighborSolicitation { target }) => format!("Neighbor solicitation for {}", target),
+            Some(NdpMessage::NeighborAdvertisement { target, router, solicited, override_flag }) => {
+                let flags: Vec<&str> = [(*router, "rtr"), (*solicited, "sol"), (*override_flag, "ovr")]
+                    .iter()
+                    .filter(|(set, _)| *set)
+                    .map(|(_, name)| *name)
+                    .collect();
+                format!("Neighbor advertisement {} ({})", target, flags.join(", "))
+            }
+            Some(NdpMessage::RouterAdvertisement { router_lifetime, managed, other_config, .. }) => format!(
+                "Router advertisement lifetime {}s{}{}",
+                router_lifetime,
+                if *managed { " M" } else { "" },
+                if *other_config { " O" } else { "" }
+            ),
+            Some(NdpMessage::Redirect { target, destination }) => {
+                format!("Redirect {} via {}", destination, target)
+            }
+            Some(NdpMessage::RouterSolicitation) | None => self.description(),
+        };
+
+        if let Some(echo) = &self.echo {
+            text.push_str(&format!(" id=0x{:04x} seq={}", echo.identifier, echo.sequence));
+        }
+        if let Some(rtt) = self.rtt {
+            text.push_str(&format!(" rtt={:.3} ms", rtt.as_secs_f64() * 1000.0));
+        }
+        if let Some(mtu) = self.mtu {
+            text.push_str(&format!(" mtu={}", mtu));
+        }
+        for option in &self.options {
+            text.push_str(&format!(", {}", option.summary()));
+        }
+        if let Some(quoted) = &self.quoted {
+            text.push_str(&format!(" for {}", quoted.summary()));
+        }
+
+        text
+    }
+}
+
+fn code_name(icmp_type: u8, code: u8) -> Option<&'static str> {
+    let name = match (icmp_type, code) {
+        (1, 0) => "No route to destination",
+        (1, 1) => "Administratively prohibited",
+        (1, 2) => "Beyond scope of source address",
+        (1, 3) => "Address unreachable",
+        (1, 4) => "Port unreachable",
+        (1, 5) => "Source address failed ingress/egress policy",
+        (1, 6) => "Reject route to destination",
+        (3, 0) => "Hop limit exceeded in transit",
+        (3, 1) => "Fragment reassembly time exceeded",
+        (4, 0) => "Erroneous header field",
+        (4, 1) => "Unrecognized next header",
+        (4, 2) => "Unrecognized IPv6 option",
+        (1, _) | (3, _) | (4, _) => "Unknown code",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Parses an ICMPv6 message and updates the provided PacketInfo.
+///
+/// # Arguments
+/// * `data` - The ICMPv6 message (from the IPv6 payload)
+/// * `info` - Mutable reference to the PacketInfo to populate
+pub fn parse_icmpv6(data: &[u8], info: &mut PacketInfo) -> Result<(), &'static str> {
+    if data.len() < 8 {
+        return Err("ICMPv6 header too short");
+    }
+
+    let icmp_type = data[0];
+    let code = data[1];
+    let checksum = u16::from_be_bytes([data[2], data[3]]);
+
+    let mut icmp = Icmpv6Info {
+        icmp_type,
+        code,
+        checksum,
+        echo: None,
+        mtu: None,
+        ndp: None,
+        options: Vec::new(),
+        quoted: None,
+        rtt: None,
+    };
+
+    match icmp_type {
+        1..=4 => {
+            if icmp_type == 2 {
+                icmp.mtu = Some(u32::from_be_bytes([data[4], data[5], data[6], data[7]]));
+            }
+            icmp.quoted = parse_quoted_ipv6(&data[8..]);
+        }
+        128 | 129 => {
+            icmp.echo = Some(IcmpEcho {
+                identifier: u16::from_be_bytes([data[4], data[5]]),
+                sequence: u16::from_be_bytes([data[6], data[7]]),
+            });
+        }
+        133..=137 => {
+            // A short or snaplen-truncated NDP body still leaves a valid ICMPv6 packet
+            if let Ok((message, options_offset)) = parse_ndp_message(icmp_type, data) {
+                icmp.ndp = Some(message);
+                icmp.options = parse_ndp_options(&data[options_offset..]);
+            }
+        }
+        _ => {}
+    }
+
+    info.packet_type = PacketType::ICMPv6;
+    info.icmpv6 = Some(icmp);
+
+    Ok(())
+}
+
+/// Parses the fixed body of an NDP message.
+/// Returns the message and the offset at which its options start.
+fn parse_ndp_message(icmp_type: u8, data: &[u8]) -> Result<(NdpMessage, usize), &'static str> {
+    let required = match icmp_type {
+        133 => 8,
+        134 => 16,
+        135 | 136 => 24,
+        _ => 40,
+    };
+    if data.len() < required {
+        return Err("Neighbor Discovery message too short");
+    }
+
+    let message = match icmp_type {
+        133 => NdpMessage::RouterSolicitation,
+        134 => NdpMessage::RouterAdvertisement {
+            cur_hop_limit: data[4],
+            managed: data[5] & 0x80 != 0,
+            other_config: data[5] & 0x40 != 0,
+            router_lifetime: u16::from_be_bytes([data[6], data[7]]),
+            reachable_time: u32::from_be_bytes([data[8], data[9], data[10], data[11]]),
+            retrans_timer: u32::from_be_bytes([data[12], data[13], data[14], data[15]]),
+        },
+        135 => NdpMessage::NeighborSolicitation {
+            target: ipv6_at(data, 8),
+        },
+        136 => NdpMessage::NeighborAdvertisement {
+            target: ipv6_at(data, 8),
+            router: data[4] & 0x80 != 0,
+            solicited: data[4] & 0x40 != 0,
+            override_flag: data[4] & 0x20 != 0,
+        },
+        _ => NdpMessage::Redirect {
+            target: ipv6_at(data, 8),
+            destination: ipv6_at(data, 24),
+        },
+    };
+
+    Ok((message, required))
+}
+
+/// Parses the TLV options of an NDP message. Parsing stops at the first
+/// malformed option; options decoded up to that point are kept.
+fn parse_ndp_options(data: &[u8]) -> Vec<NdpOption> {
+    let mut options = Vec::new();
+    let mut offset = 0;
+
+    while offset + 2 <= data.len() {
+        let option_type = data[offset];
+        // Length is in units of 8 bytes and includes the type and length fields
+        let length = data[offset + 1] as usize * 8;
+        if length == 0 || offset + length > data.len() {
+            break;
+        }
+
+        let body = &data[offset..offset + length];
+        let option = match option_type {
+            1 => NdpOption::SourceLinkLayerAddress(link_address(body)),
+            2 => NdpOption::TargetLinkLayerAddress(link_address(body)),
+            3 if length >= 32 => NdpOption::PrefixInformation {
+                prefix_len: body[2],
+                on_link: body[3] & 0x80 != 0,
+                autonomous: body[3] & 0x40 != 0,
+                valid_lifetime: u32::from_be_bytes([body[4], body[5], body[6], body[7]]),
+                preferred_lifetime: u32::from_be_bytes([body[8], body[9], body[10], body[11]]),
+                prefix: ipv6_at(body, 16),
+            },
+            5 if length >= 8 => NdpOption::Mtu(u32::from_be_bytes([body[4], body[5], body[6], body[7]])),
+            25 if length >= 8 => NdpOption::RecursiveDnsServers {
+                lifetime: u32::from_be_bytes([body[4], body[5], body[6], body[7]]),
+                servers: body[8..].chunks_exact(16).map(|c| ipv6_at(c, 0)).collect(),
+            },
+            _ => NdpOption::Other { option_type, length },
+        };
+
+        options.push(option);
+        offset += length;
+    }
+
+    options
+}
+
+/// Link-layer address carried in a source/target link-layer option,
+/// without the trailing padding for Ethernet.
+fn link_address(option: &[u8]) -> Vec<u8> {
+    let addr = &option[2..];
+    if addr.len() >= 6 {
+        addr[..6].to_vec()
+    } else {
+        addr.to_vec()
+    }
+}
+
+fn format_link_address(addr: &[u8]) -> String {
+    match <[u8; 6]>::try_from(addr) {
+        Ok(mac) => format_mac(&mac),
+        Err(_) => addr.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(":"),
+    }
+}
+
+fn ipv6_at(data: &[u8], offset: usize) -> Ipv6Addr {
+    let mut octets = [0u8; 16];
+    octets.copy_from_slice(&data[offset..offset + 16]);
+    Ipv6Addr::from(octets)
+}
+
+/// Parses the IPv6 header and first transport bytes quoted by an ICMPv6 error.
+fn parse_quoted_ipv6(data: &[u8]) -> Option<QuotedPacket> {
+    if data.len() < 40 || data[0] >> 4 != 6 {
+        return None;
+    }
+
+    let protocol = data[6];
+    let transport = &data[40..];
+    let (src_port, dst_port) = if matches!(protocol, 6 | 17) && transport.len() >= 4 {
+        (
+            Some(u16::from_be_bytes([transport[0], transport[1]])),
+            Some(u16::from_be_bytes([transport[2], transport[3]])),
+        )
+    } else {
+        (None, None)
+    };
+
+    Some(QuotedPacket {
+        src_ip: ipv6_at(data, 8).to_string(),
+        dst_ip: ipv6_at(data, 24).to_string(),
+        protocol,
+        src_port,
+        dst_port,
+    })
+}
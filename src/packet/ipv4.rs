@@ -1,6 +1,81 @@
-use crate::packet::{PacketInfo, PacketType};
+use crate::packet::{IpFragment, PacketInfo, PacketType};
+use crate::packet::transport::parse_transport;
 use std::net::Ipv4Addr;
 
+/// Decoded IPv4 header fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ipv4Header {
+    pub version: u8,
+    /// Header length in bytes (IHL * 4)
+    pub header_len: usize,
+    pub dscp: u8,
+    pub ecn: u8,
+    pub total_length: u16,
+    pub identification: u16,
+    pub dont_fragment: bool,
+    pub more_fragments: bool,
+    /// Fragment offset in bytes
+    pub fragment_offset: usize,
+    pub ttl: u8,
+    pub protocol: u8,
+    pub checksum: u16,
+    /// False when the header checksum does not verify. Outgoing packets captured
+    /// before checksum offload is applied also show up as invalid.
+    pub checksum_valid: bool,
+    pub options: Vec<Ipv4Option>,
+}
+
+/// An IPv4 header option (excluding End of Option List and No-Operation padding).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ipv4Option {
+    pub kind: u8,
+    pub data: Vec<u8>,
+}
+
+impl Ipv4Option {
+    pub fn name(&self) -> String {
+        match self.kind {
+            7 => "Record Route".into(),
+            68 => "Timestamp".into(),
+            130 => "Security".into(),
+            131 => "Loose Source Route".into(),
+            136 => "Stream ID".into(),
+            137 => "Strict Source Route".into(),
+            148 => "Router Alert".into(),
+            kind => format!("Option {}", kind),
+        }
+    }
+}
+
+impl Ipv4Header {
+    /// DSCP class name such as `EF` or `AF41`, or the raw value for unnamed code points.
+    pub fn dscp_label(&self) -> String {
+        match self.dscp {
+            46 => "EF".into(),
+            44 => "VOICE-ADMIT".into(),
+            dscp if dscp % 8 == 0 => format!("CS{}", dscp / 8),
+            // AFxy: class x in the top three bits, drop precedence y in the next two
+            dscp if dscp & 1 == 0 && (1..=4).contains(&(dscp >> 3)) && (1..=3).contains(&((dscp >> 1) & 3)) => {
+                format!("AF{}{}", dscp >> 3, (dscp >> 1) & 3)
+            }
+            dscp => dscp.to_string(),
+        }
+    }
+
+    pub fn ecn_label(&self) -> &'static str {
+        match self.ecn {
+            0 => "Not-ECT",
+            1 => "ECT(1)",
+            2 => "ECT(0)",
+            _ => "CE",
+        }
+    }
+
+    pub fn is_fragment(&self) -> bool {
+        self.more_fragments || self.fragment_offset != 0
+    }
+}
+
 pub fn parse_ipv4(payload: &[u8], info: &mut PacketInfo) -> Result<(), &'static str> {
     if payload.len() < 20 {
         return Err("IPv4 header too short");
@@ -9,36 +84,111 @@ pub fn parse_ipv4(payload: &[u8], info: &mut PacketInfo) -> Result<(), &'static
     let ihl = payload[0] & 0x0F;
     let ip_header_len = (ihl as usize) * 4;
 
+    if ip_header_len < 20 {
+        return Err("IPv4 header length below minimum");
+    }
+
     if payload.len() < ip_header_len {
         return Err("IPv4 header length exceeds payload size");
     }
 
+    let total_length = u16::from_be_bytes([payload[2], payload[3]]);
+    if total_length != 0 && (total_length as usize) < ip_header_len {
+        return Err("IPv4 total length shorter than header");
+    }
+
+    // Drop link-layer padding. A zero total length is left alone: it is what
+    // segmentation offload produces on outgoing packets captured locally.
+    let payload = if total_length != 0 && (total_length as usize) <= payload.len() {
+        &payload[..total_length as usize]
+    } else {
+        payload
+    };
+
     let src = Ipv4Addr::new(payload[12], payload[13], payload[14], payload[15]);
     let dst = Ipv4Addr::new(payload[16], payload[17], payload[18], payload[19]);
     let protocol = payload[9];
+    let flags_offset = u16::from_be_bytes([payload[6], payload[7]]);
+
+    let header = Ipv4Header {
+        version: payload[0] >> 4,
+        header_len: ip_header_len,
+        dscp: payload[1] >> 2,
+        ecn: payload[1] & 0x03,
+        total_length,
+        identification: u16::from_be_bytes([payload[4], payload[5]]),
+        dont_fragment: flags_offset & 0x4000 != 0,
+        more_fragments: flags_offset & 0x2000 != 0,
+        fragment_offset: (flags_offset & 0x1FFF) as usize * 8,
+        ttl: payload[8],
+        protocol,
+        checksum: u16::from_be_bytes([payload[10], payload[11]]),
+        checksum_valid: internet_checksum(&payload[..ip_header_len]) == 0,
+        options: parse_options(&payload[20..ip_header_len]),
+    };
 
     info.src_ip = Some(src.to_string());
     info.dst_ip = Some(dst.to_string());
+    info.packet_type = PacketType::IPv4;
+
+    if header.is_fragment() {
+        info.fragment = Some(IpFragment {
+            identification: header.identification as u32,
+            offset: header.fragment_offset,
+            more_fragments: header.more_fragments,
+            protocol,
+            payload: payload[ip_header_len..].to_vec(),
+            reassembled: None,
+        });
+        info.ipv4 = Some(header);
+        // The transport header is decoded once the datagram is reassembled
+        return Ok(());
+    }
+
+    info.ipv4 = Some(header);
+
+    parse_transport(protocol, &payload[ip_header_len..], info)
+}
+
+/// Parses the option bytes between the fixed header and the IHL boundary.
+/// Malformed options end parsing rather than failing the packet.
+fn parse_options(mut data: &[u8]) -> Vec<Ipv4Option> {
+    let mut options = Vec::new();
 
-    match protocol {
-        6 => { // TCP
-            if payload.len() < ip_header_len + 20 {
-                return Err("TCP header too short");
+    while let Some(&kind) = data.first() {
+        match kind {
+            0 => break,                   // End of Option List
+            1 => data = &data[1..],       // No-Operation
+            _ => {
+                let Some(&length) = data.get(1) else {
+                    break;
+                };
+                let length = length as usize;
+                if length < 2 || length > data.len() {
+                    break;
+                }
+                options.push(Ipv4Option {
+                    kind,
+                    data: data[2..length].to_vec(),
+                });
+                data = &data[length..];
             }
-            let (src_port, dst_port, flags) = crate::packet::tcp::parse_tcp_header(&payload[ip_header_len..ip_header_len + 20])?;
-            info.packet_type = PacketType::TCP;
-            info.src_port = Some(src_port);
-            info.dst_port = Some(dst_port);
-            info.tcp_flags = Some(flags);
-        }
-        17 => { // UDP
-            let udp_payload = &payload[ip_header_len..];
-            crate::packet::udp::parse_udp(udp_payload, info)?;
-        }
-        _ => {
-            info.packet_type = PacketType::IPv4;
         }
     }
 
-    Ok(())
+    options
+}
+
+/// RFC 1071 ones' complement sum; zero over a header that includes a valid checksum.
+fn internet_checksum(data: &[u8]) -> u16 {
+    let mut sum: u32 = data
+        .chunks(2)
+        .map(|pair| u16::from_be_bytes([pair[0], *pair.get(1).unwrap_or(&0)]) as u32)
+        .sum();
+
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+
+    !(sum as u16)
 }
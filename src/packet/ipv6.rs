@@ -1,6 +1,45 @@
-use crate::packet::{PacketInfo, PacketType};
+use crate::packet::{IpFragment, PacketInfo, PacketType};
+use crate::packet::transport::parse_transport;
+use std::fmt;
 use std::net::Ipv6Addr;
 
+/// Upper bound on chained extension headers, guarding against crafted loops
+const MAX_EXTENSION_HEADERS: usize = 16;
+
+/// An IPv6 extension header found while walking the header chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ipv6ExtHeader {
+    HopByHop,
+    Routing { routing_type: u8, segments_left: u8 },
+    Fragment { offset: u16, more_fragments: bool, identification: u32 },
+    DestinationOptions,
+    Authentication,
+    EncapsulatingSecurityPayload,
+    Mobility,
+}
+
+impl fmt::Display for Ipv6ExtHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ipv6ExtHeader::HopByHop => write!(f, "Hop-by-Hop"),
+            Ipv6ExtHeader::Routing { routing_type, segments_left } => {
+                write!(f, "Routing (type {}, {} left)", routing_type, segments_left)
+            }
+            Ipv6ExtHeader::Fragment { offset, more_fragments, identification } => write!(
+                f,
+                "Fragment (id 0x{:08x}, off {}{})",
+                identification,
+                offset,
+                if *more_fragments { ", MF" } else { "" }
+            ),
+            Ipv6ExtHeader::DestinationOptions => write!(f, "Destination Options"),
+            Ipv6ExtHeader::Authentication => write!(f, "AH"),
+            Ipv6ExtHeader::EncapsulatingSecurityPayload => write!(f, "ESP"),
+            Ipv6ExtHeader::Mobility => write!(f, "Mobility"),
+        }
+    }
+}
+
 pub fn parse_ipv6(payload: &[u8], info: &mut PacketInfo) -> Result<(), &'static str> {
     if payload.len() < 40 {
         return Err("IPv6 header too short");
@@ -20,30 +59,118 @@ pub fn parse_ipv6(payload: &[u8], info: &mut PacketInfo) -> Result<(), &'static
         payload[36], payload[37], payload[38], payload[39],
     ]);
 
-    let next_header = payload[6];
-
     info.src_ip = Some(src.to_string());
     info.dst_ip = Some(dst.to_string());
+    info.packet_type = PacketType::IPv6;
+
+    // Drop Ethernet padding; a zero payload length means a jumbogram, which we leave as is
+    let payload_len = u16::from_be_bytes([payload[4], payload[5]]) as usize;
+    let payload = if payload_len != 0 && 40 + payload_len <= payload.len() {
+        &payload[..40 + payload_len]
+    } else {
+        payload
+    };
+
+    let Some((next_header, offset)) = walk_extension_headers(payload, info)? else {
+        // No transport header to decode (ESP, no-next-header or a fragment)
+        return Ok(());
+    };
 
-    match next_header {
-        6 => { // TCP
-            if payload.len() < 40 + 20 {
-                return Err("TCP header too short");
+    parse_transport(next_header, &payload[offset..], info)
+}
+
+/// Walks the extension header chain starting after the fixed 40-byte header,
+/// recording every header in `info.ipv6_ext_headers`.
+///
+/// # Returns
+/// * `Some((protocol, offset))` of the upper-layer header
+/// * `None` if there is no decodable upper-layer header, including fragments
+fn walk_extension_headers(
+    payload: &[u8],
+    info: &mut PacketInfo,
+) -> Result<Option<(u8, usize)>, &'static str> {
+    let mut next_header = payload[6];
+    let mut offset = 40;
+
+    for _ in 0..MAX_EXTENSION_HEADERS {
+        let header = match next_header {
+            0 | 43 | 60 | 135 => {
+                if payload.len() < offset + 8 {
+                    return Err("IPv6 extension header truncated");
+                }
+                match next_header {
+                    0 => Ipv6ExtHeader::HopByHop,
+                    43 => Ipv6ExtHeader::Routing {
+                        routing_type: payload[offset + 2],
+                        segments_left: payload[offset + 3],
+                    },
+                    60 => Ipv6ExtHeader::DestinationOptions,
+                    _ => Ipv6ExtHeader::Mobility,
+                }
             }
-            let (src_port, dst_port, flags) = crate::packet::tcp::parse_tcp_header(&payload[40..40 + 20])?;
-            info.packet_type = PacketType::TCP;
-            info.src_port = Some(src_port);
-            info.dst_port = Some(dst_port);
-            info.tcp_flags = Some(flags);
-        }
-        17 => { // UDP
-            let udp_payload = &payload[40..];
-            crate::packet::udp::parse_udp(udp_payload, info)?;
+            44 => {
+                if payload.len() < offset + 8 {
+                    return Err("IPv6 fragment header truncated");
+                }
+                let offset_flags = u16::from_be_bytes([payload[offset + 2], payload[offset + 3]]);
+                Ipv6ExtHeader::Fragment {
+                    offset: offset_flags >> 3,
+                    more_fragments: offset_flags & 0x1 != 0,
+                    identification: u32::from_be_bytes([
+                        payload[offset + 4],
+                        payload[offset + 5],
+                        payload[offset + 6],
+                        payload[offset + 7],
+                    ]),
+                }
+            }
+            51 => {
+                if payload.len() < offset + 8 {
+                    return Err("IPv6 authentication header truncated");
+                }
+                Ipv6ExtHeader::Authentication
+            }
+            50 => {
+                // Everything after the ESP header is encrypted
+                info.ipv6_ext_headers.push(Ipv6ExtHeader::EncapsulatingSecurityPayload);
+                return Ok(None);
+            }
+            59 => return Ok(None), // No next header
+            upper => return Ok(Some((upper, offset))),
+        };
+
+        let header_len = match header {
+            Ipv6ExtHeader::Fragment { .. } => 8,
+            // AH length is in 4-byte units, not counting the first 8 bytes
+            Ipv6ExtHeader::Authentication => (payload[offset + 1] as usize + 2) * 4,
+            // Other extension headers count 8-byte units, not counting the first 8 bytes
+            _ => (payload[offset + 1] as usize + 1) * 8,
+        };
+
+        info.ipv6_ext_headers.push(header);
+        next_header = payload[offset];
+        offset += header_len;
+
+        if offset > payload.len() {
+            return Err("IPv6 extension header length exceeds payload");
         }
-        _ => {
-            info.packet_type = PacketType::IPv6;
+
+        // Atomic fragments (offset 0, no MF) are decoded like unfragmented packets
+        if let Ipv6ExtHeader::Fragment { offset: fragment_offset, more_fragments, identification } = header
+            && (more_fragments || fragment_offset != 0)
+        {
+            info.fragment = Some(IpFragment {
+                identification,
+                offset: fragment_offset as usize * 8,
+                more_fragments,
+                protocol: next_header,
+                payload: payload[offset..].to_vec(),
+                reassembled: None,
+            });
+            // The transport header is decoded once the datagram is reassembled
+            return Ok(None);
         }
     }
 
-    Ok(())
+    Err("Too many IPv6 extension headers")
 }
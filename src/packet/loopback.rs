@@ -0,0 +1,47 @@
+use super::PacketInfo;
+use crate::packet::{ipv4, ipv6};
+
+/// Address family values used for IPv4 by every BSD-derived loopback header
+const AF_INET: u32 = 2;
+
+/// The IPv6 address family differs per operating system:
+/// Linux (10), NetBSD/OpenBSD/BSD/OS (24), FreeBSD (28) and Darwin (30).
+const AF_INET6_VALUES: [u32; 4] = [10, 24, 28, 30];
+
+/// Parses a BSD loopback frame (DLT_NULL). The 4-byte address family is in the
+/// byte order of the machine that wrote the capture, so both orders are tried.
+pub fn parse_null(data: &[u8], info: &mut PacketInfo) -> Result<(), &'static str> {
+    if data.len() < 4 {
+        return Err("Packet too short for loopback header");
+    }
+
+    let family = [data[0], data[1], data[2], data[3]];
+    // Family values are small, so the non-zero half tells us the byte order
+    let family = if family[0] == 0 && family[1] == 0 {
+        u32::from_be_bytes(family)
+    } else {
+        u32::from_le_bytes(family)
+    };
+
+    parse_family(family, &data[4..], info)
+}
+
+/// Parses an OpenBSD loopback frame (DLT_LOOP), whose address family is always big-endian.
+pub fn parse_loop(data: &[u8], info: &mut PacketInfo) -> Result<(), &'static str> {
+    if data.len() < 4 {
+        return Err("Packet too short for loopback header");
+    }
+
+    let family = u32::from_be_bytes([data[0], data[1], data[2], data[3]]);
+    parse_family(family, &data[4..], info)
+}
+
+fn parse_family(family: u32, payload: &[u8], info: &mut PacketInfo) -> Result<(), &'static str> {
+    if family == AF_INET {
+        ipv4::parse_ipv4(payload, info)
+    } else if AF_INET6_VALUES.contains(&family) {
+        ipv6::parse_ipv6(payload, info)
+    } else {
+        Err("Unsupported loopback address family")
+    }
+}
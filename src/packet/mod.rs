@@ -1,22 +1,71 @@
 mod ethernet;
+mod arp;
+mod sll;
+mod loopback;
+mod raw;
+mod vlan;
 mod ipv4;
+mod icmp;
+mod icmpv6;
 mod ipv6;
+mod fragment;
+mod transport;
 mod udp;
 mod dns;
+mod dhcp;
+mod dhcpv6;
+mod hpack;
+mod http;
+mod http2;
+mod quic;
+mod tls;
+mod x509;
 mod tcp;
 
 pub use tcp::*;
+pub use arp::ArpPacket;
+pub use dhcp::{DhcpInfo, DhcpMessageType};
+pub use quic::{QuicInfo, QuicPacketType};
+pub use tls::{parse_certificate_message, parse_client_hello, parse_server_hello, TlsInfo, TlsTransport};
+pub use x509::{format_unix_time, Certificate};
+pub use http::{looks_like_http, parse_http_head, HttpMessage, HttpStartLine};
+pub use hpack::HpackDecoder;
+pub use http2::{
+    error_code_name, grpc_call, header_value, parse_control_frame, strip_padding, Http2Frame, Http2FrameKind,
+    FLAG_END_HEADERS, FLAG_PADDED, FLAG_PRIORITY, HTTP2_PREFACE,
+};
+pub use dhcpv6::{format_duid, Dhcpv6Info, Dhcpv6MessageType, IaAddress, IaPrefix};
+pub use dns::{dns_protocol_for_ports, parse_dns_message, rcode_name, set_extra_dns_ports, type_name, DnsMessage, DnsProtocol, DnsRecord};
+pub use icmp::IcmpInfo;
+pub use icmpv6::Icmpv6Info;
+pub use ipv4::Ipv4Header;
+pub use ipv6::Ipv6ExtHeader;
+pub use fragment::{IpFragment, Reassembly};
+pub use transport::parse_transport;
+pub use ethernet::format_mac;
+pub use vlan::VlanTag;
 
+use pcap::Linktype;
 use std::fmt;
+use std::time::Duration;
 
 #[derive(Hash, Eq, PartialEq, Debug, Clone)]
 pub enum PacketType {
     Ethernet,
+    ARP,
     IPv4,
     IPv6,
+    ICMP,
+    ICMPv6,
     TCP,
     UDP,
     DNS,
+    DHCP,
+    DHCPv6,
+    HTTP,
+    HTTP2,
+    TLS,
+    QUIC,
     Other(u16),
 }
 
@@ -24,29 +73,243 @@ impl fmt::Display for PacketType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             PacketType::Ethernet => write!(f, "Ethernet"),
+            PacketType::ARP => write!(f, "ARP"),
             PacketType::IPv4 => write!(f, "IPv4"),
             PacketType::IPv6 => write!(f, "IPv6"),
+            PacketType::ICMP => write!(f, "ICMP"),
+            PacketType::ICMPv6 => write!(f, "ICMPv6"),
             PacketType::TCP => write!(f, "TCP"),
             PacketType::UDP => write!(f, "UDP"),
             PacketType::DNS => write!(f, "DNS"),
+            PacketType::DHCP => write!(f, "DHCP"),
+            PacketType::DHCPv6 => write!(f, "DHCPv6"),
+            PacketType::HTTP => write!(f, "HTTP"),
+            PacketType::HTTP2 => write!(f, "HTTP/2"),
+            PacketType::TLS => write!(f, "TLS"),
+            PacketType::QUIC => write!(f, "QUIC"),
             PacketType::Other(t) => write!(f, "Other EtherType 0x{:04x}", t),
         }
     }
 }
 
+/// Capture metadata from the pcap record header of a frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameMeta {
+    /// Capture time since the Unix epoch
+    pub timestamp: Duration,
+    /// Number of bytes actually captured
+    pub caplen: u32,
+    /// Length of the frame on the wire (may exceed `caplen` if truncated by the snaplen)
+    pub orig_len: u32,
+}
+
 #[derive(Debug, Clone)]
 pub struct PacketInfo {
+    pub timestamp: Duration,
+    pub caplen: u32,
+    pub orig_len: u32,
     pub packet_type: PacketType,
     pub src_mac: Option<[u8; 6]>,
     pub dst_mac: Option<[u8; 6]>,
+    /// VLAN tags in the order they appear on the wire (outermost first)
+    pub vlan_tags: Vec<VlanTag>,
     pub src_ip: Option<String>,
     pub dst_ip: Option<String>,
+    pub ipv4: Option<Ipv4Header>,
+    /// IPv6 extension headers in chain order
+    pub ipv6_ext_headers: Vec<Ipv6ExtHeader>,
+    /// Set when the IP datagram was fragmented
+    pub fragment: Option<IpFragment>,
     pub src_port: Option<u16>,
     pub dst_port: Option<u16>,
     pub tcp_flags: Option<TcpFlags>,
+    pub tcp: Option<TcpHeader>,
     pub dns_queries: Option<Vec<String>>,
+    /// DNS messages carried by this packet; TCP segments may complete several
+    pub dns: Vec<DnsMessage>,
+    pub dhcp: Option<DhcpInfo>,
+    pub dhcpv6: Option<Dhcpv6Info>,
+    /// HTTP/1.x message heads completed by this packet
+    pub http: Vec<HttpMessage>,
+    /// HTTP/2 frames completed by this packet
+    pub http2: Vec<Http2Frame>,
+    /// TLS handshake messages completed by this packet
+    pub tls: Vec<TlsInfo>,
+    pub quic: Option<QuicInfo>,
+    pub arp: Option<ArpPacket>,
+    pub icmp: Option<IcmpInfo>,
+    pub icmpv6: Option<Icmpv6Info>,
+    /// Transport-layer payload (currently filled in for TCP segments)
+    pub payload: Vec<u8>,
+}
+
+impl PacketInfo {
+    /// Creates an empty `PacketInfo` for a frame; the link-layer parser fills in the rest.
+    pub fn new(meta: FrameMeta) -> Self {
+        PacketInfo {
+            timestamp: meta.timestamp,
+            caplen: meta.caplen,
+            orig_len: meta.orig_len,
+            packet_type: PacketType::Ethernet,
+            src_mac: None,
+            dst_mac: None,
+            vlan_tags: Vec::new(),
+            src_ip: None,
+            dst_ip: None,
+            ipv4: None,
+            ipv6_ext_headers: Vec::new(),
+            fragment: None,
+            src_port: None,
+            dst_port: None,
+            tcp_flags: None,
+            tcp: None,
+            dns_queries: None,
+            dns: Vec::new(),
+            dhcp: None,
+            dhcpv6: None,
+            http: Vec::new(),
+            http2: Vec::new(),
+            tls: Vec::new(),
+            quic: None,
+            arp: None,
+            icmp: None,
+            icmpv6: None,
+            payload: Vec::new(),
+        }
+    }
+
+    /// One-line, protocol specific description used for "Info" columns.
+    pub fn summary(&self) -> String {
+        let summary = self.protocol_summary();
+        if self.ipv4.as_ref().is_none_or(|header| header.checksum_valid) {
+            return summary;
+        }
+        if summary.is_empty() {
+            "[bad IPv4 checksum]".into()
+        } else {
+            format!("{} [bad IPv4 checksum]", summary)
+        }
+    }
+
+    fn protocol_summary(&self) -> String {
+        if let Some(arp) = &self.arp {
+            return arp.summary();
+        }
+        if let Some(icmp) = &self.icmp {
+            return icmp.summary();
+        }
+        if let Some(icmpv6) = &self.icmpv6 {
+            return icmpv6.summary();
+        }
+        if !self.dns.is_empty() {
+            return self.dns.iter().map(DnsMessage::summary).collect::<Vec<_>>().join(", ");
+        }
+        if let Some(dhcp) = &self.dhcp {
+            return dhcp.summary();
+        }
+        if let Some(dhcpv6) = &self.dhcpv6 {
+            return dhcpv6.summary();
+        }
+        if !self.http.is_empty() {
+            return self.http.iter().map(HttpMessage::summary).collect::<Vec<_>>().join(", ");
+        }
+        if !self.http2.is_empty() {
+            return self.http2.iter().map(Http2Frame::summary).collect::<Vec<_>>().join(", ");
+        }
+        if let Some(quic) = &self.quic {
+            // A decrypted Initial adds the ClientHello it completed
+            let tls = self.tls.iter().map(|message| format!(", {}", message.summary()));
+            return quic.summary() + &tls.collect::<String>();
+        }
+        if !self.tls.is_empty() {
+            return self.tls.iter().map(TlsInfo::summary).collect::<Vec<_>>().join(", ");
+        }
+        if let Some(tcp) = &self.tcp {
+            return tcp.summary();
+        }
+        if let Some(fragment) = &self.fragment {
+            return fragment.to_string();
+        }
+        if !self.ipv6_ext_headers.is_empty() {
+            return self
+                .ipv6_ext_headers
+                .iter()
+                .map(|header| header.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+        }
+        String::new()
+    }
+
+    /// Formats the VLAN tag stack, e.g. `100/200 p5`; empty for untagged frames.
+    pub fn vlan_label(&self) -> String {
+        self.vlan_tags
+            .iter()
+            .map(|tag| tag.to_string())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Returns true if any VLAN tag on the frame carries the given VLAN ID.
+    pub fn has_vlan(&self, id: u16) -> bool {
+        self.vlan_tags.iter().any(|tag| tag.id == id)
+    }
+}
+
+/// Parses a captured frame, choosing the link-layer decoder from the capture's datalink type.
+pub fn parse_packet(linktype: Linktype, data: &[u8], meta: FrameMeta) -> Result<PacketInfo, &'static str> {
+    let mut info = PacketInfo::new(meta);
+
+    match linktype {
+        Linktype::ETHERNET => ethernet::parse_ethernet(data, &mut info)?,
+        Linktype::LINUX_SLL => sll::parse_sll(data, &mut info)?,
+        Linktype::LINUX_SLL2 => sll::parse_sll2(data, &mut info)?,
+        Linktype::NULL => loopback::parse_null(data, &mut info)?,
+        Linktype::LOOP => loopback::parse_loop(data, &mut info)?,
+        // LINKTYPE_RAW in files; DLT_RAW is 12 on most platforms and 14 on OpenBSD
+        Linktype::RAW | Linktype(12) | Linktype(14) | Linktype::IPV4 | Linktype::IPV6 => {
+            raw::parse_raw_ip(data, &mut info)?
+        }
+        _ => return Err("Unsupported datalink type"),
+    }
+
+    Ok(info)
+}
+
+/// Formats a capture timestamp as a UTC date and time with microsecond precision,
+/// e.g. `2024-03-01 12:34:56.789012`.
+pub fn format_timestamp(ts: Duration) -> String {
+    let secs = ts.as_secs();
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    format!(
+        "{:04}-{:02}-{:02} {}",
+        year, month, day, format_time_of_day(ts)
+    )
+}
+
+/// Formats only the UTC time of day of a capture timestamp, e.g. `12:34:56.789012`.
+pub fn format_time_of_day(ts: Duration) -> String {
+    let secs_of_day = ts.as_secs() % 86_400;
+    format!(
+        "{:02}:{:02}:{:02}.{:06}",
+        secs_of_day / 3600,
+        (secs_of_day / 60) % 60,
+        secs_of_day % 60,
+        ts.subsec_micros()
+    )
 }
 
-pub fn parse_packet(data: &[u8]) -> Result<PacketInfo, &'static str> {
-    ethernet::parse_packet(data)
+/// Converts days since 1970-01-01 into a (year, month, day) civil date.
+/// Based on Howard Hinnant's `civil_from_days` algorithm.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
 }
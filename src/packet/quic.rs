@@ -0,0 +1,551 @@
+use aes::cipher::{generic_array::GenericArray, BlockEncrypt};
+use aes::Aes128;
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes128Gcm, Nonce};
+use hkdf::Hkdf;
+use sha2::Sha256;
+use std::fmt;
+
+use super::{PacketInfo, PacketType};
+
+const VERSION_1: u32 = 0x0000_0001;
+const VERSION_2: u32 = 0x6b33_43cf;
+const VERSION_DRAFT_29: u32 = 0xff00_001d;
+/// Longest connection ID allowed by RFC 9000
+const MAX_CID_LEN: usize = 20;
+
+/// Initial salts (RFC 9001 section 5.2, RFC 9369 section 3.3.1, draft-ietf-quic-tls-29)
+const SALT_V1: [u8; 20] = [
+    0x38, 0x76, 0x2c, 0xf7, 0xf5, 0x59, 0x34, 0xb3, 0x4d, 0x17, 0x9a, 0xe6, 0xa4, 0xc8, 0x0c, 0xad, 0xcc, 0xbb, 0x7f,
+    0x0a,
+];
+const SALT_V2: [u8; 20] = [
+    0x0d, 0xed, 0xe3, 0xde, 0xf7, 0x00, 0xa6, 0xdb, 0x81, 0x93, 0x81, 0xbe, 0x6e, 0x26, 0x9d, 0xcb, 0xf9, 0xbd, 0x2e,
+    0xd9,
+];
+const SALT_DRAFT_29: [u8; 20] = [
+    0xaf, 0xbf, 0xec, 0x28, 0x99, 0x93, 0xd2, 0x4c, 0x9e, 0x97, 0x86, 0xf1, 0x9c, 0x61, 0x11, 0xe0, 0x43, 0x90, 0xa8,
+    0x99,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuicPacketType {
+    Initial,
+    ZeroRtt,
+    Handshake,
+    Retry,
+    VersionNegotiation,
+    /// Short header packet
+    OneRtt,
+    /// Long header packet of a version whose layout beyond the connection IDs is unknown
+    /// (RFC 8999 only fixes the version and connection IDs)
+    UnknownVersion,
+}
+
+impl fmt::Display for QuicPacketType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuicPacketType::Initial => write!(f, "Initial"),
+            QuicPacketType::ZeroRtt => write!(f, "0-RTT"),
+            QuicPacketType::Handshake => write!(f, "Handshake"),
+            QuicPacketType::Retry => write!(f, "Retry"),
+            QuicPacketType::VersionNegotiation => write!(f, "Version Negotiation"),
+            QuicPacketType::OneRtt => write!(f, "1-RTT"),
+            QuicPacketType::UnknownVersion => write!(f, "Long Header"),
+        }
+    }
+}
+
+/// One QUIC packet; a UDP datagram may carry several coalesced long header packets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuicPacket {
+    pub packet_type: QuicPacketType,
+    /// Absent in short header packets
+    pub version: Option<u32>,
+    /// Destination connection ID; for short header packets only known once the
+    /// receiver's connection ID length has been learned from its long header packets
+    pub dcid: Option<Vec<u8>>,
+    pub scid: Option<Vec<u8>>,
+    /// Bytes following the first byte of a short header packet, of which the DCID is a prefix
+    pub short_header_bytes: Vec<u8>,
+    /// Versions offered in a Version Negotiation packet
+    pub supported_versions: Vec<u32>,
+    /// Whether the payload of a client Initial was decrypted
+    pub decrypted: bool,
+    /// CRYPTO frames (offset, data) from a decrypted Initial
+    pub crypto: Vec<(u64, Vec<u8>)>,
+}
+
+/// Decoded QUIC packets of one UDP datagram.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuicInfo {
+    pub packets: Vec<QuicPacket>,
+}
+
+impl QuicInfo {
+    /// One-line description, e.g. `QUIC v1 Initial DCID 8394c8f03e515708 SCID -`.
+    pub fn summary(&self) -> String {
+        let packets: Vec<_> = self
+            .packets
+            .iter()
+            .map(|packet| {
+                let cid = |cid: &Option<Vec<u8>>| match cid.as_deref() {
+                    Some(cid) if !cid.is_empty() => hex(cid),
+                    _ => "-".into(),
+                };
+                match packet.packet_type {
+                    QuicPacketType::OneRtt => format!("1-RTT DCID {}", cid(&packet.dcid)),
+                    QuicPacketType::VersionNegotiation => {
+                        let versions: Vec<_> = packet.supported_versions.iter().map(|&v| version_name(v)).collect();
+                        format!("Version Negotiation [{}]", versions.join(", "))
+                    }
+                    packet_type => format!(
+                        "{} {} DCID {} SCID {}",
+                        version_name(packet.version.unwrap_or_default()),
+                        packet_type,
+                        cid(&packet.dcid),
+                        cid(&packet.scid)
+                    ),
+                }
+            })
+            .collect();
+        format!("QUIC {}", packets.join("; "))
+    }
+}
+
+pub fn version_name(version: u32) -> String {
+    match version {
+        VERSION_1 => "v1".into(),
+        VERSION_2 => "v2".into(),
+        v if v >> 8 == 0x00ff_0000 => format!("draft-{}", v & 0xff),
+        v => format!("0x{:08x}", v),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Whether a UDP payload on a QUIC port looks like a QUIC packet (fixed bit set,
+/// or a Version Negotiation packet which may clear it).
+pub fn looks_like_quic(data: &[u8]) -> bool {
+    match data {
+        [first, _, _, _, _, ..] if first & 0x80 != 0 => {
+            first & 0x40 != 0 || data[1..5] == [0, 0, 0, 0]
+        }
+        [first, ..] => first & 0x40 != 0,
+        [] => false,
+    }
+}
+
+/// Parses the QUIC packets in a UDP datagram, decrypting client Initial packets.
+/// A coalesced packet that fails to parse ends the datagram; the packets before it are kept.
+pub fn parse_quic(data: &[u8], info: &mut PacketInfo) -> Result<(), &'static str> {
+    let mut packets = Vec::new();
+    let mut rest = data;
+
+    while let Some(&first) = rest.first() {
+        if first & 0x80 == 0 {
+            // A short header packet extends to the end of the datagram
+            packets.push(QuicPacket {
+                packet_type: QuicPacketType::OneRtt,
+                version: None,
+                dcid: None,
+                scid: None,
+                short_header_bytes: rest[1..].iter().take(MAX_CID_LEN).copied().collect(),
+                supported_versions: Vec::new(),
+                decrypted: false,
+                crypto: Vec::new(),
+            });
+            break;
+        }
+
+        let Ok((packet, len)) = parse_long_header(rest) else {
+            break;
+        };
+        packets.push(packet);
+        rest = &rest[len..];
+        // Anything after a packet without a length field, or trailing padding, ends the datagram
+        if rest.first().is_none_or(|&b| b == 0) {
+            break;
+        }
+    }
+
+    if packets.is_empty() {
+        return Err("QUIC datagram empty");
+    }
+    info.packet_type = PacketType::QUIC;
+    info.quic = Some(QuicInfo { packets });
+    Ok(())
+}
+
+/// Parses one long header packet, returning it and its length within the datagram.
+fn parse_long_header(data: &[u8]) -> Result<(QuicPacket, usize), &'static str> {
+    let mut reader = Reader::new(data);
+    let first = reader.u8().ok_or("QUIC header truncated")?;
+    let version = reader.u32().ok_or("QUIC version truncated")?;
+    let dcid = reader.vec8().ok_or("QUIC destination connection ID truncated")?;
+    let scid = reader.vec8().ok_or("QUIC source connection ID truncated")?;
+    if dcid.len() > MAX_CID_LEN || scid.len() > MAX_CID_LEN {
+        return Err("QUIC connection ID too long");
+    }
+
+    let mut packet = QuicPacket {
+        packet_type: QuicPacketType::VersionNegotiation,
+        version: Some(version),
+        dcid: Some(dcid.to_vec()),
+        scid: Some(scid.to_vec()),
+        short_header_bytes: Vec::new(),
+        supported_versions: Vec::new(),
+        decrypted: false,
+        crypto: Vec::new(),
+    };
+
+    if version == 0 {
+        while let Some(supported) = reader.u32() {
+            packet.supported_versions.push(supported);
+        }
+        return Ok((packet, data.len()));
+    }
+    if !matches!(version, VERSION_1 | VERSION_2 | VERSION_DRAFT_29) {
+        // Unknown and GREASE versions: nothing past the connection IDs is version independent
+        packet.packet_type = QuicPacketType::UnknownVersion;
+        return Ok((packet, data.len()));
+    }
+
+    // QUIC v2 rotates the long packet type codes
+    let type_bits = (first >> 4) & 0x03;
+    packet.packet_type = match (version == VERSION_2, type_bits) {
+        (false, 0) | (true, 1) => QuicPacketType::Initial,
+        (false, 1) | (true, 2) => QuicPacketType::ZeroRtt,
+        (false, 2) | (true, 3) => QuicPacketType::Handshake,
+        _ => QuicPacketType::Retry,
+    };
+
+    if packet.packet_type == QuicPacketType::Retry {
+        return Ok((packet, data.len()));
+    }
+    if packet.packet_type == QuicPacketType::Initial {
+        let token_len = reader.varint().ok_or("QUIC token length truncated")?;
+        reader.take(token_len as usize).ok_or("QUIC token truncated")?;
+    }
+    let length = reader.varint().ok_or("QUIC length truncated")? as usize;
+    let pn_offset = data.len() - reader.rest().len();
+    let end = pn_offset.checked_add(length).filter(|&end| end <= data.len()).ok_or("QUIC packet length exceeds datagram")?;
+
+    if packet.packet_type == QuicPacketType::Initial
+        && let Some(plaintext) = decrypt_initial(&data[..end], pn_offset, version, dcid)
+    {
+        packet.decrypted = true;
+        packet.crypto = crypto_frames(&plaintext);
+    }
+
+    Ok((packet, end))
+}
+
+/// Removes header protection and decrypts a client Initial packet with keys derived
+/// from its destination connection ID (RFC 9001 section 5). Returns None for server
+/// Initials, which use the client's original connection ID, and unknown versions.
+fn decrypt_initial(packet: &[u8], pn_offset: usize, version: u32, dcid: &[u8]) -> Option<Vec<u8>> {
+    let (salt, label_prefix) = match version {
+        VERSION_1 => (&SALT_V1, "quic"),
+        VERSION_2 => (&SALT_V2, "quicv2"),
+        VERSION_DRAFT_29 => (&SALT_DRAFT_29, "quic"),
+        _ => return None,
+    };
+
+    let (initial_secret, _) = Hkdf::<Sha256>::extract(Some(salt), dcid);
+    let initial = Hkdf::<Sha256>::from_prk(&initial_secret).ok()?;
+    let mut client_secret = [0u8; 32];
+    expand_label(&initial, "client in", &mut client_secret)?;
+    let client = Hkdf::<Sha256>::from_prk(&client_secret).ok()?;
+
+    let mut key = [0u8; 16];
+    let mut iv = [0u8; 12];
+    let mut hp = [0u8; 16];
+    expand_label(&client, &format!("{} key", label_prefix), &mut key)?;
+    expand_label(&client, &format!("{} iv", label_prefix), &mut iv)?;
+    expand_label(&client, &format!("{} hp", label_prefix), &mut hp)?;
+
+    // Header protection: the mask comes from a sample 4 bytes past the packet number start
+    let sample = packet.get(pn_offset + 4..pn_offset + 20)?;
+    let mut mask = GenericArray::clone_from_slice(sample);
+    Aes128::new(GenericArray::from_slice(&hp)).encrypt_block(&mut mask);
+
+    let mut header = packet[..pn_offset].to_vec();
+    header[0] ^= mask[0] & 0x0f;
+    let pn_len = (header[0] & 0x03) as usize + 1;
+    let mut packet_number = 0u64;
+    for i in 0..pn_len {
+        let byte = *packet.get(pn_offset + i)? ^ mask[1 + i];
+        header.push(byte);
+        packet_number = (packet_number << 8) | byte as u64;
+    }
+
+    let mut nonce = iv;
+    for (i, byte) in packet_number.to_be_bytes().iter().enumerate() {
+        nonce[4 + i] ^= byte;
+    }
+
+    let cipher = Aes128Gcm::new_from_slice(&key).ok()?;
+    let payload = Payload {
+        msg: &packet[pn_offset + pn_len..],
+        aad: &header,
+    };
+    cipher.decrypt(Nonce::from_slice(&nonce), payload).ok()
+}
+
+/// HKDF-Expand-Label from TLS 1.3 (RFC 8446 section 7.1) with an empty context.
+fn expand_label(hkdf: &Hkdf<Sha256>, label: &str, out: &mut [u8]) -> Option<()> {
+    let label = format!("tls13 {}", label);
+    let mut info = Vec::with_capacity(4 + label.len());
+    info.extend_from_slice(&(out.len() as u16).to_be_bytes());
+    info.push(label.len() as u8);
+    info.extend_from_slice(label.as_bytes());
+    info.push(0);
+    hkdf.expand(&info, out).ok()
+}
+
+/// Collects CRYPTO frames from a decrypted Initial payload, stopping at the
+/// first frame type not allowed in Initial packets.
+fn crypto_frames(payload: &[u8]) -> Vec<(u64, Vec<u8>)> {
+    let mut frames = Vec::new();
+    let mut reader = Reader::new(payload);
+
+    while let Some(frame_type) = reader.varint() {
+        match frame_type {
+            0x00 | 0x01 => {} // PADDING, PING
+            0x02 | 0x03 => {
+                // ACK: largest acknowledged, delay, range count, first range, then two varints
+                // per additional range and, for 0x03, three ECN counts
+                let Some(ranges) = reader.skip_varints(2).and_then(|_| reader.varint()) else {
+                    break;
+                };
+                let ecn_counts = if frame_type == 0x03 { 3 } else { 0 };
+                if reader.skip_varints(1 + ranges.saturating_mul(2) + ecn_counts).is_none() {
+                    break;
+                }
+            }
+            0x06 => {
+                let (Some(offset), Some(len)) = (reader.varint(), reader.varint()) else {
+                    break;
+                };
+                let Some(data) = reader.take(len as usize) else {
+                    break;
+                };
+                frames.push((offset, data.to_vec()));
+            }
+            _ => break,
+        }
+    }
+
+    frames
+}
+
+/// Cursor over QUIC wire data.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Reader { data }
+    }
+
+    fn rest(&self) -> &'a [u8] {
+        self.data
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.data.len() < len {
+            return None;
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Some(head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take(4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn vec8(&mut self) -> Option<&'a [u8]> {
+        let len = self.u8()? as usize;
+        self.take(len)
+    }
+
+    /// A variable-length integer (RFC 9000 section 16).
+    fn varint(&mut self) -> Option<u64> {
+        let first = *self.data.first()?;
+        let len = 1usize << (first >> 6);
+        let bytes = self.take(len)?;
+        Some(bytes[1..].iter().fold((first & 0x3f) as u64, |value, &b| (value << 8) | b as u64))
+    }
+
+    fn skip_varints(&mut self, count: u64) -> Option<()> {
+        for _ in 0..count {
+            self.varint()?;
+        }
+        Some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packet::{parse_client_hello, FrameMeta, TlsTransport};
+
+    /// ClientHello carried in the CRYPTO frame of the RFC 9001 and RFC 9369 examples
+    const CLIENT_HELLO: &str = "\
+        010000ed0303ebf8fa56f12939b9584a3896472ec40bb863cfd3e86804fe3a47\
+        f06a2b69484c00000413011302010000c000000010000e00000b6578616d706c\
+        652e636f6dff01000100000a00080006001d0017001800100007000504616c70\
+        6e000500050100000000003300260024001d00209370b2c9caa47fbabaf4559f\
+        edba753de171fa71f50f1ce15d43e994ec74d748002b0003020304000d001000\
+        0e0403050306030203080408050806002d00020101001c000240010039003204\
+        08ffffffffffffffff05048000ffff07048000ffff0801100104800075300901\
+        100f088394c8f03e51570806048000ffff";
+
+    /// Protected client Initial from RFC 9001 Appendix A.2
+    const V1_CLIENT_INITIAL: &str = "\
+        c000000001088394c8f03e5157080000449e7b9aec34d1b1c98dd7689fb8ec11\
+        d242b123dc9bd8bab936b47d92ec356c0bab7df5976d27cd449f63300099f399\
+        1c260ec4c60d17b31f8429157bb35a1282a643a8d2262cad67500cadb8e7378c\
+        8eb7539ec4d4905fed1bee1fc8aafba17c750e2c7ace01e6005f80fcb7df6212\
+        30c83711b39343fa028cea7f7fb5ff89eac2308249a02252155e2347b63d58c5\
+        457afd84d05dfffdb20392844ae812154682e9cf012f9021a6f0be17ddd0c208\
+        4dce25ff9b06cde535d0f920a2db1bf362c23e596d11a4f5a6cf3948838a3aec\
+        4e15daf8500a6ef69ec4e3feb6b1d98e610ac8b7ec3faf6ad760b7bad1db4ba3\
+        485e8a94dc250ae3fdb41ed15fb6a8e5eba0fc3dd60bc8e30c5c4287e53805db\
+        059ae0648db2f64264ed5e39be2e20d82df566da8dd5998ccabdae053060ae6c\
+        7b4378e846d29f37ed7b4ea9ec5d82e7961b7f25a9323851f681d582363aa5f8\
+        9937f5a67258bf63ad6f1a0b1d96dbd4faddfcefc5266ba6611722395c906556\
+        be52afe3f565636ad1b17d508b73d8743eeb524be22b3dcbc2c7468d54119c74\
+        68449a13d8e3b95811a198f3491de3e7fe942b330407abf82a4ed7c1b311663a\
+        c69890f4157015853d91e923037c227a33cdd5ec281ca3f79c44546b9d90ca00\
+        f064c99e3dd97911d39fe9c5d0b23a229a234cb36186c4819e8b9c5927726632\
+        291d6a418211cc2962e20fe47feb3edf330f2c603a9d48c0fcb5699dbfe58964\
+        25c5bac4aee82e57a85aaf4e2513e4f05796b07ba2ee47d80506f8d2c25e50fd\
+        14de71e6c418559302f939b0e1abd576f279c4b2e0feb85c1f28ff18f58891ff\
+        ef132eef2fa09346aee33c28eb130ff28f5b766953334113211996d20011a198\
+        e3fc433f9f2541010ae17c1bf202580f6047472fb36857fe843b19f5984009dd\
+        c324044e847a4f4a0ab34f719595de37252d6235365e9b84392b061085349d73\
+        203a4a13e96f5432ec0fd4a1ee65accdd5e3904df54c1da510b0ff20dcc0c77f\
+        cb2c0e0eb605cb0504db87632cf3d8b4dae6e705769d1de354270123cb11450e\
+        fc60ac47683d7b8d0f811365565fd98c4c8eb936bcab8d069fc33bd801b03ade\
+        a2e1fbc5aa463d08ca19896d2bf59a071b851e6c239052172f296bfb5e724047\
+        90a2181014f3b94a4e97d117b438130368cc39dbb2d198065ae3986547926cd2\
+        162f40a29f0c3c8745c0f50fba3852e566d44575c29d39a03f0cda721984b6f4\
+        40591f355e12d439ff150aab7613499dbd49adabc8676eef023b15b65bfc5ca0\
+        6948109f23f350db82123535eb8a7433bdabcb909271a6ecbcb58b936a88cd4e\
+        8f2e6ff5800175f113253d8fa9ca8885c2f552e657dc603f252e1a8e308f76f0\
+        be79e2fb8f5d5fbbe2e30ecadd220723c8c0aea8078cdfcb3868263ff8f09400\
+        54da48781893a7e49ad5aff4af300cd804a6b6279ab3ff3afb64491c85194aab\
+        760d58a606654f9f4400e8b38591356fbf6425aca26dc85244259ff2b19c41b9\
+        f96f3ca9ec1dde434da7d2d392b905ddf3d1f9af93d1af5950bd493f5aa731b4\
+        056df31bd267b6b90a079831aaf579be0a39013137aac6d404f518cfd4684064\
+        7e78bfe706ca4cf5e9c5453e9f7cfd2b8b4c8d169a44e55c88d4a9a7f9474241\
+        e221af44860018ab0856972e194cd934";
+
+    /// Protected client Initial from RFC 9369 Appendix A.2
+    const V2_CLIENT_INITIAL: &str = "\
+        d76b3343cf088394c8f03e5157080000449ea0c95e82ffe67b6abcdb4298b485\
+        dd04de806071bf03dceebfa162e75d6c96058bdbfb127cdfcbf903388e99ad04\
+        9f9a3dd4425ae4d0992cfff18ecf0fdb5a842d09747052f17ac2053d21f57c5d\
+        250f2c4f0e0202b70785b7946e992e58a59ac52dea6774d4f03b55545243cf1a\
+        12834e3f249a78d395e0d18f4d766004f1a2674802a747eaa901c3f10cda5500\
+        cb9122faa9f1df66c392079a1b40f0de1c6054196a11cbea40afb6ef5253cd68\
+        18f6625efce3b6def6ba7e4b37a40f7732e093daa7d52190935b8da58976ff33\
+        12ae50b187c1433c0f028edcc4c2838b6a9bfc226ca4b4530e7a4ccee1bfa2a3\
+        d396ae5a3fb512384b2fdd851f784a65e03f2c4fbe11a53c7777c023462239dd\
+        6f7521a3f6c7d5dd3ec9b3f233773d4b46d23cc375eb198c63301c21801f6520\
+        bcfb7966fc49b393f0061d974a2706df8c4a9449f11d7f3d2dcbb90c6b877045\
+        636e7c0c0fe4eb0f697545460c806910d2c355f1d253bc9d2452aaa549e27a1f\
+        ac7cf4ed77f322e8fa894b6a83810a34b361901751a6f5eb65a0326e07de7c12\
+        16ccce2d0193f958bb3850a833f7ae432b65bc5a53975c155aa4bcb4f7b2c4e5\
+        4df16efaf6ddea94e2c50b4cd1dfe06017e0e9d02900cffe1935e0491d77ffb4\
+        fdf85290fdd893d577b1131a610ef6a5c32b2ee0293617a37cbb08b847741c3b\
+        8017c25ca9052ca1079d8b78aebd47876d330a30f6a8c6d61dd1ab5589329de7\
+        14d19d61370f8149748c72f132f0fc99f34d766c6938597040d8f9e2bb522ff9\
+        9c63a344d6a2ae8aa8e51b7b90a4a806105fcbca31506c446151adfeceb51b91\
+        abfe43960977c87471cf9ad4074d30e10d6a7f03c63bd5d4317f68ff325ba3bd\
+        80bf4dc8b52a0ba031758022eb025cdd770b44d6d6cf0670f4e990b22347a7db\
+        848265e3e5eb72dfe8299ad7481a408322cac55786e52f633b2fb6b614eaed18\
+        d703dd84045a274ae8bfa73379661388d6991fe39b0d93debb41700b41f90a15\
+        c4d526250235ddcd6776fc77bc97e7a417ebcb31600d01e57f32162a8560cacc\
+        7e27a096d37a1a86952ec71bd89a3e9a30a2a26162984d7740f81193e8238e61\
+        f6b5b984d4d3dfa033c1bb7e4f0037febf406d91c0dccf32acf423cfa1e70710\
+        10d3f270121b493ce85054ef58bada42310138fe081adb04e2bd901f2f13458b\
+        3d6758158197107c14ebb193230cd1157380aa79cae1374a7c1e5bbcb80ee23e\
+        06ebfde206bfb0fcbc0edc4ebec309661bdd908d532eb0c6adc38b7ca7331dce\
+        8dfce39ab71e7c32d318d136b6100671a1ae6a6600e3899f31f0eed19e3417d1\
+        34b90c9058f8632c798d4490da4987307cba922d61c39805d072b589bd52fdf1\
+        e86215c2d54e6670e07383a27bbffb5addf47d66aa85a0c6f9f32e59d85a44dd\
+        5d3b22dc2be80919b490437ae4f36a0ae55edf1d0b5cb4e9a3ecabee93dfc6e3\
+        8d209d0fa6536d27a5d6fbb17641cde27525d61093f1b28072d111b2b4ae5f89\
+        d5974ee12e5cf7d5da4d6a31123041f33e61407e76cffcdcfd7e19ba58cf4b53\
+        6f4c4938ae79324dc402894b44faf8afbab35282ab659d13c93f70412e85cb19\
+        9a37ddec600545473cfb5a05e08d0b209973b2172b4d21fb69745a262ccde96b\
+        a18b2faa745b6fe189cf772a9f84cbfc";
+
+    fn unhex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn parse(datagram: &[u8]) -> Option<QuicInfo> {
+        let mut info = PacketInfo::new(FrameMeta::default());
+        parse_quic(datagram, &mut info).ok()?;
+        info.quic
+    }
+
+    fn assert_client_initial(datagram: &str, version: u32) {
+        let quic = parse(&unhex(datagram)).unwrap();
+        assert_eq!(quic.packets.len(), 1);
+        let packet = &quic.packets[0];
+        assert_eq!(packet.packet_type, QuicPacketType::Initial);
+        assert_eq!(packet.version, Some(version));
+        assert_eq!(packet.dcid, Some(unhex("8394c8f03e515708")));
+        assert!(packet.decrypted);
+        assert_eq!(packet.crypto, vec![(0, unhex(CLIENT_HELLO))]);
+
+        let hello = parse_client_hello(&packet.crypto[0].1[4..], TlsTransport::Quic).unwrap();
+        assert_eq!(hello.sni.as_deref(), Some("example.com"));
+        assert_eq!(hello.alpn, vec!["alpn".to_string()]);
+    }
+
+    #[test]
+    fn decrypts_rfc9001_client_initial() {
+        assert_client_initial(V1_CLIENT_INITIAL, VERSION_1);
+    }
+
+    #[test]
+    fn decrypts_rfc9369_client_initial() {
+        assert_client_initial(V2_CLIENT_INITIAL, VERSION_2);
+    }
+
+    #[test]
+    fn unknown_version_stops_after_connection_ids() {
+        // GREASE version 0x1a2a3a4a, 2-byte DCID, empty SCID, then bytes of unknown layout
+        let quic = parse(&[0xc0, 0x1a, 0x2a, 0x3a, 0x4a, 2, 0xab, 0xcd, 0, 0xff, 0xff, 0xff]).unwrap();
+        assert_eq!(quic.packets.len(), 1);
+        assert_eq!(quic.packets[0].packet_type, QuicPacketType::UnknownVersion);
+        assert_eq!(quic.packets[0].dcid, Some(vec![0xab, 0xcd]));
+    }
+
+    #[test]
+    fn undecodable_coalesced_packet_keeps_earlier_ones() {
+        let mut datagram = unhex(V1_CLIENT_INITIAL);
+        // A truncated long header after the Initial
+        datagram.extend_from_slice(&[0xc0, 0, 0, 0, 1, 8]);
+        let quic = parse(&datagram).unwrap();
+        assert_eq!(quic.packets.len(), 1);
+        assert!(quic.packets[0].decrypted);
+    }
+}
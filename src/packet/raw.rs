@@ -0,0 +1,16 @@
+use super::PacketInfo;
+use crate::packet::{ipv4, ipv6};
+
+/// Parses a raw IP frame with no link-layer header (DLT_RAW, tun devices).
+/// The IP version is taken from the first nibble of the packet.
+pub fn parse_raw_ip(data: &[u8], info: &mut PacketInfo) -> Result<(), &'static str> {
+    if data.is_empty() {
+        return Err("Empty raw IP packet");
+    }
+
+    match data[0] >> 4 {
+        4 => ipv4::parse_ipv4(data, info),
+        6 => ipv6::parse_ipv6(data, info),
+        _ => Err("Unknown IP version in raw packet"),
+    }
+}
@@ -0,0 +1,50 @@
+use super::PacketInfo;
+use crate::packet::ethernet::{array_from_slice, parse_ethertype};
+
+/// Length of the Linux cooked capture v1 header (DLT_LINUX_SLL)
+const SLL_HEADER_LEN: usize = 16;
+
+/// Length of the Linux cooked capture v2 header (DLT_LINUX_SLL2)
+const SLL2_HEADER_LEN: usize = 20;
+
+/// Parses a Linux "cooked" capture v1 frame, as produced when capturing on the `any` device.
+///
+/// Layout: packet type (2), ARPHRD type (2), address length (2), address (8), protocol (2).
+pub fn parse_sll(data: &[u8], info: &mut PacketInfo) -> Result<(), &'static str> {
+    if data.len() < SLL_HEADER_LEN {
+        return Err("Packet too short for Linux SLL header");
+    }
+
+    let addr_len = u16::from_be_bytes([data[4], data[5]]) as usize;
+    let protocol = u16::from_be_bytes([data[14], data[15]]);
+
+    info.src_mac = link_address(&data[6..14], addr_len);
+
+    parse_ethertype(protocol, &data[SLL_HEADER_LEN..], info)
+}
+
+/// Parses a Linux "cooked" capture v2 frame.
+///
+/// Layout: protocol (2), reserved (2), interface index (4), ARPHRD type (2),
+/// packet type (1), address length (1), address (8).
+pub fn parse_sll2(data: &[u8], info: &mut PacketInfo) -> Result<(), &'static str> {
+    if data.len() < SLL2_HEADER_LEN {
+        return Err("Packet too short for Linux SLL2 header");
+    }
+
+    let protocol = u16::from_be_bytes([data[0], data[1]]);
+    let addr_len = data[11] as usize;
+
+    info.src_mac = link_address(&data[12..20], addr_len);
+
+    parse_ethertype(protocol, &data[SLL2_HEADER_LEN..], info)
+}
+
+/// Returns the sender's link-layer address if it is a 6-byte MAC.
+fn link_address(field: &[u8], addr_len: usize) -> Option<[u8; 6]> {
+    if addr_len == 6 {
+        array_from_slice(&field[..6])
+    } else {
+        None
+    }
+}
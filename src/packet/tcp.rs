@@ -1,6 +1,11 @@
+use std::fmt;
+
 /// Represents parsed TCP flags.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct TcpFlags {
+    pub ns: bool,
+    pub cwr: bool,
+    pub ece: bool,
     pub urg: bool,
     pub ack: bool,
     pub psh: bool,
@@ -10,21 +15,28 @@ pub struct TcpFlags {
 }
 
 impl TcpFlags {
-    pub fn from_byte(byte: u8) -> Self {
+    /// Decodes the nine flag bits that follow the data offset (NS is the most significant).
+    pub fn from_bits(bits: u16) -> Self {
         Self {
-            urg: byte & 0b0010_0000 != 0,
-            ack: byte & 0b0001_0000 != 0,
-            psh: byte & 0b0000_1000 != 0,
-            rst: byte & 0b0000_0100 != 0,
-            syn: byte & 0b0000_0010 != 0,
-            fin: byte & 0b0000_0001 != 0,
+            ns: bits & 0b1_0000_0000 != 0,
+            cwr: bits & 0b0_1000_0000 != 0,
+            ece: bits & 0b0_0100_0000 != 0,
+            urg: bits & 0b0_0010_0000 != 0,
+            ack: bits & 0b0_0001_0000 != 0,
+            psh: bits & 0b0_0000_1000 != 0,
+            rst: bits & 0b0_0000_0100 != 0,
+            syn: bits & 0b0_0000_0010 != 0,
+            fin: bits & 0b0_0000_0001 != 0,
         }
     }
 }
 
-impl std::fmt::Display for TcpFlags {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl fmt::Display for TcpFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut flags = vec![];
+        if self.ns { flags.push("NS"); }
+        if self.cwr { flags.push("CWR"); }
+        if self.ece { flags.push("ECE"); }
         if self.urg { flags.push("URG"); }
         if self.ack { flags.push("ACK"); }
         if self.psh { flags.push("PSH"); }
@@ -36,22 +48,186 @@ impl std::fmt::Display for TcpFlags {
     }
 }
 
+/// A decoded TCP option. End of Option List and No-Operation padding are not kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TcpOption {
+    Mss(u16),
+    WindowScale(u8),
+    SackPermitted,
+    /// Left and right edges of each selectively acknowledged block
+    Sack(Vec<(u32, u32)>),
+    Timestamps { value: u32, echo_reply: u32 },
+    Other { kind: u8, length: u8 },
+}
+
+impl fmt::Display for TcpOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TcpOption::Mss(mss) => write!(f, "MSS={}", mss),
+            TcpOption::WindowScale(shift) => write!(f, "WS={}", 1u32 << (*shift).min(14)),
+            TcpOption::SackPermitted => write!(f, "SACK_PERM"),
+            TcpOption::Sack(blocks) => {
+                let blocks: Vec<String> = blocks
+                    .iter()
+                    .map(|(left, right)| format!("{}-{}", left, right))
+                    .collect();
+                write!(f, "SACK={}", blocks.join(","))
+            }
+            TcpOption::Timestamps { value, echo_reply } => {
+                write!(f, "TSval={} TSecr={}", value, echo_reply)
+            }
+            TcpOption::Other { kind, length } => write!(f, "Option {} ({} bytes)", kind, length),
+        }
+    }
+}
+
+/// How a segment's payload relates to the bytes already seen on its stream.
+/// Filled in by the stream reassembler in the capture loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentKind {
+    /// Starts exactly at the next expected byte
+    InOrder,
+    /// Starts beyond the next expected byte, leaving a gap
+    OutOfOrder,
+    /// Carries only (or partly) bytes that were already delivered
+    Retransmission,
+}
+
+/// Decoded TCP header fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TcpHeader {
+    pub src_port: u16,
+    pub dst_port: u16,
+    pub seq: u32,
+    pub ack: u32,
+    /// Header length in bytes, including options
+    pub data_offset: usize,
+    pub flags: TcpFlags,
+    /// Advertised window, before any window scaling
+    pub window: u16,
+    pub checksum: u16,
+    pub urgent_pointer: u16,
+    pub options: Vec<TcpOption>,
+    /// Number of payload bytes following the header
+    pub payload_len: usize,
+    /// Capture-wide stream index, set by the stream reassembler
+    pub stream_id: Option<u64>,
+    /// Set by the stream reassembler for segments that carry payload
+    pub segment: Option<SegmentKind>,
+}
+
+impl TcpHeader {
+    /// Wireshark-style one-liner, e.g. `[SYN|ACK] seq=1 ack=2 win=64240 len=0 MSS=1460`.
+    pub fn summary(&self) -> String {
+        let mut summary = match self.segment {
+            Some(SegmentKind::Retransmission) => "[TCP Retransmission] ".to_string(),
+            Some(SegmentKind::OutOfOrder) => "[TCP Out-Of-Order] ".to_string(),
+            _ => String::new(),
+        };
+        summary.push_str(&format!(
+            "[{}] seq={} ack={} win={} len={}",
+            self.flags, self.seq, self.ack, self.window, self.payload_len
+        ));
+        for option in &self.options {
+            summary.push(' ');
+            summary.push_str(&option.to_string());
+        }
+        summary
+    }
+}
+
 /// Parses the TCP header from the given byte slice.
 ///
 /// # Arguments
-/// * `header` - A slice containing at least the first 20 bytes of a TCP segment
+/// * `segment` - The whole TCP segment (from the IP payload)
 ///
 /// # Returns
-/// * A tuple: (source port, destination port, TCP flags) on success
+/// * A tuple: (decoded header, payload slice) on success
 /// * An error message on failure
-pub fn parse_tcp_header(header: &[u8]) -> Result<(u16, u16, TcpFlags), &'static str> {
-    if header.len() < 20 {
+pub fn parse_tcp_header(segment: &[u8]) -> Result<(TcpHeader, &[u8]), &'static str> {
+    if segment.len() < 20 {
         return Err("TCP header too short");
     }
 
-    let src_port = u16::from_be_bytes([header[0], header[1]]);
-    let dst_port = u16::from_be_bytes([header[2], header[3]]);
-    let flags = TcpFlags::from_byte(header[13]); // TCP flags are at byte offset 13
+    let data_offset = (segment[12] >> 4) as usize * 4;
+    if data_offset < 20 {
+        return Err("TCP data offset below minimum");
+    }
+    if data_offset > segment.len() {
+        return Err("TCP data offset exceeds segment length");
+    }
+
+    let payload = &segment[data_offset..];
+    // The NS flag is the low bit of the data offset byte
+    let flag_bits = u16::from_be_bytes([segment[12] & 0x01, segment[13]]);
+
+    let header = TcpHeader {
+        src_port: u16::from_be_bytes([segment[0], segment[1]]),
+        dst_port: u16::from_be_bytes([segment[2], segment[3]]),
+        seq: u32::from_be_bytes([segment[4], segment[5], segment[6], segment[7]]),
+        ack: u32::from_be_bytes([segment[8], segment[9], segment[10], segment[11]]),
+        data_offset,
+        flags: TcpFlags::from_bits(flag_bits),
+        window: u16::from_be_bytes([segment[14], segment[15]]),
+        checksum: u16::from_be_bytes([segment[16], segment[17]]),
+        urgent_pointer: u16::from_be_bytes([segment[18], segment[19]]),
+        options: parse_options(&segment[20..data_offset]),
+        payload_len: payload.len(),
+        stream_id: None,
+        segment: None,
+    };
+
+    Ok((header, payload))
+}
+
+/// Decodes the option bytes between the fixed header and the data offset.
+/// Malformed options end parsing rather than failing the segment.
+fn parse_options(mut data: &[u8]) -> Vec<TcpOption> {
+    let mut options = Vec::new();
+
+    while let Some(&kind) = data.first() {
+        match kind {
+            0 => break,              // End of Option List
+            1 => {                   // No-Operation
+                data = &data[1..];
+                continue;
+            }
+            _ => {}
+        }
+
+        let Some(&length) = data.get(1) else {
+            break;
+        };
+        if length < 2 || length as usize > data.len() {
+            break;
+        }
+        let value = &data[2..length as usize];
+
+        let option = match (kind, value.len()) {
+            (2, 2) => TcpOption::Mss(u16::from_be_bytes([value[0], value[1]])),
+            (3, 1) => TcpOption::WindowScale(value[0]),
+            (4, 0) => TcpOption::SackPermitted,
+            (5, len) if len % 8 == 0 => TcpOption::Sack(
+                value
+                    .chunks_exact(8)
+                    .map(|block| {
+                        (
+                            u32::from_be_bytes([block[0], block[1], block[2], block[3]]),
+                            u32::from_be_bytes([block[4], block[5], block[6], block[7]]),
+                        )
+                    })
+                    .collect(),
+            ),
+            (8, 8) => TcpOption::Timestamps {
+                value: u32::from_be_bytes([value[0], value[1], value[2], value[3]]),
+                echo_reply: u32::from_be_bytes([value[4], value[5], value[6], value[7]]),
+            },
+            _ => TcpOption::Other { kind, length },
+        };
+
+        options.push(option);
+        data = &data[length as usize..];
+    }
 
-    Ok((src_port, dst_port, flags))
+    options
 }
@@ -0,0 +1,614 @@
+use md5::Md5;
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+use super::x509::{format_unix_time, parse_certificate, Certificate};
+
+/// Transport a ClientHello arrived on; the JA4 fingerprint starts with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TlsTransport {
+    Tcp,
+    Quic,
+}
+
+/// Fields of a ClientHello used for identification and fingerprinting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientHello {
+    pub legacy_version: u16,
+    pub cipher_suites: Vec<u16>,
+    /// Extension types in the order sent
+    pub extensions: Vec<u16>,
+    pub sni: Option<String>,
+    pub alpn: Vec<String>,
+    pub supported_groups: Vec<u16>,
+    pub ec_point_formats: Vec<u8>,
+    pub signature_algorithms: Vec<u16>,
+    pub supported_versions: Vec<u16>,
+    pub ja3: String,
+    pub ja3_hash: String,
+    pub ja4: String,
+}
+
+/// Fields of a ServerHello.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerHello {
+    pub legacy_version: u16,
+    pub cipher_suite: u16,
+    pub extensions: Vec<u16>,
+    pub alpn: Option<String>,
+    /// Version chosen through the supported_versions extension (TLS 1.3)
+    pub selected_version: Option<u16>,
+    /// A TLS 1.3 HelloRetryRequest, which shares the ServerHello format; the client
+    /// answers it with a second, still cleartext, ClientHello
+    pub hello_retry_request: bool,
+    pub ja3s: String,
+    pub ja3s_hash: String,
+}
+
+impl ServerHello {
+    /// The negotiated protocol version.
+    pub fn version(&self) -> u16 {
+        self.selected_version.unwrap_or(self.legacy_version)
+    }
+
+    pub fn message_name(&self) -> &'static str {
+        if self.hello_retry_request {
+            "Hello Retry Request"
+        } else {
+            "Server Hello"
+        }
+    }
+}
+
+/// A decoded TLS handshake message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TlsInfo {
+    ClientHello(ClientHello),
+    ServerHello(ServerHello),
+    /// Certificate chain sent by the server, leaf first
+    Certificate(Vec<Certificate>),
+}
+
+impl TlsInfo {
+    /// One-line description, e.g. `Client Hello SNI example.com ALPN h2,http/1.1 JA4 t13d1516h2_...`.
+    pub fn summary(&self) -> String {
+        match self {
+            TlsInfo::ClientHello(hello) => {
+                let mut summary = String::from("Client Hello");
+                if let Some(sni) = &hello.sni {
+                    summary.push_str(&format!(" SNI {}", sni));
+                }
+                if !hello.alpn.is_empty() {
+                    summary.push_str(&format!(" ALPN {}", hello.alpn.join(",")));
+                }
+                summary.push_str(&format!(" JA4 {}", hello.ja4));
+                summary
+            }
+            TlsInfo::ServerHello(hello) => {
+                let mut summary = format!(
+                    "{} {} {}",
+                    hello.message_name(),
+                    version_name(hello.version()),
+                    cipher_suite_name(hello.cipher_suite)
+                );
+                if let Some(alpn) = &hello.alpn {
+                    summary.push_str(&format!(" ALPN {}", alpn));
+                }
+                summary
+            }
+            TlsInfo::Certificate(chain) => match chain.first() {
+                Some(leaf) => format!("Certificate CN {} ({} in chain)", leaf.common_name(), chain.len()),
+                None => "Certificate (empty)".into(),
+            },
+        }
+    }
+
+    /// Multi-line description with the full lists and fingerprints, for tooltips.
+    pub fn details(&self) -> String {
+        let hex_list = |values: &[u16]| values.iter().map(|v| format!("0x{:04x}", v)).collect::<Vec<_>>().join(" ");
+        match self {
+            TlsInfo::ClientHello(hello) => {
+                let versions: Vec<_> = hello.supported_versions.iter().map(|&v| version_name(v)).collect();
+                let ciphers: Vec<_> = hello.cipher_suites.iter().map(|&c| cipher_suite_name(c)).collect();
+                let groups: Vec<_> = hello.supported_groups.iter().map(|&g| group_name(g)).collect();
+                [
+                    format!("Client Hello, record version {}", version_name(hello.legacy_version)),
+                    format!("SNI: {}", hello.sni.as_deref().unwrap_or("-")),
+                    format!("ALPN: {}", hello.alpn.join(", ")),
+                    format!("Supported versions: {}", versions.join(", ")),
+                    format!("Cipher suites: {}", ciphers.join(", ")),
+                    format!("Supported groups: {}", groups.join(", ")),
+                    format!("Signature algorithms: {}", hex_list(&hello.signature_algorithms)),
+                    format!("Extensions: {}", hex_list(&hello.extensions)),
+                    format!("JA3: {} ({})", hello.ja3_hash, hello.ja3),
+                    format!("JA4: {}", hello.ja4),
+                ]
+                .join("\n")
+            }
+            TlsInfo::ServerHello(hello) => [
+                format!("{}, {}", hello.message_name(), version_name(hello.version())),
+                format!("Cipher suite: {}", cipher_suite_name(hello.cipher_suite)),
+                format!("ALPN: {}", hello.alpn.as_deref().unwrap_or("-")),
+                format!("Extensions: {}", hex_list(&hello.extensions)),
+                format!("JA3S: {} ({})", hello.ja3s_hash, hello.ja3s),
+            ]
+            .join("\n"),
+            TlsInfo::Certificate(chain) => {
+                let mut lines = vec![format!("Certificate chain ({})", chain.len())];
+                for (position, cert) in chain.iter().enumerate() {
+                    lines.push(format!("[{}] {}", position, cert.subject));
+                    lines.push(format!("    Issuer: {}", cert.issuer));
+                    lines.push(format!(
+                        "    Valid: {} to {} UTC",
+                        format_unix_time(cert.not_before),
+                        format_unix_time(cert.not_after)
+                    ));
+                    if !cert.subject_alt_names.is_empty() {
+                        lines.push(format!("    SAN: {}", cert.subject_alt_names.join(", ")));
+                    }
+                    lines.push(format!("    Key: {}{}", cert.key_type, if cert.self_signed { ", self-signed" } else { "" }));
+                }
+                lines.join("\n")
+            }
+        }
+    }
+}
+
+impl fmt::Display for TlsInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.summary())
+    }
+}
+
+/// GREASE values (RFC 8701) are random placeholders and left out of fingerprints.
+fn is_grease(value: u16) -> bool {
+    value & 0x0f0f == 0x0a0a && value >> 8 == value & 0xff
+}
+
+pub fn version_name(version: u16) -> String {
+    match version {
+        0x0300 => "SSL 3.0".into(),
+        0x0301 => "TLS 1.0".into(),
+        0x0302 => "TLS 1.1".into(),
+        0x0303 => "TLS 1.2".into(),
+        0x0304 => "TLS 1.3".into(),
+        v if is_grease(v) => "GREASE".into(),
+        v => format!("0x{:04x}", v),
+    }
+}
+
+pub fn cipher_suite_name(suite: u16) -> String {
+    let name = match suite {
+        0x002f => "TLS_RSA_WITH_AES_128_CBC_SHA",
+        0x0035 => "TLS_RSA_WITH_AES_256_CBC_SHA",
+        0x009c => "TLS_RSA_WITH_AES_128_GCM_SHA256",
+        0x009d => "TLS_RSA_WITH_AES_256_GCM_SHA384",
+        0x1301 => "TLS_AES_128_GCM_SHA256",
+        0x1302 => "TLS_AES_256_GCM_SHA384",
+        0x1303 => "TLS_CHACHA20_POLY1305_SHA256",
+        0xc009 => "TLS_ECDHE_ECDSA_WITH_AES_128_CBC_SHA",
+        0xc00a => "TLS_ECDHE_ECDSA_WITH_AES_256_CBC_SHA",
+        0xc013 => "TLS_ECDHE_RSA_WITH_AES_128_CBC_SHA",
+        0xc014 => "TLS_ECDHE_RSA_WITH_AES_256_CBC_SHA",
+        0xc02b => "TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256",
+        0xc02c => "TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384",
+        0xc02f => "TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256",
+        0xc030 => "TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384",
+        0xcca8 => "TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256",
+        0xcca9 => "TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256",
+        0x00ff => "TLS_EMPTY_RENEGOTIATION_INFO_SCSV",
+        s if is_grease(s) => "GREASE",
+        s => return format!("0x{:04x}", s),
+    };
+    name.into()
+}
+
+pub fn group_name(group: u16) -> String {
+    let name = match group {
+        0x0017 => "secp256r1",
+        0x0018 => "secp384r1",
+        0x0019 => "secp521r1",
+        0x001d => "x25519",
+        0x001e => "x448",
+        0x0100 => "ffdhe2048",
+        0x0101 => "ffdhe3072",
+        0x11ec => "X25519MLKEM768",
+        0x6399 => "X25519Kyber768Draft00",
+        g if is_grease(g) => "GREASE",
+        g => return format!("0x{:04x}", g),
+    };
+    name.into()
+}
+
+/// Parses a ClientHello handshake body (after the 4-byte handshake header).
+pub fn parse_client_hello(body: &[u8], transport: TlsTransport) -> Result<ClientHello, &'static str> {
+    let mut reader = Reader::new(body);
+    let legacy_version = reader.u16().ok_or("ClientHello too short")?;
+    reader.skip(32).ok_or("ClientHello random truncated")?;
+    reader.vec8().ok_or("ClientHello session ID truncated")?;
+    let cipher_suites = u16_list(reader.vec16().ok_or("ClientHello cipher suites truncated")?);
+    reader.vec8().ok_or("ClientHello compression methods truncated")?;
+
+    let mut hello = ClientHello {
+        legacy_version,
+        cipher_suites,
+        extensions: Vec::new(),
+        sni: None,
+        alpn: Vec::new(),
+        supported_groups: Vec::new(),
+        ec_point_formats: Vec::new(),
+        signature_algorithms: Vec::new(),
+        supported_versions: Vec::new(),
+        ja3: String::new(),
+        ja3_hash: String::new(),
+        ja4: String::new(),
+    };
+
+    // Extensions are optional before TLS 1.2
+    if let Some(extensions) = reader.vec16() {
+        for (kind, data) in extensions_iter(extensions) {
+            hello.extensions.push(kind);
+            let mut ext = Reader::new(data);
+            match kind {
+                0 => hello.sni = parse_sni(data),
+                10 => hello.supported_groups = ext.vec16().map(u16_list).unwrap_or_default(),
+                11 => hello.ec_point_formats = ext.vec8().map(<[u8]>::to_vec).unwrap_or_default(),
+                13 => hello.signature_algorithms = ext.vec16().map(u16_list).unwrap_or_default(),
+                16 => hello.alpn = ext.vec16().map(parse_alpn_list).unwrap_or_default(),
+                43 => hello.supported_versions = ext.vec8().map(u16_list).unwrap_or_default(),
+                _ => {}
+            }
+        }
+    }
+
+    hello.ja3 = ja3_string(&hello);
+    hello.ja3_hash = md5_hex(&hello.ja3);
+    hello.ja4 = ja4(&hello, transport);
+    Ok(hello)
+}
+
+/// SHA-256 of "HelloRetryRequest", the fixed random of a HelloRetryRequest (RFC 8446 section 4.1.3)
+const HELLO_RETRY_REQUEST_RANDOM: [u8; 32] = [
+    0xcf, 0x21, 0xad, 0x74, 0xe5, 0x9a, 0x61, 0x11, 0xbe, 0x1d, 0x8c, 0x02, 0x1e, 0x65, 0xb8, 0x91, 0xc2, 0xa2, 0x11,
+    0x16, 0x7a, 0xbb, 0x8c, 0x5e, 0x07, 0x9e, 0x09, 0xe2, 0xc8, 0xa8, 0x33, 0x9c,
+];
+
+/// Parses a ServerHello handshake body (after the 4-byte handshake header).
+pub fn parse_server_hello(body: &[u8]) -> Result<ServerHello, &'static str> {
+    let mut reader = Reader::new(body);
+    let legacy_version = reader.u16().ok_or("ServerHello too short")?;
+    let random = reader.take(32).ok_or("ServerHello random truncated")?;
+    reader.vec8().ok_or("ServerHello session ID truncated")?;
+    let cipher_suite = reader.u16().ok_or("ServerHello cipher suite truncated")?;
+    reader.skip(1).ok_or("ServerHello compression method truncated")?;
+
+    let mut hello = ServerHello {
+        legacy_version,
+        cipher_suite,
+        extensions: Vec::new(),
+        alpn: None,
+        selected_version: None,
+        hello_retry_request: random == HELLO_RETRY_REQUEST_RANDOM,
+        ja3s: String::new(),
+        ja3s_hash: String::new(),
+    };
+
+    if let Some(extensions) = reader.vec16() {
+        for (kind, data) in extensions_iter(extensions) {
+            hello.extensions.push(kind);
+            let mut ext = Reader::new(data);
+            match kind {
+                16 => hello.alpn = ext.vec16().map(parse_alpn_list).and_then(|list| list.into_iter().next()),
+                43 => hello.selected_version = ext.u16(),
+                _ => {}
+            }
+        }
+    }
+
+    let extensions = join_decimal(hello.extensions.iter().copied().filter(|&e| !is_grease(e)));
+    hello.ja3s = format!("{},{},{}", legacy_version, cipher_suite, extensions);
+    hello.ja3s_hash = md5_hex(&hello.ja3s);
+    Ok(hello)
+}
+
+/// Parses a TLS 1.2 Certificate handshake body: a list of DER certificates, leaf first.
+/// Entries that are not valid X.509 are skipped.
+pub fn parse_certificate_message(body: &[u8]) -> Result<Vec<Certificate>, &'static str> {
+    let mut list = Reader::new(Reader::new(body).vec24().ok_or("Certificate list truncated")?);
+    let mut chain = Vec::new();
+    while !list.rest().is_empty() {
+        let der = list.vec24().ok_or("Certificate entry truncated")?;
+        if let Ok(certificate) = parse_certificate(der) {
+            chain.push(certificate);
+        }
+    }
+    Ok(chain)
+}
+
+/// JA3: version,ciphers,extensions,groups,point formats as dash-separated decimals.
+fn ja3_string(hello: &ClientHello) -> String {
+    format!(
+        "{},{},{},{},{}",
+        hello.legacy_version,
+        join_decimal(hello.cipher_suites.iter().copied().filter(|&c| !is_grease(c))),
+        join_decimal(hello.extensions.iter().copied().filter(|&e| !is_grease(e))),
+        join_decimal(hello.supported_groups.iter().copied().filter(|&g| !is_grease(g))),
+        join_decimal(hello.ec_point_formats.iter().map(|&f| f as u16)),
+    )
+}
+
+/// JA4 (FoxIO): `<proto><version><sni><#ciphers><#extensions><alpn>_<cipher hash>_<extension hash>`.
+fn ja4(hello: &ClientHello, transport: TlsTransport) -> String {
+    let protocol = match transport {
+        TlsTransport::Tcp => 't',
+        TlsTransport::Quic => 'q',
+    };
+    let version = hello
+        .supported_versions
+        .iter()
+        .copied()
+        .filter(|&v| !is_grease(v))
+        .max()
+        .unwrap_or(hello.legacy_version);
+    let version = match version {
+        0x0304 => "13",
+        0x0303 => "12",
+        0x0302 => "11",
+        0x0301 => "10",
+        0x0300 => "s3",
+        0x0002 => "s2",
+        0xfeff => "d1",
+        0xfefd => "d2",
+        0xfefc => "d3",
+        _ => "00",
+    };
+    let sni = if hello.extensions.contains(&0) { 'd' } else { 'i' };
+
+    let ciphers: Vec<u16> = hello.cipher_suites.iter().copied().filter(|&c| !is_grease(c)).collect();
+    let extensions: Vec<u16> = hello.extensions.iter().copied().filter(|&e| !is_grease(e)).collect();
+
+    let alpn = match hello.alpn.first().map(String::as_bytes) {
+        Some([first, .., last]) if first.is_ascii_alphanumeric() && last.is_ascii_alphanumeric() => {
+            format!("{}{}", *first as char, *last as char)
+        }
+        Some([only]) if only.is_ascii_alphanumeric() => format!("{}{}", *only as char, *only as char),
+        Some(value @ [_, ..]) => {
+            let hex: String = value.iter().map(|b| format!("{:02x}", b)).collect();
+            format!("{}{}", &hex[..1], &hex[hex.len() - 1..])
+        }
+        _ => "00".into(),
+    };
+
+    let mut sorted_ciphers = ciphers.clone();
+    sorted_ciphers.sort_unstable();
+    // SNI and ALPN are already reflected in the first section
+    let mut sorted_extensions: Vec<u16> = extensions.iter().copied().filter(|&e| e != 0 && e != 16).collect();
+    sorted_extensions.sort_unstable();
+
+    let mut extension_input = join_hex(&sorted_extensions);
+    if !hello.signature_algorithms.is_empty() {
+        extension_input.push('_');
+        extension_input.push_str(&join_hex(&hello.signature_algorithms));
+    }
+
+    format!(
+        "{}{}{}{:02}{:02}{}_{}_{}",
+        protocol,
+        version,
+        sni,
+        ciphers.len().min(99),
+        extensions.len().min(99),
+        alpn,
+        truncated_sha256(&join_hex(&sorted_ciphers)),
+        truncated_sha256(if sorted_extensions.is_empty() { "" } else { &extension_input }),
+    )
+}
+
+fn truncated_sha256(input: &str) -> String {
+    if input.is_empty() {
+        return "000000000000".into();
+    }
+    let digest = Sha256::digest(input.as_bytes());
+    digest.iter().take(6).map(|b| format!("{:02x}", b)).collect()
+}
+
+fn md5_hex(input: &str) -> String {
+    Md5::digest(input.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn join_decimal(values: impl Iterator<Item = u16>) -> String {
+    values.map(|v| v.to_string()).collect::<Vec<_>>().join("-")
+}
+
+fn join_hex(values: &[u16]) -> String {
+    values.iter().map(|v| format!("{:04x}", v)).collect::<Vec<_>>().join(",")
+}
+
+fn u16_list(data: &[u8]) -> Vec<u16> {
+    data.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])).collect()
+}
+
+fn extensions_iter(mut data: &[u8]) -> impl Iterator<Item = (u16, &[u8])> {
+    std::iter::from_fn(move || {
+        let mut reader = Reader::new(data);
+        let kind = reader.u16()?;
+        let value = reader.vec16()?;
+        data = reader.rest();
+        Some((kind, value))
+    })
+}
+
+/// First host_name entry of a server_name extension.
+fn parse_sni(data: &[u8]) -> Option<String> {
+    let mut list = Reader::new(Reader::new(data).vec16()?);
+    while let Some(name_type) = list.u8() {
+        let name = list.vec16()?;
+        if name_type == 0 {
+            return Some(String::from_utf8_lossy(name).into_owned());
+        }
+    }
+    None
+}
+
+fn parse_alpn_list(data: &[u8]) -> Vec<String> {
+    let mut reader = Reader::new(data);
+    std::iter::from_fn(|| reader.vec8().map(|protocol| String::from_utf8_lossy(protocol).into_owned())).collect()
+}
+
+/// Cursor over big-endian TLS wire data.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Reader { data }
+    }
+
+    fn rest(&self) -> &'a [u8] {
+        self.data
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.data.len() < len {
+            return None;
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Some(head)
+    }
+
+    fn skip(&mut self, len: usize) -> Option<()> {
+        self.take(len).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take(2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn u24(&mut self) -> Option<usize> {
+        self.take(3).map(|b| (b[0] as usize) << 16 | (b[1] as usize) << 8 | b[2] as usize)
+    }
+
+    /// A vector with a one-byte length prefix.
+    fn vec8(&mut self) -> Option<&'a [u8]> {
+        let len = self.u8()? as usize;
+        self.take(len)
+    }
+
+    /// A vector with a two-byte length prefix.
+    fn vec16(&mut self) -> Option<&'a [u8]> {
+        let len = self.u16()? as usize;
+        self.take(len)
+    }
+
+    /// A vector with a three-byte length prefix.
+    fn vec24(&mut self) -> Option<&'a [u8]> {
+        let len = self.u24()?;
+        self.take(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vec16(data: &[u8]) -> Vec<u8> {
+        let mut out = (data.len() as u16).to_be_bytes().to_vec();
+        out.extend_from_slice(data);
+        out
+    }
+
+    fn extension(kind: u16, data: &[u8]) -> Vec<u8> {
+        let mut out = kind.to_be_bytes().to_vec();
+        out.extend(vec16(data));
+        out
+    }
+
+    fn u16s(values: &[u16]) -> Vec<u8> {
+        values.iter().flat_map(|value| value.to_be_bytes()).collect()
+    }
+
+    /// ClientHello body with an empty session ID and null compression.
+    fn client_hello(version: u16, ciphers: &[u16], extensions: &[Vec<u8>]) -> Vec<u8> {
+        let mut body = version.to_be_bytes().to_vec();
+        body.extend([0; 32]);
+        body.push(0);
+        body.extend(vec16(&u16s(ciphers)));
+        body.extend([1, 0]);
+        body.extend(vec16(&extensions.concat()));
+        body
+    }
+
+    fn sni(name: &str) -> Vec<u8> {
+        let mut entry = vec![0];
+        entry.extend(vec16(name.as_bytes()));
+        extension(0, &vec16(&entry))
+    }
+
+    /// Example from the JA3 README, with GREASE values added that must be ignored
+    #[test]
+    fn ja3_matches_published_example() {
+        let body = client_hello(
+            0x0301,
+            &[0x0a0a, 47, 53, 5, 10, 49161, 49162, 49171, 49172, 50, 56, 19, 4],
+            &[
+                extension(0x2a2a, &[]),
+                sni("example.com"),
+                extension(10, &vec16(&u16s(&[0x3a3a, 23, 24, 25]))),
+                extension(11, &[1, 0]),
+            ],
+        );
+        let hello = parse_client_hello(&body, TlsTransport::Tcp).unwrap();
+        assert_eq!(hello.ja3, "769,47-53-5-10-49161-49162-49171-49172-50-56-19-4,0-10-11,23-24-25,0");
+        assert_eq!(hello.ja3_hash, "ada70206e40642a3e4461f35503241d5");
+    }
+
+    /// Chrome example from the JA4 specification, including its GREASE cipher, extension, group and version
+    #[test]
+    fn ja4_matches_published_example() {
+        let ciphers = [
+            0x0a0a, 0x1301, 0x1302, 0x1303, 0xc02b, 0xc02f, 0xc02c, 0xc030, 0xcca9, 0xcca8, 0xc013, 0xc014, 0x009c,
+            0x009d, 0x002f, 0x0035,
+        ];
+        let mut extensions = vec![extension(0x1a1a, &[]), sni("example.com")];
+        for kind in [
+            0x0017, 0xff01, 0x000a, 0x000b, 0x0023, 0x0010, 0x0005, 0x000d, 0x0012, 0x0033, 0x002d, 0x002b, 0x001b,
+            0x4469, 0x0015,
+        ] {
+            let data = match kind {
+                0x000a => vec16(&u16s(&[0x0a0a, 0x001d, 0x0017, 0x0018])),
+                0x000b => vec![1, 0],
+                0x0010 => vec16(b"\x02h2\x08http/1.1"),
+                0x000d => vec16(&u16s(&[0x0403, 0x0804, 0x0401, 0x0503, 0x0805, 0x0501, 0x0806, 0x0601])),
+                0x002b => vec![6, 0x2a, 0x2a, 3, 4, 3, 3],
+                _ => Vec::new(),
+            };
+            extensions.push(extension(kind, &data));
+        }
+
+        let hello = parse_client_hello(&client_hello(0x0303, &ciphers, &extensions), TlsTransport::Tcp).unwrap();
+        assert_eq!(hello.ja4, "t13d1516h2_8daaf6152771_e5627efa2ab1");
+        assert_eq!(hello.sni.as_deref(), Some("example.com"));
+        assert_eq!(hello.alpn, vec!["h2".to_string(), "http/1.1".to_string()]);
+    }
+
+    #[test]
+    fn recognizes_hello_retry_request() {
+        let server_hello = |random: [u8; 32]| {
+            let mut body = vec![3, 3];
+            body.extend(random);
+            body.extend([0, 0x13, 0x01, 0]);
+            body.extend(vec16(&extension(43, &[3, 4])));
+            parse_server_hello(&body).unwrap()
+        };
+        let retry = server_hello(HELLO_RETRY_REQUEST_RANDOM);
+        assert!(retry.hello_retry_request);
+        assert_eq!(retry.version(), 0x0304);
+        assert!(!server_hello([0; 32]).hello_retry_request);
+    }
+}
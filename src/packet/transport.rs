@@ -0,0 +1,32 @@
+use super::{PacketInfo, PacketType};
+use crate::packet::{icmp, icmpv6, tcp, udp};
+
+/// Dispatches an IP payload to the transport-layer parser for `protocol`.
+///
+/// The caller sets the network-layer `packet_type` beforehand; it is only
+/// replaced when the protocol is one we decode.
+pub fn parse_transport(protocol: u8, data: &[u8], info: &mut PacketInfo) -> Result<(), &'static str> {
+    match protocol {
+        6 => { // TCP
+            let (header, payload) = tcp::parse_tcp_header(data)?;
+            info.packet_type = PacketType::TCP;
+            info.src_port = Some(header.src_port);
+            info.dst_port = Some(header.dst_port);
+            info.tcp_flags = Some(header.flags);
+            info.payload = payload.to_vec();
+            info.tcp = Some(header);
+        }
+        17 => { // UDP
+            udp::parse_udp(data, info)?;
+        }
+        1 => { // ICMP
+            icmp::parse_icmp(data, info)?;
+        }
+        58 => { // ICMPv6
+            icmpv6::parse_icmpv6(data, info)?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
@@ -1,4 +1,8 @@
-use super::{dns::parse_dns_queries, PacketInfo, PacketType};
+use super::dns::{dns_protocol_for_ports, looks_like_dns, parse_dns_message, DnsProtocol};
+use super::dhcp::parse_dhcp;
+use super::dhcpv6::parse_dhcpv6;
+use super::quic::{looks_like_quic, parse_quic};
+use super::{PacketInfo, PacketType};
 
 /// Parses the UDP layer and updates the provided PacketInfo.
 ///
@@ -23,20 +27,44 @@ pub fn parse_udp(data: &[u8], info: &mut PacketInfo) -> Result<(), &'static str>
     info.dst_port = Some(dst_port);
 
     // Only parse if length is valid
+    if udp_len < 8 {
+        return Err("UDP length field too small");
+    }
     if udp_len as usize > data.len() {
         return Err("UDP length field exceeds packet size");
     }
 
     let payload = &data[8..udp_len as usize];
 
-    // DNS detection: check if port 53 is involved
-    if src_port == 53 || dst_port == 53 {
-        if let Ok(queries) = parse_dns_queries(payload) {
-            if !queries.is_empty() {
-                info.packet_type = PacketType::DNS;
-                info.dns_queries = Some(queries);
-            }
-        }
+    // BOOTP/DHCP: server port 67, client port 68
+    // Malformed or snaplen-truncated messages are kept as plain UDP
+    if matches!((src_port, dst_port), (67, 68) | (68, 67) | (67, 67)) {
+        let _ = parse_dhcp(payload, info);
+        return Ok(());
+    }
+
+    // DHCPv6: client port 546, server/relay port 547; malformed messages stay plain UDP
+    if matches!((src_port, dst_port), (546, 547) | (547, 546) | (547, 547)) {
+        let _ = parse_dhcpv6(payload, info);
+        return Ok(());
+    }
+
+    // QUIC (HTTP/3) on UDP 443; undecodable datagrams stay plain UDP
+    if (src_port == 443 || dst_port == 443) && looks_like_quic(payload) && parse_quic(payload, info).is_ok() {
+        return Ok(());
+    }
+
+    // DNS detection: known and configured ports first, then a strict heuristic for the rest
+    let dns = match dns_protocol_for_ports(src_port, dst_port) {
+        Some(protocol) => parse_dns_message(payload).ok().map(|message| (message, protocol)),
+        None => looks_like_dns(payload).map(|message| (message, DnsProtocol::Dns)),
+    };
+
+    if let Some((mut message, protocol)) = dns {
+        message.protocol = protocol;
+        info.packet_type = PacketType::DNS;
+        info.dns_queries = Some(message.query_names());
+        info.dns = vec![message];
     }
 
     Ok(())
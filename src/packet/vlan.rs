@@ -0,0 +1,52 @@
+use std::fmt;
+
+/// A single 802.1Q / 802.1ad VLAN tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VlanTag {
+    /// Tag protocol identifier (0x8100 for 802.1Q, 0x88A8 for 802.1ad service tags)
+    pub tpid: u16,
+    /// Priority code point (802.1p class of service, 0-7)
+    pub pcp: u8,
+    /// Drop eligible indicator
+    pub dei: bool,
+    /// VLAN identifier (0-4095)
+    pub id: u16,
+}
+
+impl fmt::Display for VlanTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id)?;
+        if self.pcp != 0 {
+            write!(f, " p{}", self.pcp)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns true if the EtherType announces a VLAN tag.
+/// 0x9100 is the pre-standard QinQ outer tag still used by some switches.
+pub fn is_vlan_tpid(ethertype: u16) -> bool {
+    matches!(ethertype, 0x8100 | 0x88A8 | 0x9100)
+}
+
+/// Parses one VLAN tag that follows an EtherType of `tpid`.
+///
+/// # Returns
+/// * The tag, the encapsulated EtherType and the remaining payload
+pub fn parse_vlan_tag(tpid: u16, data: &[u8]) -> Result<(VlanTag, u16, &[u8]), &'static str> {
+    if data.len() < 4 {
+        return Err("Packet too short for VLAN tag");
+    }
+
+    let tci = u16::from_be_bytes([data[0], data[1]]);
+    let inner_ethertype = u16::from_be_bytes([data[2], data[3]]);
+
+    let tag = VlanTag {
+        tpid,
+        pcp: (tci >> 13) as u8,
+        dei: tci & 0x1000 != 0,
+        id: tci & 0x0FFF,
+    };
+
+    Ok((tag, inner_ethertype, &data[4..]))
+}
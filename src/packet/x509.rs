@@ -0,0 +1,335 @@
+use sha2::{Digest, Sha256};
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use super::civil_from_days;
+
+const TAG_BOOLEAN: u8 = 0x01;
+const TAG_INTEGER: u8 = 0x02;
+const TAG_BIT_STRING: u8 = 0x03;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_OID: u8 = 0x06;
+const TAG_UTC_TIME: u8 = 0x17;
+const TAG_GENERALIZED_TIME: u8 = 0x18;
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_SET: u8 = 0x31;
+
+/// Fields of an X.509 certificate used for inventory and expiry monitoring.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Certificate {
+    pub der: Vec<u8>,
+    /// Serial number as hex
+    pub serial: String,
+    pub subject: String,
+    pub issuer: String,
+    /// Validity period in seconds since the Unix epoch
+    pub not_before: i64,
+    pub not_after: i64,
+    pub subject_alt_names: Vec<String>,
+    /// Public key algorithm and size, e.g. `RSA 2048` or `EC P-256`
+    pub key_type: String,
+    /// Subject and issuer are the same name
+    pub self_signed: bool,
+    /// SHA-256 of the DER encoding as hex
+    pub fingerprint: String,
+}
+
+impl Certificate {
+    /// The subject common name, falling back to the whole subject.
+    pub fn common_name(&self) -> &str {
+        self.subject
+            .split(", ")
+            .find_map(|part| part.strip_prefix("CN="))
+            .unwrap_or(&self.subject)
+    }
+}
+
+/// Formats seconds since the Unix epoch as a UTC date and time, e.g. `2025-03-01 00:00:00`.
+pub fn format_unix_time(secs: i64) -> String {
+    let (year, month, day) = civil_from_days(secs.div_euclid(86_400));
+    let secs_of_day = secs.rem_euclid(86_400);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        (secs_of_day / 60) % 60,
+        secs_of_day % 60
+    )
+}
+
+/// Parses a DER-encoded X.509 certificate.
+pub fn parse_certificate(der: &[u8]) -> Result<Certificate, &'static str> {
+    let (_, certificate) = Der::new(der).expect(TAG_SEQUENCE).ok_or("Certificate is not a DER sequence")?;
+    let (_, tbs) = Der::new(certificate).expect(TAG_SEQUENCE).ok_or("TBSCertificate missing")?;
+    let mut tbs = Der::new(tbs);
+
+    // Explicitly tagged version, absent for v1 certificates
+    if tbs.peek_tag() == Some(0xa0) {
+        tbs.read().ok_or("Certificate version truncated")?;
+    }
+    let (_, serial) = tbs.expect(TAG_INTEGER).ok_or("Certificate serial number missing")?;
+    tbs.expect(TAG_SEQUENCE).ok_or("Certificate signature algorithm missing")?;
+    let (issuer_raw, issuer) = tbs.expect(TAG_SEQUENCE).ok_or("Certificate issuer missing")?;
+    let (_, validity) = tbs.expect(TAG_SEQUENCE).ok_or("Certificate validity missing")?;
+    let (subject_raw, subject) = tbs.expect(TAG_SEQUENCE).ok_or("Certificate subject missing")?;
+    let (_, spki) = tbs.expect(TAG_SEQUENCE).ok_or("Certificate public key missing")?;
+
+    let mut validity = Der::new(validity);
+    let not_before = validity.read().and_then(|(tag, value, _)| parse_time(tag, value));
+    let not_after = validity.read().and_then(|(tag, value, _)| parse_time(tag, value));
+    let (Some(not_before), Some(not_after)) = (not_before, not_after) else {
+        return Err("Certificate validity malformed");
+    };
+
+    let mut subject_alt_names = Vec::new();
+    while let Some((tag, value, _)) = tbs.read() {
+        // Extensions are explicitly tagged [3]
+        if tag == 0xa3 {
+            subject_alt_names = Der::new(value)
+                .expect(TAG_SEQUENCE)
+                .map(|(_, extensions)| parse_subject_alt_names(extensions))
+                .unwrap_or_default();
+        }
+    }
+
+    Ok(Certificate {
+        der: der.to_vec(),
+        serial: serial.iter().map(|b| format!("{:02x}", b)).collect(),
+        subject: format_name(subject),
+        issuer: format_name(issuer),
+        not_before,
+        not_after,
+        subject_alt_names,
+        key_type: key_type(spki),
+        self_signed: subject_raw == issuer_raw,
+        fingerprint: Sha256::digest(der).iter().map(|b| format!("{:02x}", b)).collect(),
+    })
+}
+
+/// Formats a Name as `C=US, O=Example, CN=example.com`, in encoded order.
+fn format_name(name: &[u8]) -> String {
+    let mut parts = Vec::new();
+    let mut rdns = Der::new(name);
+    while let Some((TAG_SET, rdn, _)) = rdns.read() {
+        let mut attributes = Der::new(rdn);
+        while let Some((TAG_SEQUENCE, attribute, _)) = attributes.read() {
+            let mut attribute = Der::new(attribute);
+            let (Some((_, oid)), Some((tag, value, _))) = (attribute.expect(TAG_OID), attribute.read()) else {
+                continue;
+            };
+            let oid = format_oid(oid);
+            let key = match oid.as_str() {
+                "2.5.4.3" => "CN",
+                "2.5.4.5" => "serialNumber",
+                "2.5.4.6" => "C",
+                "2.5.4.7" => "L",
+                "2.5.4.8" => "ST",
+                "2.5.4.10" => "O",
+                "2.5.4.11" => "OU",
+                "0.9.2342.19200300.100.1.25" => "DC",
+                "1.2.840.113549.1.9.1" => "emailAddress",
+                other => other,
+            };
+            parts.push(format!("{}={}", key, decode_string(tag, value)));
+        }
+    }
+    parts.join(", ")
+}
+
+fn decode_string(tag: u8, value: &[u8]) -> String {
+    match tag {
+        // BMPString is UTF-16BE
+        0x1e => {
+            let units: Vec<u16> = value.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])).collect();
+            String::from_utf16_lossy(&units)
+        }
+        _ => String::from_utf8_lossy(value).into_owned(),
+    }
+}
+
+/// DNS names, IP addresses, e-mail addresses and URIs from the subjectAltName extension.
+fn parse_subject_alt_names(extensions: &[u8]) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut extensions = Der::new(extensions);
+    while let Some((TAG_SEQUENCE, extension, _)) = extensions.read() {
+        let mut extension = Der::new(extension);
+        let Some((_, oid)) = extension.expect(TAG_OID) else {
+            continue;
+        };
+        if oid != [0x55, 0x1d, 0x11] {
+            continue;
+        }
+        if extension.peek_tag() == Some(TAG_BOOLEAN) {
+            extension.read();
+        }
+        let Some((_, value)) = extension.expect(TAG_OCTET_STRING) else {
+            continue;
+        };
+        let Some((_, general_names)) = Der::new(value).expect(TAG_SEQUENCE) else {
+            continue;
+        };
+
+        let mut general_names = Der::new(general_names);
+        while let Some((tag, name, _)) = general_names.read() {
+            match tag {
+                0x81 => names.push(format!("email:{}", String::from_utf8_lossy(name))),
+                0x82 => names.push(String::from_utf8_lossy(name).into_owned()),
+                0x86 => names.push(format!("URI:{}", String::from_utf8_lossy(name))),
+                0x87 if name.len() == 4 => names.push(Ipv4Addr::new(name[0], name[1], name[2], name[3]).to_string()),
+                0x87 if name.len() == 16 => {
+                    let mut octets = [0u8; 16];
+                    octets.copy_from_slice(name);
+                    names.push(Ipv6Addr::from(octets).to_string());
+                }
+                _ => {}
+            }
+        }
+    }
+    names
+}
+
+fn key_type(spki: &[u8]) -> String {
+    let mut spki = Der::new(spki);
+    let Some((_, algorithm)) = spki.expect(TAG_SEQUENCE) else {
+        return "unknown".into();
+    };
+    let mut algorithm = Der::new(algorithm);
+    let oid = algorithm.expect(TAG_OID).map(|(_, oid)| format_oid(oid)).unwrap_or_default();
+    let parameters = algorithm.read();
+
+    match oid.as_str() {
+        "1.2.840.113549.1.1.1" | "1.2.840.113549.1.1.10" => {
+            // BIT STRING: unused-bits byte, then RSAPublicKey { modulus, publicExponent }
+            let modulus_bits = spki
+                .expect(TAG_BIT_STRING)
+                .and_then(|(_, bits)| bits.get(1..))
+                .and_then(|key| Der::new(key).expect(TAG_SEQUENCE))
+                .and_then(|(_, key)| Der::new(key).expect(TAG_INTEGER))
+                .map(|(_, modulus)| integer_bits(modulus));
+            match modulus_bits {
+                Some(bits) => format!("RSA {}", bits),
+                None => "RSA".into(),
+            }
+        }
+        "1.2.840.10045.2.1" => {
+            let curve = match parameters {
+                Some((TAG_OID, curve, _)) => match format_oid(curve).as_str() {
+                    "1.2.840.10045.3.1.7" => "P-256".into(),
+                    "1.3.132.0.34" => "P-384".into(),
+                    "1.3.132.0.35" => "P-521".into(),
+                    other => other.to_string(),
+                },
+                _ => "explicit curve".into(),
+            };
+            format!("EC {}", curve)
+        }
+        "1.3.101.112" => "Ed25519".into(),
+        "1.3.101.113" => "Ed448".into(),
+        "1.2.840.10040.4.1" => "DSA".into(),
+        other => other.to_string(),
+    }
+}
+
+/// Bit length of a positive DER INTEGER.
+fn integer_bits(value: &[u8]) -> usize {
+    let value = match value.iter().position(|&b| b != 0) {
+        Some(start) => &value[start..],
+        None => return 0,
+    };
+    value.len() * 8 - value[0].leading_zeros() as usize
+}
+
+fn format_oid(oid: &[u8]) -> String {
+    let mut arcs = Vec::new();
+    let mut value: u64 = 0;
+    for &byte in oid {
+        value = (value << 7) | (byte & 0x7f) as u64;
+        if byte & 0x80 == 0 {
+            if arcs.is_empty() {
+                let first = (value / 40).min(2);
+                arcs.push(first);
+                arcs.push(value - first * 40);
+            } else {
+                arcs.push(value);
+            }
+            value = 0;
+        }
+    }
+    arcs.iter().map(|arc| arc.to_string()).collect::<Vec<_>>().join(".")
+}
+
+/// Parses UTCTime (`YYMMDDHHMMSSZ`) or GeneralizedTime (`YYYYMMDDHHMMSSZ`) into Unix seconds.
+fn parse_time(tag: u8, value: &[u8]) -> Option<i64> {
+    let text = std::str::from_utf8(value).ok()?.strip_suffix('Z')?;
+    let (year, rest) = match tag {
+        TAG_UTC_TIME => {
+            let year: i64 = text.get(..2)?.parse().ok()?;
+            // RFC 5280: two-digit years from 50 are 19xx
+            (if year >= 50 { 1900 + year } else { 2000 + year }, text.get(2..)?)
+        }
+        TAG_GENERALIZED_TIME => (text.get(..4)?.parse().ok()?, text.get(4..)?),
+        _ => return None,
+    };
+    let field = |at: usize| -> Option<i64> { rest.get(at..at + 2)?.parse().ok() };
+    let (month, day, hour, minute) = (field(0)?, field(2)?, field(4)?, field(6)?);
+    let second = field(8).unwrap_or(0);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    Some(days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second)
+}
+
+/// Converts a civil date into days since 1970-01-01; the inverse of `civil_from_days`.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Cursor over DER tag-length-value elements.
+struct Der<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Der<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Der { data }
+    }
+
+    fn peek_tag(&self) -> Option<u8> {
+        self.data.first().copied()
+    }
+
+    /// Reads the next element as (tag, contents, whole encoding).
+    fn read(&mut self) -> Option<(u8, &'a [u8], &'a [u8])> {
+        let tag = *self.data.first()?;
+        let first = *self.data.get(1)?;
+        let (len, header) = if first & 0x80 == 0 {
+            (first as usize, 2)
+        } else {
+            let count = (first & 0x7f) as usize;
+            if count == 0 || count > 4 {
+                return None;
+            }
+            let bytes = self.data.get(2..2 + count)?;
+            (bytes.iter().fold(0usize, |len, &b| (len << 8) | b as usize), 2 + count)
+        };
+        let end = header.checked_add(len)?;
+        let whole = self.data.get(..end)?;
+        self.data = &self.data[end..];
+        Some((tag, &whole[header..], whole))
+    }
+
+    /// Reads the next element if it has `tag`, returning (whole encoding, contents).
+    fn expect(&mut self, tag: u8) -> Option<(&'a [u8], &'a [u8])> {
+        if self.peek_tag()? != tag {
+            return None;
+        }
+        self.read().map(|(_, contents, whole)| (whole, contents))
+    }
+}
@@ -0,0 +1,308 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{backend::CrosstermBackend, widgets::{ListState, TableState}, Terminal};
+
+use crate::capture::DEVICE_PRECISION;
+use crate::core::capture_loop::{get_available_devices, reinitialize_capture};
+use crate::core::diagnostics::set_diagnostics_sink;
+use crate::core::runner::spawn_capture_thread;
+use crate::packet::{PacketInfo, PacketType};
+use crate::tui::ui;
+
+/// Maximum number of packets kept in the scrollback of the packet table
+const MAX_LOG_ENTRIES: usize = 5000;
+
+/// How often the UI redraws while waiting for key presses
+const TICK_RATE: Duration = Duration::from_millis(100);
+
+/// Which screen the TUI is currently showing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Screen {
+    InterfacePicker,
+    Packets,
+}
+
+/// The full-screen terminal front-end for Sniffy
+pub struct TuiApp {
+    pub screen: Screen,
+    pub interfaces: Vec<String>,
+    pub interface_state: ListState,
+    pub selected_interface: Option<String>,
+    pub bpf_filter: String,
+    pub export: Option<String>,
+    pub log: VecDeque<(usize, PacketInfo)>,
+    pub total_packets: usize,
+    pub table_state: TableState,
+    pub follow: bool,
+    pub packet_counts: Arc<Mutex<HashMap<PacketType, usize>>>,
+    pub status: String,
+    /// Messages from the capture thread (e.g. undecodable frames) since the last clear
+    pub diagnostics: usize,
+    pub last_diagnostic: Option<String>,
+    running: Arc<AtomicBool>,
+    packet_rx: Receiver<PacketInfo>,
+    packet_tx: Sender<PacketInfo>,
+    /// Receives what the capture thread would otherwise print over the TUI on stderr
+    diagnostics_rx: Receiver<String>,
+    diagnostics_tx: Sender<String>,
+    capture_thread_handle: Option<JoinHandle<()>>,
+    should_quit: bool,
+}
+
+impl TuiApp {
+    pub fn new(interface: Option<String>, bpf_filter: String, export: Option<String>) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let (diagnostics_tx, diagnostics_rx) = mpsc::channel();
+
+        let (interfaces, status) = match get_available_devices() {
+            Ok(list) => (list.into_iter().map(|d| d.name).collect::<Vec<_>>(), String::new()),
+            Err(e) => (vec![], e),
+        };
+
+        let mut interface_state = ListState::default();
+        let preselected = interface
+            .as_ref()
+            .and_then(|name| interfaces.iter().position(|i| i == name))
+            .or(if interfaces.is_empty() { None } else { Some(0) });
+        interface_state.select(preselected);
+
+        TuiApp {
+            screen: Screen::InterfacePicker,
+            interfaces,
+            interface_state,
+            selected_interface: None,
+            bpf_filter,
+            export,
+            log: VecDeque::new(),
+            total_packets: 0,
+            table_state: TableState::default(),
+            follow: true,
+            packet_counts: Arc::new(Mutex::new(HashMap::new())),
+            status,
+            diagnostics: 0,
+            last_diagnostic: None,
+            running: Arc::new(AtomicBool::new(false)),
+            packet_rx: rx,
+            packet_tx: tx,
+            diagnostics_rx,
+            diagnostics_tx,
+            capture_thread_handle: None,
+            should_quit: false,
+        }
+    }
+
+    pub fn is_capturing(&self) -> bool {
+        self.capture_thread_handle.is_some()
+    }
+
+    fn start_capture(&mut self) {
+        if self.is_capturing() {
+            return;
+        }
+
+        let Some(device_name) = self.selected_interface.clone() else {
+            self.status = "No interface selected".into();
+            return;
+        };
+
+        self.running.store(true, Ordering::SeqCst);
+
+        let open_name = device_name.clone();
+        let handle = spawn_capture_thread(
+            move || reinitialize_capture(&open_name),
+            DEVICE_PRECISION,
+            self.bpf_filter.clone(),
+            self.export.clone(),
+            self.running.clone(),
+            self.packet_counts.clone(),
+            Some(self.packet_tx.clone()),
+        );
+
+        self.capture_thread_handle = Some(handle);
+        self.status = format!("Capturing on {}", device_name);
+    } // start_capture
+
+    fn stop_capture(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+
+        if let Some(handle) = self.capture_thread_handle.take()
+            && handle.join().is_err()
+        {
+            self.status = "Capture thread panicked".into();
+            return;
+        }
+
+        self.status = "Capture stopped".into();
+    } // stop_capture
+
+    fn clear(&mut self) {
+        self.log.clear();
+        self.total_packets = 0;
+        self.table_state.select(None);
+        self.follow = true;
+        self.diagnostics = 0;
+        self.last_diagnostic = None;
+        if let Ok(mut counts) = self.packet_counts.lock() {
+            counts.clear();
+        }
+    }
+
+    /// Moves everything the capture thread produced since the last tick into the log.
+    fn drain_packets(&mut self) {
+        while let Ok(packet) = self.packet_rx.try_recv() {
+            self.total_packets += 1;
+            self.log.push_back((self.total_packets, packet));
+            if self.log.len() > MAX_LOG_ENTRIES {
+                self.log.pop_front();
+            }
+        }
+
+        while let Ok(message) = self.diagnostics_rx.try_recv() {
+            self.diagnostics += 1;
+            self.last_diagnostic = Some(message);
+        }
+
+        // The capture thread exits on its own if the device fails to open
+        if self
+            .capture_thread_handle
+            .as_ref()
+            .is_some_and(|handle| handle.is_finished())
+        {
+            self.stop_capture();
+        }
+
+        if self.follow && !self.log.is_empty() {
+            self.table_state.select(Some(self.log.len() - 1));
+        }
+    }
+
+    fn scroll(&mut self, delta: isize) {
+        if self.log.is_empty() {
+            return;
+        }
+
+        let last = self.log.len() - 1;
+        let current = self.table_state.selected().unwrap_or(last) as isize;
+        let next = (current + delta).clamp(0, last as isize) as usize;
+
+        self.table_state.select(Some(next));
+        self.follow = next == last;
+    }
+
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        if code == KeyCode::Char('q')
+            || (code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL))
+        {
+            self.should_quit = true;
+            return;
+        }
+
+        match self.screen {
+            Screen::InterfacePicker => self.handle_picker_key(code),
+            Screen::Packets => self.handle_packets_key(code),
+        }
+    }
+
+    fn handle_picker_key(&mut self, code: KeyCode) {
+        let count = self.interfaces.len();
+        if count == 0 {
+            return;
+        }
+
+        let current = self.interface_state.selected().unwrap_or(0);
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.interface_state.select(Some((current + count - 1) % count));
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.interface_state.select(Some((current + 1) % count));
+            }
+            KeyCode::Enter => {
+                self.selected_interface = Some(self.interfaces[current].clone());
+                self.screen = Screen::Packets;
+                self.start_capture();
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_packets_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('s') => {
+                if self.is_capturing() {
+                    self.stop_capture();
+                } else {
+                    self.start_capture();
+                }
+            }
+            KeyCode::Char('i') if !self.is_capturing() => {
+                self.screen = Screen::InterfacePicker;
+            }
+            KeyCode::Char('c') => self.clear(),
+            KeyCode::Char('f') | KeyCode::End => {
+                self.follow = true;
+                self.scroll(isize::MAX / 2);
+            }
+            KeyCode::Home => self.scroll(isize::MIN / 2),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll(1),
+            KeyCode::PageUp => self.scroll(-20),
+            KeyCode::PageDown => self.scroll(20),
+            _ => {}
+        }
+    }
+
+    /// Runs the draw / input loop until the user quits.
+    fn run_loop(&mut self, terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> io::Result<()> {
+        while !self.should_quit {
+            self.drain_packets();
+            terminal.draw(|frame| ui::draw(frame, self))?;
+
+            if event::poll(TICK_RATE)?
+                && let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                self.handle_key(key.code, key.modifiers);
+            }
+        }
+
+        self.stop_capture();
+        Ok(())
+    }
+} // impl TuiApp
+
+/// Sets up the terminal, runs the TUI and restores the terminal afterwards,
+/// even if the UI loop returned an error.
+pub fn run_tui(interface: Option<String>, bpf_filter: String, export: Option<String>) -> io::Result<()> {
+    let mut app = TuiApp::new(interface, bpf_filter, export);
+    set_diagnostics_sink(Some(app.diagnostics_tx.clone()));
+
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    let result = app.run_loop(&mut terminal);
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    set_diagnostics_sink(None);
+
+    result
+}
@@ -0,0 +1,179 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph, Row, Table},
+    Frame,
+};
+
+use crate::packet::{format_time_of_day, PacketType};
+use crate::tui::app::{Screen, TuiApp};
+
+/// Draws the whole TUI for the current frame.
+pub fn draw(frame: &mut Frame, app: &mut TuiApp) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Min(5),
+            Constraint::Length(1),
+        ])
+        .split(frame.size());
+
+    draw_status(frame, app, chunks[0]);
+    draw_counters(frame, app, chunks[1]);
+
+    match app.screen {
+        Screen::InterfacePicker => draw_interface_picker(frame, app, chunks[2]),
+        Screen::Packets => draw_packet_table(frame, app, chunks[2]),
+    }
+
+    draw_help(frame, app, chunks[3]);
+}
+
+fn draw_status(frame: &mut Frame, app: &TuiApp, area: Rect) {
+    let state = if app.is_capturing() {
+        Span::styled("CAPTURING", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD))
+    } else {
+        Span::styled("IDLE", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+    };
+
+    let filter = if app.bpf_filter.trim().is_empty() { "-" } else { app.bpf_filter.as_str() };
+
+    let mut spans = vec![
+        state,
+        Span::raw(format!(
+            "  iface: {}  filter: {}  packets: {}  ",
+            app.selected_interface.as_deref().unwrap_or("-"),
+            filter,
+            app.total_packets,
+        )),
+    ];
+    if let Some(last) = &app.last_diagnostic {
+        spans.push(Span::styled(
+            format!("messages: {} (last: {})  ", app.diagnostics, last),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    spans.push(Span::styled(app.status.clone(), Style::default().fg(Color::Gray)));
+    let line = Line::from(spans);
+
+    let block = Block::default().borders(Borders::ALL).title(" Sniffy ");
+    frame.render_widget(Paragraph::new(line).block(block), area);
+}
+
+fn draw_counters(frame: &mut Frame, app: &TuiApp, area: Rect) {
+    let mut entries: Vec<(PacketType, usize)> = app
+        .packet_counts
+        .lock()
+        .map(|counts| counts.iter().map(|(k, v)| (k.clone(), *v)).collect())
+        .unwrap_or_default();
+    entries.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+    let mut spans = Vec::new();
+    for (ptype, count) in entries {
+        spans.push(Span::styled(
+            format!("{}: ", ptype),
+            Style::default().fg(protocol_color(&ptype)).add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::raw(format!("{}   ", count)));
+    }
+
+    let block = Block::default().borders(Borders::ALL).title(" Protocols ");
+    frame.render_widget(Paragraph::new(Line::from(spans)).block(block), area);
+}
+
+fn draw_interface_picker(frame: &mut Frame, app: &mut TuiApp, area: Rect) {
+    let items: Vec<ListItem> = app
+        .interfaces
+        .iter()
+        .map(|name| ListItem::new(name.as_str()))
+        .collect();
+
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(" Select interface "))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("> ");
+
+    frame.render_stateful_widget(list, area, &mut app.interface_state);
+}
+
+fn draw_packet_table(frame: &mut Frame, app: &mut TuiApp, area: Rect) {
+    let header = Row::new(vec!["No.", "Time (UTC)", "Protocol", "Source", "Destination", "Len", "Info"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+
+    let rows: Vec<Row> = app
+        .log
+        .iter()
+        .map(|(number, packet)| {
+            Row::new(vec![
+                number.to_string(),
+                format_time_of_day(packet.timestamp),
+                packet.packet_type.to_string(),
+                endpoint(packet.src_ip.as_deref(), packet.src_port),
+                endpoint(packet.dst_ip.as_deref(), packet.dst_port),
+                packet.orig_len.to_string(),
+                packet.summary(),
+            ])
+            .style(Style::default().fg(protocol_color(&packet.packet_type)))
+        })
+        .collect();
+
+    let title = if app.follow { " Packets (following) " } else { " Packets " };
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(7),
+            Constraint::Length(15),
+            Constraint::Length(10),
+            Constraint::Percentage(25),
+            Constraint::Percentage(25),
+            Constraint::Length(6),
+            Constraint::Min(10),
+        ],
+    )
+    .header(header)
+    .block(Block::default().borders(Borders::ALL).title(title))
+    .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+    frame.render_stateful_widget(table, area, &mut app.table_state);
+}
+
+fn draw_help(frame: &mut Frame, app: &TuiApp, area: Rect) {
+    let help = match app.screen {
+        Screen::InterfacePicker => "↑/↓ select  Enter start capture  q quit",
+        Screen::Packets => "s start/stop  ↑/↓ PgUp/PgDn scroll  f follow  c clear  i interfaces  q quit",
+    };
+
+    frame.render_widget(
+        Paragraph::new(help).style(Style::default().fg(Color::DarkGray)),
+        area,
+    );
+}
+
+fn endpoint(ip: Option<&str>, port: Option<u16>) -> String {
+    match (ip, port) {
+        (Some(ip), Some(port)) => format!("{}:{}", ip, port),
+        (Some(ip), None) => ip.to_string(),
+        (None, _) => "-".into(),
+    }
+}
+
+/// Terminal colors matching the GUI protocol palette
+fn protocol_color(packet_type: &PacketType) -> Color {
+    match packet_type {
+        PacketType::DNS => Color::Green,
+        PacketType::TCP => Color::LightBlue,
+        PacketType::HTTP | PacketType::HTTP2 => Color::LightGreen,
+        PacketType::TLS | PacketType::QUIC => Color::LightMagenta,
+        PacketType::UDP => Color::Yellow,
+        PacketType::IPv4 | PacketType::IPv6 => Color::Gray,
+        PacketType::ICMP | PacketType::ICMPv6 => Color::Magenta,
+        PacketType::ARP => Color::LightRed,
+        PacketType::DHCP | PacketType::DHCPv6 => Color::Cyan,
+        PacketType::Ethernet => Color::Gray,
+        PacketType::Other(_) => Color::DarkGray,
+    }
+}